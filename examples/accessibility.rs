@@ -0,0 +1,112 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+#![feature(proc_macro)]
+
+extern crate gtk;
+#[macro_use]
+extern crate relm;
+extern crate relm_attributes;
+#[macro_use]
+extern crate relm_derive;
+
+use gtk::{
+    ButtonExt,
+    ImageExt,
+    Inhibit,
+    LabelExt,
+    OrientableExt,
+    WidgetExt,
+};
+use gtk::Orientation::{Horizontal, Vertical};
+use relm::Widget;
+use relm_attributes::widget;
+
+use self::Msg::*;
+
+pub struct Model {
+    counter: i32,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Decrement,
+    Increment,
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            counter: 0,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Decrement => self.model.counter -= 1,
+            Increment => self.model.counter += 1,
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="title"]
+                gtk::Label {
+                    text: "Counter",
+                    accessible role: "heading",
+                },
+                gtk::Box {
+                    orientation: Horizontal,
+                    // Without an accessible name, this icon-only button would trigger a warning.
+                    gtk::Button {
+                        clicked => Decrement,
+                        accessible name: "Decrement",
+                        gtk::Image {
+                            property_icon_name: Some("list-remove"),
+                        },
+                    },
+                    gtk::Label {
+                        text: &self.model.counter.to_string(),
+                        accessible labelled_by: title,
+                        accessible description: &format!("The counter is at {}", self.model.counter),
+                    },
+                    gtk::Button {
+                        clicked => Increment,
+                        accessible name: "Increment",
+                        gtk::Image {
+                            property_icon_name: Some("list-add"),
+                        },
+                    },
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    Win::run(()).unwrap();
+}
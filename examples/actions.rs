@@ -0,0 +1,132 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+#![feature(proc_macro)]
+
+extern crate gio;
+extern crate gtk;
+#[macro_use]
+extern crate relm;
+extern crate relm_attributes;
+#[macro_use]
+extern crate relm_derive;
+
+use gio::MenuExt;
+use gtk::{
+    ButtonExt,
+    Inhibit,
+    LabelExt,
+    MenuButtonExt,
+    OrientableExt,
+    ToVariant,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::{ActionStateExt, Actions, Relm, Widget};
+use relm_attributes::widget;
+
+use self::Msg::*;
+
+pub struct Model {
+    actions: Option<Actions<Win>>,
+    modified: bool,
+    relm: Relm<Win>,
+    text: String,
+    uppercase: bool,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Edit,
+    Open(String),
+    Quit,
+    Save,
+    Uppercase(bool),
+}
+
+#[widget]
+impl Widget for Win {
+    fn init_view(&mut self) {
+        let actions = Actions::new(&self.window, &self.model.relm);
+        actions.add("edit", || Edit);
+        actions.add_with_parameter("open", Open);
+        actions.add("save", || Save);
+        actions.add_stateful("uppercase", false, Uppercase);
+        self.model.actions = Some(actions);
+
+        let menu = gio::Menu::new();
+        menu.append("Edit", "win.edit");
+        menu.append("Open README", "win.open::README.md");
+        menu.append("Save", "win.save");
+        menu.append("Uppercase", "win.uppercase");
+        self.menu_button.set_menu_model(Some(&menu));
+    }
+
+    fn model(relm: &Relm<Self>, _: ()) -> Model {
+        Model {
+            actions: None,
+            modified: false,
+            relm: relm.clone(),
+            text: String::new(),
+            uppercase: false,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Edit => {
+                self.model.text = "Edited".to_string();
+                // The save action is enabled by the action_enabled property of the window.
+                self.model.modified = true;
+            },
+            Open(file) => self.model.text = format!("Opened {}", file),
+            Quit => gtk::main_quit(),
+            Save => {
+                self.model.text = "Saved".to_string();
+                self.model.modified = false;
+            },
+            Uppercase(uppercase) => self.model.uppercase = uppercase,
+        }
+    }
+
+    view! {
+        #[name="window"]
+        gtk::ApplicationWindow {
+            action_enabled: ("save", self.model.modified),
+            action_state: ("uppercase", self.model.uppercase.to_variant()),
+            gtk::Box {
+                orientation: Vertical,
+                #[name="menu_button"]
+                gtk::MenuButton {
+                    label: "Menu",
+                },
+                gtk::Label {
+                    text: &if self.model.uppercase { self.model.text.to_uppercase() } else { self.model.text.clone() },
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    Win::run(()).unwrap();
+}
@@ -0,0 +1,134 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+extern crate gio;
+extern crate gtk;
+#[macro_use]
+extern crate relm;
+#[macro_use]
+extern crate relm_derive;
+
+use gio::ApplicationExt;
+use gtk::{
+    Button,
+    ButtonExt,
+    ContainerExt,
+    Label,
+    LabelExt,
+    WidgetExt,
+    Window,
+    WindowType,
+};
+use gtk::Orientation::Vertical;
+use relm::{AppEvent, AppWidget, Relm, Update, Widget};
+
+use self::Msg::*;
+
+struct Model {
+    application: gtk::Application,
+    args: Vec<String>,
+}
+
+#[derive(Msg)]
+enum Msg {
+    Activated,
+    Quit,
+    Shutdown,
+}
+
+struct Win {
+    label: Label,
+    model: Model,
+    window: Window,
+}
+
+impl Update for Win {
+    type Model = Model;
+    type ModelParam = (gtk::Application, Vec<String>);
+    type Msg = Msg;
+
+    fn model(_: &Relm<Self>, (application, args): (gtk::Application, Vec<String>)) -> Model {
+        Model {
+            application,
+            args,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Activated => self.label.set_text(&format!("Activated with arguments {:?}", self.model.args)),
+            Quit => self.model.application.quit(),
+            Shutdown => println!("Shutting down"),
+        }
+    }
+}
+
+impl Widget for Win {
+    type Root = Window;
+
+    fn root(&self) -> Self::Root {
+        self.window.clone()
+    }
+
+    fn view(relm: &Relm<Self>, model: Model) -> Self {
+        let vbox = gtk::Box::new(Vertical, 0);
+
+        let label = Label::new(None);
+        vbox.add(&label);
+
+        let quit_button = Button::new_with_label("Quit");
+        vbox.add(&quit_button);
+
+        let window = Window::new(WindowType::Toplevel);
+        window.add(&vbox);
+        window.show_all();
+
+        connect!(relm, quit_button, connect_clicked(_), Quit);
+
+        Win {
+            label,
+            model,
+            window,
+        }
+    }
+}
+
+impl AppWidget for Win {
+    fn application_id() -> Option<&'static str> {
+        Some("org.relm.Application")
+    }
+
+    fn app_msg(event: AppEvent) -> Option<Msg> {
+        match event {
+            AppEvent::Activate => Some(Activated),
+            AppEvent::Shutdown => Some(Shutdown),
+            AppEvent::Startup => None,
+        }
+    }
+
+    fn model_param(application: &gtk::Application, args: &[String]) -> (gtk::Application, Vec<String>) {
+        (application.clone(), args[1..].to_vec())
+    }
+}
+
+fn main() {
+    ::std::process::exit(Win::run_app().unwrap());
+}
@@ -0,0 +1,120 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+#![feature(proc_macro)]
+
+extern crate gtk;
+#[macro_use]
+extern crate relm;
+extern crate relm_attributes;
+#[macro_use]
+extern crate relm_derive;
+
+use gtk::{
+    ButtonExt,
+    EntryExt,
+    Inhibit,
+    LabelExt,
+    OrientableExt,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::{ClipboardContent, Relm, SignalGuard, Widget};
+use relm_attributes::widget;
+
+use self::Msg::*;
+
+pub struct Model {
+    changes: u32,
+    clipboard: gtk::Clipboard,
+    owner_change: Option<SignalGuard>,
+    relm: Relm<Win>,
+    text: String,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Changed,
+    Copy,
+    Paste,
+    Pasted(Option<String>),
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model(relm: &Relm<Self>, _: ()) -> Model {
+        Model {
+            changes: 0,
+            clipboard: relm::clipboard(),
+            owner_change: None,
+            relm: relm.clone(),
+            text: String::new(),
+        }
+    }
+
+    fn subscriptions(&mut self, relm: &Relm<Self>) {
+        self.model.owner_change = Some(relm::connect_clipboard_owner_change(&self.model.clipboard, relm, || Changed));
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Changed => self.model.changes += 1,
+            Copy => {
+                let text = self.entry.get_text().unwrap_or_default();
+                relm::set_clipboard(&self.model.clipboard, ClipboardContent::Text(text));
+            },
+            Paste => relm::request_clipboard_text(&self.model.clipboard, &self.model.relm, Pasted),
+            Pasted(text) => self.model.text = text.unwrap_or_default(),
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="entry"]
+                gtk::Entry {
+                },
+                gtk::Button {
+                    clicked => Copy,
+                    label: "Copy",
+                },
+                gtk::Button {
+                    clicked => Paste,
+                    label: "Paste",
+                },
+                gtk::Label {
+                    text: &self.model.text,
+                },
+                gtk::Label {
+                    text: &format!("The clipboard changed {} times", self.model.changes),
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    Win::run(()).unwrap();
+}
@@ -0,0 +1,229 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+extern crate gtk;
+#[macro_use]
+extern crate relm;
+#[macro_use]
+extern crate relm_derive;
+
+use gtk::{
+    Button,
+    ButtonExt,
+    ContainerExt,
+    Inhibit,
+    Label,
+    LabelExt,
+    WidgetExt,
+    Window,
+    WindowType,
+};
+use gtk::Orientation::{Horizontal, Vertical};
+use relm::{ComponentList, Relm, Update, Widget};
+
+use self::CounterMsg::*;
+use self::Msg::*;
+
+struct CounterModel {
+    counter: i32,
+    name: String,
+}
+
+#[derive(Msg)]
+enum CounterMsg {
+    Increment,
+    Rename(String),
+}
+
+struct Counter {
+    counter_label: Label,
+    model: CounterModel,
+    name_label: Label,
+    vbox: gtk::Box,
+}
+
+impl Update for Counter {
+    type Msg = CounterMsg;
+    type Model = CounterModel;
+    type ModelParam = String;
+
+    fn model(_: &Relm<Self>, name: String) -> CounterModel {
+        CounterModel {
+            counter: 0,
+            name,
+        }
+    }
+
+    fn update(&mut self, event: CounterMsg) {
+        match event {
+            Increment => {
+                self.model.counter += 1;
+                self.counter_label.set_text(&self.model.counter.to_string());
+            },
+            Rename(name) => {
+                self.model.name = name;
+                self.name_label.set_text(&self.model.name);
+            },
+        }
+    }
+}
+
+impl Widget for Counter {
+    type Root = gtk::Box;
+
+    fn root(&self) -> Self::Root {
+        self.vbox.clone()
+    }
+
+    fn view(relm: &Relm<Self>, model: CounterModel) -> Self {
+        let vbox = gtk::Box::new(Vertical, 0);
+
+        let name_label = Label::new(Some(model.name.as_str()));
+        vbox.add(&name_label);
+
+        let counter_label = Label::new("0");
+        vbox.add(&counter_label);
+
+        let plus_button = Button::new_with_label("+");
+        vbox.add(&plus_button);
+
+        vbox.show_all();
+
+        connect!(relm, plus_button, connect_clicked(_), Increment);
+
+        Counter {
+            counter_label,
+            model,
+            name_label,
+            vbox,
+        }
+    }
+}
+
+#[derive(Msg)]
+enum Msg {
+    Add,
+    Quit,
+    RemoveFirst,
+    Reverse,
+    Uppercase,
+}
+
+struct Model {
+    // The key is kept separately from the name to show that renaming keeps the counter value.
+    items: Vec<(u32, String)>,
+    next_key: u32,
+    relm: Relm<Win>,
+}
+
+struct Win {
+    counters: ComponentList<u32, Counter>,
+    model: Model,
+    window: Window,
+}
+
+impl Win {
+    fn sync(&mut self) {
+        let items = self.model.items.clone();
+        self.counters.set_items(&self.model.relm, items);
+    }
+}
+
+impl Update for Win {
+    type Model = Model;
+    type ModelParam = ();
+    type Msg = Msg;
+
+    fn model(relm: &Relm<Self>, _: ()) -> Model {
+        Model {
+            items: vec![],
+            next_key: 0,
+            relm: relm.clone(),
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Add => {
+                let key = self.model.next_key;
+                self.model.next_key += 1;
+                self.model.items.push((key, format!("counter {}", key)));
+            },
+            Quit => gtk::main_quit(),
+            RemoveFirst => {
+                if !self.model.items.is_empty() {
+                    let _ = self.model.items.remove(0);
+                }
+            },
+            Reverse => self.model.items.reverse(),
+            Uppercase => {
+                for &mut (_, ref mut name) in &mut self.model.items {
+                    *name = name.to_uppercase();
+                }
+            },
+        }
+        self.sync();
+    }
+}
+
+impl Widget for Win {
+    type Root = Window;
+
+    fn root(&self) -> Self::Root {
+        self.window.clone()
+    }
+
+    fn view(relm: &Relm<Self>, model: Model) -> Self {
+        let window = Window::new(WindowType::Toplevel);
+
+        let vbox = gtk::Box::new(Vertical, 0);
+        let hbox = gtk::Box::new(Horizontal, 0);
+        vbox.add(&hbox);
+
+        let add_button = Button::new_with_label("Add");
+        vbox.add(&add_button);
+        let remove_button = Button::new_with_label("Remove first");
+        vbox.add(&remove_button);
+        let reverse_button = Button::new_with_label("Reverse");
+        vbox.add(&reverse_button);
+        let uppercase_button = Button::new_with_label("Uppercase");
+        vbox.add(&uppercase_button);
+
+        window.add(&vbox);
+        window.show_all();
+
+        connect!(relm, add_button, connect_clicked(_), Add);
+        connect!(relm, remove_button, connect_clicked(_), RemoveFirst);
+        connect!(relm, reverse_button, connect_clicked(_), Reverse);
+        connect!(relm, uppercase_button, connect_clicked(_), Uppercase);
+        connect!(relm, window, connect_delete_event(_, _), return (Some(Quit), Inhibit(false)));
+
+        Win {
+            counters: ComponentList::with_update_msg(&hbox, Rename),
+            model,
+            window,
+        }
+    }
+}
+
+fn main() {
+    Win::run(()).unwrap();
+}
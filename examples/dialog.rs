@@ -0,0 +1,211 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+extern crate gtk;
+#[macro_use]
+extern crate relm;
+#[macro_use]
+extern crate relm_derive;
+
+use std::path::PathBuf;
+
+use gtk::{
+    Button,
+    ButtonExt,
+    ContainerExt,
+    DialogExt,
+    Inhibit,
+    Label,
+    LabelExt,
+    ResponseType,
+    WidgetExt,
+    Window,
+    WindowType,
+};
+use gtk::Orientation::Vertical;
+use relm::{DialogWidget, FileChooser, FileChooserParams, Relm, Update, Widget};
+
+use self::Msg::*;
+
+// Custom dialog.
+
+#[derive(Msg)]
+enum ConfirmMsg {
+    Response(ResponseType),
+}
+
+struct Confirm {
+    dialog: gtk::Dialog,
+}
+
+impl Update for Confirm {
+    type Model = String;
+    type ModelParam = String;
+    type Msg = ConfirmMsg;
+
+    fn model(_: &Relm<Self>, question: String) -> String {
+        question
+    }
+
+    fn update(&mut self, _event: ConfirmMsg) {
+    }
+}
+
+impl Widget for Confirm {
+    type Root = gtk::Dialog;
+
+    fn root(&self) -> Self::Root {
+        self.dialog.clone()
+    }
+
+    fn view(relm: &Relm<Self>, question: Self::Model) -> Self {
+        let dialog = gtk::Dialog::new();
+        dialog.set_modal(true);
+        dialog.get_content_area().add(&Label::new(Some(question.as_str())));
+        dialog.add_button("_No", ResponseType::No.into());
+        dialog.add_button("_Yes", ResponseType::Yes.into());
+        dialog.show_all();
+
+        connect!(relm, dialog, connect_response(_, response), ConfirmMsg::Response(ResponseType::from(response)));
+
+        Confirm {
+            dialog,
+        }
+    }
+}
+
+impl DialogWidget for Confirm {
+    type Output = bool;
+
+    fn output(msg: &ConfirmMsg) -> Option<bool> {
+        match *msg {
+            ConfirmMsg::Response(response) => Some(response == ResponseType::Yes),
+        }
+    }
+}
+
+// Main window.
+
+struct Model {
+    path: Option<PathBuf>,
+    relm: Relm<Win>,
+}
+
+#[derive(Msg)]
+enum Msg {
+    AskDelete,
+    Delete(bool),
+    Open,
+    Opened(Vec<PathBuf>),
+    Quit,
+}
+
+struct Win {
+    label: Label,
+    model: Model,
+    window: Window,
+}
+
+impl Update for Win {
+    type Model = Model;
+    type ModelParam = ();
+    type Msg = Msg;
+
+    fn model(relm: &Relm<Self>, _: ()) -> Model {
+        Model {
+            path: None,
+            relm: relm.clone(),
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            AskDelete => {
+                if let Some(ref path) = self.model.path {
+                    let question = format!("Forget {}?", path.display());
+                    self.model.relm.connect_exec_ignore_err(relm::dialog::<Confirm, _>(&self.model.relm, question),
+                        Delete);
+                }
+            },
+            Delete(true) => self.model.path = None,
+            Delete(false) => (),
+            Open => {
+                let params = FileChooserParams {
+                    action: gtk::FileChooserAction::Open,
+                    multiple: false,
+                    parent: Some(self.window.clone()),
+                    title: "Open a file".to_string(),
+                };
+                self.model.relm.connect_exec_ignore_err(relm::dialog::<FileChooser, _>(&self.model.relm, params),
+                    Opened);
+            },
+            Opened(files) => {
+                if let Some(file) = files.into_iter().next() {
+                    self.model.path = Some(file);
+                }
+            },
+            Quit => gtk::main_quit(),
+        }
+        let text = self.model.path.as_ref()
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|| "No file".to_string());
+        self.label.set_text(&text);
+    }
+}
+
+impl Widget for Win {
+    type Root = Window;
+
+    fn root(&self) -> Self::Root {
+        self.window.clone()
+    }
+
+    fn view(relm: &Relm<Self>, model: Self::Model) -> Self {
+        let vbox = gtk::Box::new(Vertical, 0);
+
+        let open_button = Button::new_with_label("Open");
+        vbox.add(&open_button);
+
+        let delete_button = Button::new_with_label("Forget");
+        vbox.add(&delete_button);
+
+        let label = Label::new(Some("No file"));
+        vbox.add(&label);
+
+        let window = Window::new(WindowType::Toplevel);
+        window.add(&vbox);
+        window.show_all();
+
+        connect!(relm, open_button, connect_clicked(_), Open);
+        connect!(relm, delete_button, connect_clicked(_), AskDelete);
+        connect!(relm, window, connect_delete_event(_, _), return (Some(Quit), Inhibit(false)));
+
+        Win {
+            label,
+            model,
+            window,
+        }
+    }
+}
+
+fn main() {
+    Win::run(()).unwrap();
+}
@@ -0,0 +1,120 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+#![feature(proc_macro)]
+
+extern crate gtk;
+#[macro_use]
+extern crate relm;
+extern crate relm_attributes;
+#[macro_use]
+extern crate relm_derive;
+
+use gtk::{
+    ButtonExt,
+    Inhibit,
+    LabelExt,
+    OrientableExt,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::{DragData, DragExt, Relm, Widget};
+use relm_attributes::widget;
+
+use self::Msg::*;
+
+pub struct Model {
+    items: Vec<&'static str>,
+    relm: Relm<Win>,
+    text: String,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Dropped(DragData),
+    Quit,
+    Reordered(usize, usize),
+}
+
+#[widget]
+impl Widget for Win {
+    fn init_view(&mut self) {
+        relm::connect_reorder(&self.items, &self.model.relm, Reordered);
+    }
+
+    fn model(relm: &Relm<Self>, _: ()) -> Model {
+        Model {
+            items: vec!["First", "Second", "Third"],
+            relm: relm.clone(),
+            text: "Drop some text or files here".to_string(),
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Dropped(DragData::Text(text)) => self.model.text = text,
+            Dropped(DragData::Uris(uris)) => self.model.text = uris.join("\n"),
+            Dropped(DragData::Custom(..)) => (),
+            Quit => gtk::main_quit(),
+            Reordered(from, to) => {
+                let item = self.model.items.remove(from);
+                self.model.items.insert(to, item);
+            },
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[drag_source="text"]
+                gtk::Label {
+                    drag_data: DragData::Text("Hello from relm".to_string()),
+                    text: "Drag me",
+                },
+                #[drop_target="text, uris"]
+                gtk::Label {
+                    text: &self.model.text,
+                    drop(_, data) => Dropped(data),
+                },
+                // The children of this box can be reordered by drag-and-drop.
+                #[name="items"]
+                gtk::Box {
+                    orientation: Vertical,
+                    gtk::Button {
+                        label: "First",
+                    },
+                    gtk::Button {
+                        label: "Second",
+                    },
+                    gtk::Button {
+                        label: "Third",
+                    },
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    Win::run(()).unwrap();
+}
@@ -0,0 +1,164 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+#![feature(proc_macro)]
+
+extern crate futures;
+extern crate futures_glib;
+extern crate gtk;
+#[macro_use]
+extern crate relm;
+extern crate relm_attributes;
+#[macro_use]
+extern crate relm_derive;
+
+use std::time::Duration;
+
+use futures::Future;
+use futures_glib::Timeout;
+use gtk::{
+    ButtonExt,
+    ComboBoxTextExt,
+    EntryExt,
+    Inhibit,
+    OrientableExt,
+    SpinButtonExt,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::{Form, FormMsg, Relm, Rule, Widget};
+use relm_attributes::widget;
+
+use self::Msg::*;
+
+// Simulate a request to a server checking whether the username is available.
+fn check_username(username: &str) -> Box<Future<Item=(), Error=String>> {
+    let taken = username == "admin";
+    Box::new(Timeout::new(Duration::from_millis(500))
+        .then(move |_| {
+            if taken {
+                Err("This username is taken".to_string())
+            }
+            else {
+                Ok(())
+            }
+        }))
+}
+
+pub struct Model {
+    form: Form<Win>,
+    valid: bool,
+    validating: bool,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    FormEvent(FormMsg),
+    Quit,
+    Submit,
+    Submitted,
+}
+
+#[widget]
+impl Widget for Win {
+    fn init_view(&mut self) {
+        self.country.append(Some("ca"), "Canada");
+        self.country.append(Some("fr"), "France");
+
+        let _ = self.model.form.field("username", &self.username)
+            .rule(Rule::required("The username is required"))
+            .rule(Rule::min_length(3, "The username must have at least 3 characters"))
+            .async_rule(check_username)
+            .error_label(&self.username_error);
+        let _ = self.model.form.field("age", &self.age)
+            .rule(Rule::new(|age| {
+                if age.parse::<f64>().map(|age| age >= 13.0).unwrap_or(false) {
+                    Ok(())
+                }
+                else {
+                    Err("You must be at least 13 years old".to_string())
+                }
+            }))
+            .error_label(&self.age_error);
+        let _ = self.model.form.field("country", &self.country)
+            .rule(Rule::required("Choose a country"))
+            .debounce(0)
+            .error_label(&self.country_error);
+    }
+
+    fn model(relm: &Relm<Self>, _: ()) -> Model {
+        Model {
+            form: Form::new(relm, FormEvent),
+            valid: false,
+            validating: false,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            FormEvent(msg) => self.model.form.update(msg),
+            Quit => gtk::main_quit(),
+            Submit => self.model.form.submit(Submitted),
+            Submitted => println!("Account created for {}", self.username.get_text().unwrap_or_default()),
+        }
+        self.model.valid = self.model.form.is_valid();
+        self.model.validating = self.model.form.is_validating();
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="username"]
+                gtk::Entry {
+                    placeholder_text: "Username",
+                },
+                #[name="username_error"]
+                gtk::Label {
+                },
+                #[name="age"]
+                gtk::SpinButton {
+                    adjustment: &gtk::Adjustment::new(0.0, 0.0, 150.0, 1.0, 10.0, 0.0),
+                },
+                #[name="age_error"]
+                gtk::Label {
+                },
+                #[name="country"]
+                gtk::ComboBoxText {
+                },
+                #[name="country_error"]
+                gtk::Label {
+                },
+                gtk::Button {
+                    class "suggested-action": self.model.valid,
+                    clicked => Submit,
+                    label: "Create account",
+                    sensitive: !self.model.validating,
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    Win::run(()).unwrap();
+}
@@ -0,0 +1,100 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+#![feature(proc_macro)]
+
+extern crate gtk;
+#[macro_use]
+extern crate relm;
+extern crate relm_attributes;
+#[macro_use]
+extern crate relm_derive;
+
+use gtk::{
+    ButtonExt,
+    Inhibit,
+    LabelExt,
+    OrientableExt,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::Widget;
+use relm_attributes::widget;
+
+use self::Msg::*;
+
+pub struct Model {
+    clicks: u32,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Click,
+    French,
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            clicks: 0,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Click => self.model.clicks += 1,
+            // The labels using tr!() are translated again, without recreating the view.
+            French => relm::set_locale("fr"),
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                gtk::Button {
+                    clicked => Click,
+                    label: &tr!(context = "button", "Click"),
+                },
+                gtk::Label {
+                    text: &tr!("Clicked once", "Clicked several times", self.model.clicks),
+                },
+                gtk::Button {
+                    clicked => French,
+                    label: &tr!("Switch to French"),
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    // The .mo files are generated from the messages extracted with:
+    // relm-xgettext -o po/i18n.pot examples/i18n.rs
+    if let Err(error) = relm::set_text_domain("i18n", "locale") {
+        eprintln!("Cannot load the translations: {}", error);
+    }
+    Win::run(()).unwrap();
+}
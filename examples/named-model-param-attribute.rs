@@ -0,0 +1,135 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+#![feature(proc_macro)]
+
+extern crate gtk;
+#[macro_use]
+extern crate relm;
+extern crate relm_attributes;
+#[macro_use]
+extern crate relm_derive;
+
+use gtk::{
+    ButtonExt,
+    Inhibit,
+    LabelExt,
+    OrientableExt,
+    WidgetExt,
+};
+use gtk::Orientation::{Horizontal, Vertical};
+use relm::Widget;
+use relm_attributes::widget;
+
+use self::CounterMsg::*;
+use self::Msg::*;
+
+// The fields that are not set in view! take their default value.
+#[derive(ModelParam)]
+pub struct CounterParams {
+    #[model_param(default = "Counter".to_string())]
+    label: String,
+    start: i32,
+    #[model_param(default = 1)]
+    step: i32,
+}
+
+pub struct CounterModel {
+    counter: i32,
+    label: String,
+    step: i32,
+}
+
+#[derive(Msg)]
+pub enum CounterMsg {
+    Decrement,
+    Increment,
+}
+
+#[widget]
+impl Widget for Counter {
+    fn model(params: CounterParams) -> CounterModel {
+        CounterModel {
+            counter: params.start,
+            label: params.label,
+            step: params.step,
+        }
+    }
+
+    fn update(&mut self, event: CounterMsg) {
+        match event {
+            Decrement => self.model.counter -= self.model.step,
+            Increment => self.model.counter += self.model.step,
+        }
+    }
+
+    view! {
+        gtk::Box {
+            orientation: Horizontal,
+            gtk::Label {
+                text: &self.model.label,
+            },
+            gtk::Button {
+                clicked => Decrement,
+                label: "-",
+            },
+            gtk::Label {
+                text: &self.model.counter.to_string(),
+            },
+            gtk::Button {
+                clicked => Increment,
+                label: "+",
+            },
+        },
+    }
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> () {
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                Counter(step: 2),
+                Counter(label: "Big steps".to_string(), start: 100, step: 10),
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    Win::run(()).unwrap();
+}
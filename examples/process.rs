@@ -0,0 +1,146 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+extern crate gtk;
+#[macro_use]
+extern crate relm;
+#[macro_use]
+extern crate relm_derive;
+
+use std::io;
+use std::process::{Command, ExitStatus};
+
+use gtk::{
+    Button,
+    ButtonExt,
+    ContainerExt,
+    Inhibit,
+    Label,
+    LabelExt,
+    WidgetExt,
+    Window,
+    WindowType,
+};
+use gtk::Orientation::Vertical;
+use relm::{Output, OutputMode, Process, Relm, Update, Widget};
+
+use self::Msg::*;
+
+struct Model {
+    process: Option<Process>,
+    relm: Relm<Win>,
+    text: String,
+}
+
+#[derive(Msg)]
+enum Msg {
+    Exited(ExitStatus),
+    Quit,
+    Read(Output),
+    Run,
+    WaitError(io::Error),
+}
+
+struct Win {
+    label: Label,
+    model: Model,
+    window: Window,
+}
+
+impl Update for Win {
+    type Model = Model;
+    type ModelParam = ();
+    type Msg = Msg;
+
+    fn model(relm: &Relm<Self>, _: ()) -> Model {
+        Model {
+            process: None,
+            relm: relm.clone(),
+            text: String::new(),
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Exited(status) => {
+                self.model.text.push_str(&format!("Exited with {}\n", status));
+                self.model.process = None;
+            },
+            Quit => gtk::main_quit(),
+            Read(output) => {
+                if let Output::Stderr(_) = output {
+                    self.model.text.push_str("error: ");
+                }
+                self.model.text.push_str(&output.to_string_lossy());
+                self.model.text.push('\n');
+            },
+            Run => {
+                self.model.text.clear();
+                match Process::spawn(Command::new("ls").arg("-l"), OutputMode::Lines) {
+                    Ok(mut process) => {
+                        self.model.relm.connect_exec_ignore_err(process.output(), Read);
+                        self.model.relm.connect_exec(process.wait(), Exited, WaitError);
+                        // NOTE: the process is killed if it is still running when the model is dropped.
+                        self.model.process = Some(process);
+                    },
+                    Err(error) => self.model.text = format!("Cannot run ls: {}", error),
+                }
+            },
+            WaitError(error) => self.model.text.push_str(&format!("Cannot wait for ls: {}\n", error)),
+        }
+        self.label.set_text(&self.model.text);
+    }
+}
+
+impl Widget for Win {
+    type Root = Window;
+
+    fn root(&self) -> Self::Root {
+        self.window.clone()
+    }
+
+    fn view(relm: &Relm<Self>, model: Self::Model) -> Self {
+        let vbox = gtk::Box::new(Vertical, 0);
+
+        let button = Button::new_with_label("Run ls -l");
+        vbox.add(&button);
+
+        let label = Label::new(None);
+        vbox.add(&label);
+
+        let window = Window::new(WindowType::Toplevel);
+        window.add(&vbox);
+        window.show_all();
+
+        connect!(relm, button, connect_clicked(_), Run);
+        connect!(relm, window, connect_delete_event(_, _), return (Some(Quit), Inhibit(false)));
+
+        Win {
+            label,
+            model,
+            window,
+        }
+    }
+}
+
+fn main() {
+    Win::run(()).unwrap();
+}
@@ -0,0 +1,177 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+#![feature(proc_macro)]
+
+extern crate gtk;
+#[macro_use]
+extern crate relm;
+extern crate relm_attributes;
+#[macro_use]
+extern crate relm_derive;
+
+use gtk::{
+    ButtonExt,
+    Inhibit,
+    LabelExt,
+    OrientableExt,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::{Relm, Router, Widget};
+use relm_attributes::widget;
+
+use self::HomeMsg::Open;
+use self::Msg::*;
+
+#[derive(Clone, PartialEq)]
+pub enum Route {
+    Article(u32),
+    Home,
+}
+
+#[derive(Msg)]
+pub enum HomeMsg {
+    Open(u32),
+}
+
+#[widget]
+impl Widget for HomePage {
+    fn model() -> () {
+    }
+
+    fn update(&mut self, _event: HomeMsg) {
+    }
+
+    view! {
+        gtk::Box {
+            orientation: Vertical,
+            gtk::Button {
+                clicked => Open(1),
+                label: "First article",
+            },
+            gtk::Button {
+                clicked => Open(2),
+                label: "Second article",
+            },
+        }
+    }
+}
+
+#[derive(Msg)]
+pub enum ArticleMsg {
+}
+
+#[widget]
+impl Widget for ArticlePage {
+    // The parameter of the route is the model parameter of the page.
+    fn model(id: u32) -> u32 {
+        id
+    }
+
+    fn update(&mut self, _event: ArticleMsg) {
+    }
+
+    view! {
+        gtk::Label {
+            text: &format!("Article #{}", self.model),
+        }
+    }
+}
+
+pub struct Model {
+    can_go_back: bool,
+    relm: Relm<Win>,
+    router: Option<Router<Route, Win>>,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Back,
+    Navigate(Route),
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn init_view(&mut self) {
+        let mut router = Router::new(&self.stack, &self.model.relm, |route, pages| {
+            match *route {
+                Route::Article(id) => pages.create::<ArticlePage>(id).destroy_when_hidden(),
+                Route::Home => {
+                    let page = pages.create::<HomePage>(());
+                    if let Some(home) = page.component::<HomePage>() {
+                        connect!(home@Open(id), pages.relm(), Navigate(Route::Article(id)));
+                    }
+                    page
+                },
+            }
+        });
+        router.push(Route::Home);
+        self.model.router = Some(router);
+    }
+
+    fn model(relm: &Relm<Self>, _: ()) -> Model {
+        Model {
+            can_go_back: false,
+            relm: relm.clone(),
+            router: None,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Back => {
+                if let Some(ref mut router) = self.model.router {
+                    let _ = router.pop();
+                }
+            },
+            Navigate(route) => {
+                if let Some(ref mut router) = self.model.router {
+                    router.push(route);
+                }
+            },
+            Quit => gtk::main_quit(),
+        }
+        self.model.can_go_back = self.model.router.as_ref().map(|router| router.can_go_back()).unwrap_or(false);
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                gtk::Button {
+                    clicked => Back,
+                    label: "Back",
+                    sensitive: self.model.can_go_back,
+                },
+                #[name="stack"]
+                gtk::Stack {
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    Win::run(()).unwrap();
+}
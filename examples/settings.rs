@@ -0,0 +1,96 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+#![feature(proc_macro)]
+
+extern crate gtk;
+#[macro_use]
+extern crate relm;
+extern crate relm_attributes;
+#[macro_use]
+extern crate relm_derive;
+
+use gtk::{
+    Inhibit,
+    LabelExt,
+    OrientableExt,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::{Relm, Settings, Widget};
+use relm_attributes::widget;
+
+use self::Msg::*;
+
+pub struct Model {
+    font_size: f64,
+    settings: Settings,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    FontSize(f64),
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model(relm: &Relm<Self>, _: ()) -> Model {
+        // Edit this file while the example runs to see the widgets update.
+        let settings = Settings::load("settings.ini", "Example").expect("settings");
+        settings.watch("font-size", relm, FontSize);
+        Model {
+            font_size: settings.get_or("font-size", 12.0),
+            settings,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            FontSize(size) => self.model.font_size = size,
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                gtk::CheckButton {
+                    label: "Dark theme",
+                    active: bind!(self.model.settings.dark_theme),
+                },
+                gtk::SpinButton {
+                    adjustment: &gtk::Adjustment::new(12.0, 6.0, 72.0, 1.0, 10.0, 0.0),
+                    value: bind!(self.model.settings.font_size),
+                },
+                gtk::Label {
+                    text: &format!("Font size: {}", self.model.font_size),
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    Win::run(()).unwrap();
+}
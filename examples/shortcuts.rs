@@ -0,0 +1,95 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+#![feature(proc_macro)]
+
+extern crate gtk;
+#[macro_use]
+extern crate relm;
+extern crate relm_attributes;
+#[macro_use]
+extern crate relm_derive;
+
+use gtk::{
+    Inhibit,
+    LabelExt,
+    WidgetExt,
+};
+use relm::{Relm, Shortcuts, Widget};
+use relm_attributes::widget;
+
+use self::Msg::*;
+
+pub struct Model {
+    relm: Relm<Win>,
+    shortcuts: Option<Shortcuts<Win>>,
+    text: String,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Quit,
+    Save,
+}
+
+#[widget]
+impl Widget for Win {
+    fn init_view(&mut self) {
+        let mut shortcuts = Shortcuts::new(&self.window, &self.model.relm);
+        shortcuts.add("save", "<Control>s", || Save).expect("save shortcut");
+        shortcuts.add("quit", "<Control>q", || Quit).expect("quit shortcut");
+        // Lines like "save = <Control><Shift>s" in this file remap the shortcuts.
+        if let Err(error) = shortcuts.load_config("shortcuts.conf") {
+            eprintln!("Cannot load the shortcuts: {}", error);
+        }
+        self.model.text = format!("Press {} to save", shortcuts.accelerator("save").unwrap_or_default());
+        self.model.shortcuts = Some(shortcuts);
+    }
+
+    fn model(relm: &Relm<Self>, _: ()) -> Model {
+        Model {
+            relm: relm.clone(),
+            shortcuts: None,
+            text: String::new(),
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Quit => gtk::main_quit(),
+            Save => self.model.text = "Saved".to_string(),
+        }
+    }
+
+    view! {
+        #[name="window"]
+        gtk::Window {
+            gtk::Label {
+                text: &self.model.text,
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    Win::run(()).unwrap();
+}
@@ -0,0 +1,108 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+#![feature(proc_macro)]
+
+extern crate gtk;
+#[macro_use]
+extern crate relm;
+extern crate relm_attributes;
+#[macro_use]
+extern crate relm_derive;
+
+use gtk::{
+    EditableSignals,
+    EntryExt,
+    Inhibit,
+    OrientableExt,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::{StyleSheet, Widget};
+use relm_attributes::widget;
+
+use self::Msg::*;
+
+pub struct Model {
+    invalid: bool,
+    style_sheet: Option<StyleSheet>,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Change,
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn init_view(&mut self) {
+        // Run with RELM_CSS_RELOAD=1 to see the changes made to the CSS file right away.
+        match StyleSheet::from_file("examples/style.css") {
+            Ok(style_sheet) => {
+                style_sheet.apply(&self.window);
+                self.model.style_sheet = Some(style_sheet);
+            },
+            Err(error) => eprintln!("Cannot load the style: {}", error),
+        }
+    }
+
+    fn model() -> Model {
+        Model {
+            invalid: false,
+            style_sheet: None,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Change => {
+                let text = self.entry.get_text().unwrap_or_default();
+                self.model.invalid = text.parse::<i32>().is_err();
+            },
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        #[name="window"]
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                gtk::Label {
+                    // The widget name is used by the #title selector.
+                    name: "title",
+                    text: "Enter a number",
+                },
+                #[name="entry"]
+                gtk::Entry {
+                    changed(_) => Change,
+                    class error: self.model.invalid,
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    Win::run(()).unwrap();
+}
@@ -5,34 +5,38 @@ extern crate relm;
 extern crate relm_derive;
 
 use gtk::prelude::*;
-use relm::{Relm, Update, Widget};
+use relm::{Relm, RowList, SignalGuard, Update, Widget};
 use std::fs;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use gtk::{
     Inhibit,
-    TreeView,
     Window,
     WindowType
 };
 use gtk::Orientation::Vertical;
 
-// These two constants stand for the columns of the listmodel and the listview
-const VALUE_COL: i32 = 0;
-const IS_DIR_COL: i32 = 1;
+// Every field is a column of the list store, in this order.
+#[derive(Clone, PartialEq, TreeRow)]
+struct File {
+    name: String,
+    is_dir: bool,
+}
 
 struct Directory {
     current_dir: PathBuf,
+    files: RowList<File>,
 }
 
 #[derive(Msg)]
 enum Msg {
-    ItemSelect,
+    Open(File),
     Quit,
 }
 
 struct Win {
-    tree_view: TreeView,
+    // The handler sending the activated files is disconnected when the guard is dropped.
+    _activated: SignalGuard,
     model: Directory,
     window: Window,
 }
@@ -44,42 +48,31 @@ impl Update for Win {
 
     fn model(_: &Relm<Self>, _: ()) -> Directory {
         let working_directory = fs::canonicalize(".").expect("Failed to open directory");
+        let mut files = RowList::new();
+        files.set_rows(read_files(&working_directory).unwrap());
         Directory {
-            current_dir: working_directory
+            current_dir: working_directory,
+            files,
         }
     }
 
     fn update(&mut self, event: Msg) {
         match event {
-            Msg::ItemSelect => {
-                let selection = self.tree_view.get_selection();
-                if let Some((list_model, iter)) = selection.get_selected() {
-                    let is_dir: bool = list_model
-                        .get_value(&iter, IS_DIR_COL)
-                        .get::<bool>()
-                        .unwrap();
-
-                    if is_dir {
-                        let dir_name = list_model
-                            .get_value(&iter, VALUE_COL)
-                            .get::<String>()
-                            .unwrap();
-
-                        println!("{:?} selected", dir_name);
-                        let new_dir = if dir_name == ".." {
-                            // Go up parent directory, if it exists
-                            self.model.current_dir
-                                .parent()
-                                .unwrap_or(&self.model.current_dir)
-                                .to_owned()
-                        } else {
-                            self.model.current_dir.join(dir_name)
-                        };
-                        self.model.current_dir = new_dir;
-                        let new_model = create_and_fill_model(&self.model.current_dir).unwrap();
-
-                        self.tree_view.set_model(Some(&new_model));
-                    }
+            Msg::Open(file) => {
+                if file.is_dir {
+                    println!("{:?} opened", file.name);
+                    let new_dir = if file.name == ".." {
+                        // Go up parent directory, if it exists
+                        self.model.current_dir
+                            .parent()
+                            .unwrap_or(&self.model.current_dir)
+                            .to_owned()
+                    } else {
+                        self.model.current_dir.join(file.name)
+                    };
+                    self.model.current_dir = new_dir;
+                    // Only the rows that changed are updated in the store.
+                    self.model.files.set_rows(read_files(&self.model.current_dir).unwrap());
                 }
             },
             Msg::Quit => gtk::main_quit(),
@@ -111,51 +104,49 @@ impl Widget for Win {
         column.add_attribute(&cell, "text", 0);
         tree_view.append_column(&column);
 
-        let store_model = create_and_fill_model(&model.current_dir).unwrap();
-        tree_view.set_model(Some(&store_model));
+        tree_view.set_model(Some(model.files.store()));
 
         vbox.add(&tree_view);
         window.add(&vbox);
 
         window.show_all();
 
-        connect!(relm, tree_view, connect_cursor_changed(_), Msg::ItemSelect);
+        let activated = model.files.connect_activated(&tree_view, relm, Msg::Open);
         connect!(relm, window, connect_delete_event(_, _), return (Some(Msg::Quit), Inhibit(false)));
 
         Win {
-            tree_view,
+            _activated: activated,
             model,
             window,
         }
     }
 }
 
-fn create_and_fill_model(dir_str: &PathBuf) -> io::Result<gtk::ListStore> {
-    // Single row model
-    let model = gtk::ListStore::new(&[String::static_type(), bool::static_type()]);
-
+fn read_files(dir: &Path) -> io::Result<Vec<File>> {
     // Add the parent directory
-    model.insert_with_values(None,
-                            &[VALUE_COL as u32, IS_DIR_COL as u32],
-                            &[&"..", &true]);
+    let mut files = vec![File {
+        name: "..".to_string(),
+        is_dir: true,
+    }];
 
-    let entry_iter = fs::read_dir(dir_str)?.filter_map(|x| x.ok());
+    let entry_iter = fs::read_dir(dir)?.filter_map(|x| x.ok());
     for entry in entry_iter {
         if let Ok(metadata) = entry.metadata() {
 
             if let Ok(file_name) = entry.file_name().into_string() {
-                let (final_name, is_dir) = if metadata.is_dir() {
+                let (name, is_dir) = if metadata.is_dir() {
                     (format!("{}/", file_name), true)
                 } else {
                     (file_name, false)
                 };
-                model.insert_with_values(None,
-                                        &[VALUE_COL as u32, IS_DIR_COL as u32],
-                                        &[&final_name, &is_dir]);
+                files.push(File {
+                    name,
+                    is_dir,
+                });
             }
         }
     }
-    Ok(model)
+    Ok(files)
 }
 
 
@@ -37,7 +37,7 @@ use gtk::{
     WindowType,
 };
 use gtk::Orientation::{Horizontal, Vertical};
-use relm::{Component, ContainerWidget, Relm, Update, Widget};
+use relm::{ComponentList, Relm, Update, Widget};
 
 use self::CounterMsg::*;
 use self::Msg::*;
@@ -125,8 +125,9 @@ enum Msg {
 }
 
 struct Win {
-    counters: Vec<Component<Counter>>,
-    hbox: gtk::Box,
+    counter_ids: Vec<usize>,
+    counters: ComponentList<usize, Counter>,
+    next_id: usize,
     relm: Relm<Win>,
     window: Window,
 }
@@ -143,16 +144,17 @@ impl Update for Win {
     fn update(&mut self, event: Msg) {
         match event {
             Add => {
-                let widget = self.hbox.add_widget::<Counter, _>(&self.relm, ());
-                self.counters.push(widget);
+                self.counter_ids.push(self.next_id);
+                self.next_id += 1;
             },
             Quit => gtk::main_quit(),
             Remove => {
-                if let Some(counter) = self.counters.pop() {
-                    self.hbox.remove_widget(counter);
-                }
+                let _ = self.counter_ids.pop();
             },
         }
+        // The counters are created and removed to match the ids.
+        let items = self.counter_ids.iter().map(|&id| (id, ()));
+        self.counters.set_items(&self.relm, items);
     }
 }
 
@@ -185,8 +187,9 @@ impl Widget for Win {
         connect!(relm, window, connect_delete_event(_, _), return (Some(Quit), Inhibit(false)));
 
         Win {
-            counters: vec![],
-            hbox: hbox,
+            counter_ids: vec![],
+            counters: ComponentList::new(&hbox),
+            next_id: 0,
             relm: relm.clone(),
             window: window,
         }
@@ -38,7 +38,7 @@ extern crate futures;
 use std::cell::RefCell;
 use std::collections::VecDeque;
 use std::io::Error;
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
 
 use futures::{Async, Poll, Stream};
 use futures::task::{self, Task};
@@ -90,6 +90,14 @@ impl<MSG> EventStream<MSG> {
         }
     }
 
+    /// Create a weak handle to this event stream.
+    /// The handle does not keep the stream alive.
+    pub fn downgrade(&self) -> WeakEventStream<MSG> {
+        WeakEventStream {
+            stream: Rc::downgrade(&self.stream),
+        }
+    }
+
     /// Close the event stream, i.e. stop processing messages.
     pub fn close(&self) -> Result<(), Error> {
         let mut stream = self.stream.borrow_mut();
@@ -122,6 +130,21 @@ impl<MSG> EventStream<MSG> {
         self.stream.borrow_mut().events.pop_front()
     }
 
+    /// Get an identifier of the event stream, which is the same for all of its clones.
+    pub fn id(&self) -> usize {
+        &*self.stream as *const RefCell<_EventStream<MSG>> as usize
+    }
+
+    /// Check whether there's no message waiting to be processed.
+    pub fn is_empty(&self) -> bool {
+        self.stream.borrow().events.is_empty()
+    }
+
+    /// Get the number of messages waiting to be processed.
+    pub fn len(&self) -> usize {
+        self.stream.borrow().events.len()
+    }
+
     /// Lock the stream (don't emit message) until the `Lock` goes out of scope.
     pub fn lock(&self) -> Lock<MSG> {
         self.stream.borrow_mut().locked = true;
@@ -130,7 +153,8 @@ impl<MSG> EventStream<MSG> {
         }
     }
 
-    fn is_terminated(&self) -> bool {
+    /// Check whether the event stream was closed.
+    pub fn is_closed(&self) -> bool {
         let stream = self.stream.borrow();
         stream.terminated
     }
@@ -142,12 +166,50 @@ impl<MSG> EventStream<MSG> {
     }
 }
 
+/// A weak handle to an `EventStream` which does not keep it alive.
+pub struct WeakEventStream<MSG> {
+    stream: Weak<RefCell<_EventStream<MSG>>>,
+}
+
+impl<MSG> Clone for WeakEventStream<MSG> {
+    fn clone(&self) -> Self {
+        WeakEventStream {
+            stream: self.stream.clone(),
+        }
+    }
+}
+
+impl<MSG> WeakEventStream<MSG> {
+    /// Create another weak handle to the same event stream.
+    /// This allows to use a `WeakEventStream` where an `EventStream` would be downgraded.
+    pub fn downgrade(&self) -> WeakEventStream<MSG> {
+        self.clone()
+    }
+
+    /// Send the `event` message to the stream if it is still alive and not closed.
+    /// This allows to use a `WeakEventStream` where an `EventStream` is expected, like in
+    /// `connect_stream!`.
+    pub fn emit(&self, event: MSG) {
+        match self.upgrade() {
+            Some(ref stream) if !stream.is_closed() => stream.emit(event),
+            _ => (),
+        }
+    }
+
+    /// Get the event stream if it is still alive.
+    pub fn upgrade(&self) -> Option<EventStream<MSG>> {
+        self.stream.upgrade().map(|stream| EventStream {
+            stream,
+        })
+    }
+}
+
 impl<MSG: 'static> Stream for EventStream<MSG> {
     type Item = MSG;
     type Error = ();
 
     fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
-        if self.is_terminated() {
+        if self.is_closed() {
             Ok(Async::Ready(None))
         }
         else {
@@ -6,6 +6,11 @@ extern crate syn;
 use quote::Tokens;
 use relm_gen_widget::gen_where_clause;
 use syn::{
+    Attribute,
+    Expr,
+    ExprAssign,
+    ExprParen,
+    ExprPath,
     Fields,
     GenericParam,
     Generics,
@@ -13,9 +18,124 @@ use syn::{
     Item,
     LifetimeDef,
     TypeParam,
+    parse2,
 };
 use syn::spanned::Spanned;
 
+pub fn impl_model_param(ast: &Item) -> Tokens {
+    if let Item::Struct(ref struct_item) = *ast {
+        let generics = &struct_item.generics;
+        let name = &struct_item.ident;
+        let generics_without_bound = remove_generic_bounds(generics);
+        let typ = quote! {
+            #name #generics_without_bound
+        };
+        let where_clause = gen_where_clause(generics);
+
+        let mut fields = vec![];
+        for field in &struct_item.fields {
+            let ident = field.ident.as_ref().expect("Expecting struct with named fields");
+            let default_attribute = field.attrs.iter()
+                .find(|attr| attr.path.segments.len() == 1 && attr.path.segments[0].ident.as_ref() == "model_param");
+            let value =
+                if let Some(attr) = default_attribute {
+                    let value = model_param_default(attr);
+                    quote_spanned! { attr.span() =>
+                        #value
+                    }
+                }
+                else {
+                    quote_spanned! { field.span() =>
+                        ::std::default::Default::default()
+                    }
+                };
+            fields.push(quote! {
+                #ident: #value
+            });
+        }
+
+        quote! {
+            impl #generics ::std::default::Default for #typ #where_clause {
+                fn default() -> Self {
+                    #name {
+                        #(#fields,)*
+                    }
+                }
+            }
+        }
+    }
+    else {
+        panic!("Expecting struct");
+    }
+}
+
+// Get the value of a `#[model_param(default = value)]` attribute.
+fn model_param_default(attr: &Attribute) -> Expr {
+    let value: ExprParen = parse2(attr.tts.clone()).expect("Expecting #[model_param(default = value)]");
+    if let Expr::Assign(ExprAssign { ref left, ref right, .. }) = *value.expr {
+        if let Expr::Path(ExprPath { qself: None, ref path, .. }) = **left {
+            if path.segments.len() == 1 && path.segments[0].ident.as_ref() == "default" {
+                return (**right).clone();
+            }
+        }
+    }
+    panic!("Expecting #[model_param(default = value)]");
+}
+
+pub fn impl_tree_row(ast: &Item) -> Tokens {
+    if let Item::Struct(ref struct_item) = *ast {
+        let generics = &struct_item.generics;
+        let name = &struct_item.ident;
+        let generics_without_bound = remove_generic_bounds(generics);
+        let typ = quote! {
+            #name #generics_without_bound
+        };
+        let where_clause = gen_where_clause(generics);
+
+        let mut column_types = vec![];
+        let mut values = vec![];
+        for field in &struct_item.fields {
+            let ident = field.ident.as_ref().expect("Expecting struct with named fields");
+            let skip = field.attrs.iter()
+                .filter(|attr| attr.path.segments.len() == 1 && attr.path.segments[0].ident.as_ref() == "column")
+                .any(|attr| {
+                    let value: ExprParen = parse2(attr.tts.clone()).expect("Expecting #[column(skip)]");
+                    let value = value.expr;
+                    let value = quote! { #value }.to_string();
+                    if value != "skip" {
+                        panic!("Unknown column attribute {}, expecting #[column(skip)]", value);
+                    }
+                    true
+                });
+            if skip {
+                continue;
+            }
+            let field_type = &field.ty;
+            column_types.push(quote_spanned! { field.span() =>
+                <#field_type as ::relm::StaticType>::static_type()
+            });
+            values.push(quote_spanned! { field.span() =>
+                ::relm::ToValue::to_value(&self.#ident)
+            });
+        }
+
+        quote! {
+            impl #generics ::relm::TreeRow for #typ #where_clause {
+                fn column_types() -> ::std::vec::Vec<::relm::Type> {
+                    vec![#(#column_types),*]
+                }
+
+                fn to_values(&self) -> ::std::vec::Vec<::relm::Value> {
+                    vec![#(#values),*]
+                }
+            }
+        }
+    }
+    else {
+        panic!("Expecting struct");
+    }
+}
+
 pub fn impl_msg(ast: &Item, krate: Ident) -> Tokens {
     let display = derive_display_variant(ast, &krate);
     let into_option = derive_into_option(ast, &krate);
@@ -172,3 +292,39 @@ fn remove_generic_bounds(generics: &Generics) -> Generics {
     }
     generics.clone()
 }
+
+#[cfg(test)]
+mod tests {
+    use syn::{Item, parse_str};
+
+    use super::impl_model_param;
+
+    fn model_param(item: &str) -> String {
+        let item: Item = parse_str(item).expect("item");
+        impl_model_param(&item).to_string().chars()
+            .filter(|char| !char.is_whitespace())
+            .collect()
+    }
+
+    #[test]
+    fn model_param_default() {
+        let tokens = model_param("struct Params {
+            #[model_param(default = \"Counter\".to_string())]
+            label: String,
+            start: i32,
+            #[model_param(default = 1)]
+            step: i32,
+        }");
+        assert!(tokens.starts_with("impl::std::default::DefaultforParams"));
+        assert!(tokens.contains("Params{label:\"Counter\".to_string(),start:::std::default::Default::default(),step:1,}"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Expecting #[model_param(default = value)]")]
+    fn model_param_without_default() {
+        let _ = model_param("struct Params {
+            #[model_param(1)]
+            step: i32,
+        }");
+    }
+}
@@ -14,7 +14,7 @@ extern crate syn;
 use proc_macro::TokenStream;
 use quote::Tokens;
 use relm_gen_widget::gen_widget;
-use relm_derive_common::{impl_msg, impl_simple_msg};
+use relm_derive_common::{impl_model_param, impl_msg, impl_simple_msg, impl_tree_row};
 use syn::{
     Ident,
     Item,
@@ -38,6 +38,28 @@ pub fn msg(input: TokenStream) -> TokenStream {
     gen.into()
 }
 
+/// Implement `Default` for a model parameter struct so that its relm widget can be created with
+/// named parameters in `view!`, like `Counter(step: 2)`.
+/// A field takes the value of its `#[model_param(default = value)]` attribute, or its `Default`
+/// value, when it is omitted.
+#[proc_macro_derive(ModelParam, attributes(model_param))]
+pub fn model_param(input: TokenStream) -> TokenStream {
+    let ast: Item = parse(input).unwrap();
+    let gen = impl_model_param(&ast);
+    gen.into()
+}
+
+/// Implement `relm::TreeRow` for a struct so that it can be used as the row of a `RowList` or a
+/// `RowTree`.
+/// Every field is a column, in the order they are declared, except the ones with the
+/// `#[column(skip)]` attribute.
+#[proc_macro_derive(TreeRow, attributes(column))]
+pub fn tree_row(input: TokenStream) -> TokenStream {
+    let ast: Item = parse(input).unwrap();
+    let gen = impl_tree_row(&ast);
+    gen.into()
+}
+
 #[proc_macro_derive(Widget)]
 pub fn widget(input: TokenStream) -> TokenStream {
     let ast: Item = parse(input).unwrap();
@@ -105,10 +105,19 @@ pub struct Message {
 pub struct Property {
     pub expr: Expr,
     pub is_relm_widget: bool,
+    pub kind: PropertyKind,
     pub name: Ident,
     pub widget_name: Ident,
 }
 
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub enum PropertyKind {
+    // An accessible property (name, description or role) set through the ATK object.
+    Accessible,
+    Setter,
+    StyleClass(String),
+}
+
 fn create_stmts(ident: &Ident, property_map: &PropertyModelMap, msg_map: &MsgModelMap) -> Vec<Stmt> {
     let mut stmts = vec![];
     stmts.append(&mut create_stmts_for_props(ident, property_map));
@@ -146,15 +155,23 @@ fn create_stmts_for_props(ident: &Ident, property_map: &PropertyModelMap) -> Vec
             let mut tokens = Tokens::new();
             tokens.append_all(&[&property.expr]);
             let stmt =
-                if property.is_relm_widget {
-                    quote_spanned! { ident.span() =>
-                        { self.#widget_name.#prop_name(#tokens); }
-                    }
-                }
-                else {
-                    quote_spanned! { ident.span() =>
-                        { self.#widget_name.#prop_name(#tokens); }
-                    }
+                match property.kind {
+                    PropertyKind::Accessible => {
+                        let function = Ident::new(&format!("set_accessible_{}", property.name), property.name.span());
+                        quote_spanned! { ident.span() =>
+                            { ::relm::#function(&self.#widget_name, #tokens); }
+                        }
+                    },
+                    PropertyKind::StyleClass(ref class) => {
+                        quote_spanned! { ident.span() =>
+                            { ::relm::toggle_style_class(&self.#widget_name, #class, #tokens); }
+                        }
+                    },
+                    PropertyKind::Setter => {
+                        quote_spanned! { ident.span() =>
+                            { self.#widget_name.#prop_name(#tokens); }
+                        }
+                    },
                 };
             let expr: Expr = parse(stmt.into()).expect("parse() in create_stmts");
             if let Block(ExprBlock { ref block, .. }) = expr {
@@ -27,6 +27,7 @@ use syn::{
     Expr,
     Generics,
     Ident,
+    LitStr,
     Path,
     Type,
     TypePath,
@@ -34,17 +35,20 @@ use syn::{
 };
 use syn::fold::Fold;
 use syn::spanned::Spanned;
+use syn::visit::Visit;
 
 use parser::{
     Event,
     GtkWidget,
     RelmWidget,
     Widget,
+    is_accessible_relation,
 };
 use parser::EventValue::{CurrentWidget, ForeignWidget, NoEventValue};
 use parser::EventValueReturn::{CallReturn, Return, WithoutReturn};
 use parser::EitherWidget::{Gtk, Relm};
 use transformer::Transformer;
+use walker::ModelVariableVisitor;
 use super::{Driver, MODEL_IDENT};
 
 use self::WidgetType::*;
@@ -93,14 +97,18 @@ pub fn gen(name: &Ident, widget: &Widget, driver: &mut Driver) -> (Tokens, HashM
         .collect();
     let widget_names1 = &widget_names1;
     let widget_names2 = widget_names1;
+    let bindings = &generator.bindings;
     let events = &generator.events;
     let properties = &generator.properties;
+    let relations = &generator.relations;
     let model_ident = Ident::from(MODEL_IDENT);
     let code = quote_spanned! { name.span() =>
         #widget_tokens
 
         #(#events)*
         #(#properties)*
+        #(#bindings)*
+        #(#relations)*
 
         #name {
             #root_widget_name: #root_widget_name,
@@ -113,10 +121,12 @@ pub fn gen(name: &Ident, widget: &Widget, driver: &mut Driver) -> (Tokens, HashM
 }
 
 struct Generator<'a> {
+    bindings: Vec<Tokens>,
     container_names: HashMap<Option<String>, (Ident, Path)>,
     driver: Option<&'a mut Driver>,
     events: Vec<Tokens>,
     properties: Vec<Tokens>,
+    relations: Vec<Tokens>,
     relm_widgets: HashMap<Ident, Path>,
     widget_names: Vec<Ident>,
 }
@@ -124,10 +134,12 @@ struct Generator<'a> {
 impl<'a> Generator<'a> {
     fn new(driver: &'a mut Driver) -> Self {
         Generator {
+            bindings: vec![],
             container_names: HashMap::new(),
             driver: Some(driver),
             events: vec![],
             properties: vec![],
+            relations: vec![],
             relm_widgets: HashMap::new(),
             widget_names: vec![],
         }
@@ -165,9 +177,8 @@ impl<'a> Generator<'a> {
     }
 
     fn add_or_create_widget(&mut self, parent: Option<&Ident>, parent_widget_type: WidgetType, widget_name: &Ident,
-        widget_type_ident: &Path, init_parameters: &[Expr], is_container: bool) -> Tokens
+        widget_type_ident: &Path, init_parameters: Tokens, is_container: bool) -> Tokens
     {
-        let init_parameters = gen_model_param(init_parameters, WithParens);
         if let Some(parent) = parent {
             if parent_widget_type == IsGtk {
                 let add_method =
@@ -214,6 +225,40 @@ impl<'a> Generator<'a> {
         }
     }
 
+    // NOTE: the relations are added after every widget is created since they can refer to a
+    // widget declared later in the view.
+    fn collect_accessible_relations(&mut self, widget_name: &Ident, gtk_widget: &GtkWidget) {
+        for &(ref kind, ref target) in &gtk_widget.accessible {
+            if is_accessible_relation(kind) {
+                let relation =
+                    if kind.as_ref() == "labelled_by" {
+                        quote! { ::relm::AccessibleRelation::LabelledBy }
+                    }
+                    else {
+                        quote! { ::relm::AccessibleRelation::DescribedBy }
+                    };
+                self.relations.push(quote_spanned! { kind.span() =>
+                    ::relm::add_accessible_relation(&#widget_name, #relation, &#target);
+                });
+            }
+        }
+    }
+
+    fn collect_bindings(&mut self, widget: &Widget, target: Tokens) {
+        for binding in &widget.bindings {
+            let mut remover = Transformer::new(MODEL_IDENT);
+            let source = remover.fold_expr(binding.source.clone());
+            let source_property = binding.source_property.as_ref().replace('_', "-");
+            let property = binding.property.as_ref().replace('_', "-");
+            let options = &binding.options;
+            self.bindings.push(quote_spanned! { binding.property.span() =>
+                let _ = ::relm::bind_property(&#source, #source_property, #target, #property)
+                    #(.#options)*
+                    .build();
+            });
+        }
+    }
+
     fn collect_event(&mut self, widget_name: Tokens, name: &Ident, event: &Event) {
         let event_ident = Ident::new(&format!("connect_{}", name), name.span());
         let event_params = &event.params;
@@ -327,7 +372,10 @@ impl<'a> Generator<'a> {
         }
 
         let construct_widget = gen_construct_widget(widget, gtk_widget);
+        let drag_and_drop = gen_drag_and_drop(widget_name, gtk_widget);
+        self.collect_accessible_relations(widget_name, gtk_widget);
         self.collect_events(widget, gtk_widget);
+        self.collect_bindings(widget, quote! { &#widget_name });
 
         let children: Vec<_> = widget.children.iter()
             .map(|child| self.widget(child, Some(widget_name), IsGtk))
@@ -336,11 +384,22 @@ impl<'a> Generator<'a> {
         let add_child_or_show_all = self.add_child_or_show_all(widget, parent, parent_widget_type);
         let ident = quote! { #widget_name };
         let (properties, visible_properties) = self.gtk_set_prop_calls(widget, ident);
+        let locale_setters = gen_locale_setters(widget);
+        let style_classes = gen_style_classes(widget_name, gtk_widget);
+        let accessible = gen_accessible(widget_name, gtk_widget);
+        let accessible_locale_setters = gen_accessible_locale_setters(widget_name, gtk_widget);
+        let accessibility_lint = gen_accessibility_lint(widget, gtk_widget);
         let child_properties = gen_set_child_prop_calls(widget, parent, parent_widget_type, IsGtk);
 
         quote_spanned! { widget_name.span() =>
             let #widget_name: #struct_name = #construct_widget;
+            #drag_and_drop
             #(#properties)*
+            #(#locale_setters)*
+            #(#style_classes)*
+            #(#accessible)*
+            #(#accessible_locale_setters)*
+            #accessibility_lint
             #(#children)*
             #add_child_or_show_all
             ::gtk::WidgetExt::show(&#widget_name);
@@ -360,6 +419,7 @@ impl<'a> Generator<'a> {
         self.relm_widgets.insert(widget.name.clone(), relm_component_type);
 
         self.collect_relm_events(widget, relm_widget);
+        self.collect_bindings(widget, quote! { #widget_name.widget() });
 
         let children: Vec<_> = widget.children.iter()
             .map(|child| self.widget(child, Some(widget_name), IsRelm))
@@ -369,8 +429,10 @@ impl<'a> Generator<'a> {
         self.properties.append(&mut properties);
         self.properties.append(&mut visible_properties);
 
+        let init_parameters = gen_relm_model_param(widget_type_ident, &widget.init_parameters,
+            &relm_widget.named_parameters);
         let add_or_create_widget = self.add_or_create_widget(
-            parent, parent_widget_type, widget_name, widget_type_ident, &widget.init_parameters, widget.is_container);
+            parent, parent_widget_type, widget_name, widget_type_ident, init_parameters, widget.is_container);
         let child_properties = gen_set_child_prop_calls(widget, parent, parent_widget_type, IsRelm);
         let messages = self.messages(widget, relm_widget);
 
@@ -544,48 +606,42 @@ fn gen_widget_type(widget: &Widget) -> Tokens {
 fn gen_add_widget_method(container_names: &HashMap<Option<String>, (Ident, Path)>) -> Tokens {
     if container_names.len() > 1 {
         let span = container_names.values().next().expect("at least one container name").0.span();
-        let mut default_container = Tokens::new();
-        let mut other_containers = Tokens::new();
-        for (parent_id, &(ref name, _)) in container_names {
-            if parent_id.is_none() {
-                default_container = quote_spanned! { span =>
-                    ::gtk::ContainerExt::add(&container.container, widget.widget());
-                    ::gtk::Cast::upcast(container.container.clone())
-                };
-            }
-            else {
-                if other_containers == quote! {} {
-                    other_containers = quote_spanned! { span =>
-                        if WIDGET::parent_id() == Some(#parent_id) {
-                            ::gtk::ContainerExt::add(&container.containers.#name, widget.widget());
-                            ::gtk::Cast::upcast(container.containers.#name.clone())
-                        }
-                    };
+        let add_widget = gen_parent_id_dispatch(container_names, span, |container| quote_spanned! { span =>
+            ::gtk::ContainerExt::add(&#container, widget.widget());
+            ::gtk::Cast::upcast(#container.clone())
+        });
+        let remove_widget = gen_parent_id_dispatch(container_names, span, |container| quote_spanned! { span =>
+            ::gtk::ContainerExt::remove(&#container, widget.widget());
+        });
+        let clear_containers = container_names.iter().map(|(parent_id, &(ref name, _))| {
+            let container =
+                if parent_id.is_none() {
+                    quote! { container.container }
                 }
                 else {
-                    other_containers = quote_spanned! { span =>
-                        #other_containers
-                        else if WIDGET::parent_id() == Some(#parent_id) {
-                            ::gtk::ContainerExt::add(&container.containers.#name, widget.widget());
-                            ::gtk::Cast::upcast(container.containers.#name.clone())
-                        }
-                    };
+                    quote! { container.containers.#name }
+                };
+            quote_spanned! { span =>
+                for child in ::gtk::ContainerExt::get_children(&#container) {
+                    ::gtk::ContainerExt::remove(&#container, &child);
                 }
             }
-        }
-        if other_containers != quote! {} {
-            default_container = quote! {
-                else {
-                    #default_container
-                }
-            };
-        }
+        });
         quote_spanned! { span =>
             fn add_widget<WIDGET: Widget>(container: &::relm::ContainerComponent<Self>,
                 widget: &::relm::Component<WIDGET>) -> ::gtk::Container
             {
-                #other_containers
-                #default_container
+                #add_widget
+            }
+
+            fn clear(container: &::relm::ContainerComponent<Self>) {
+                #(#clear_containers)*
+            }
+
+            fn remove_widget<WIDGET: Widget>(container: &::relm::ContainerComponent<Self>,
+                widget: &::relm::Component<WIDGET>)
+            {
+                #remove_widget
             }
         }
     }
@@ -595,6 +651,52 @@ fn gen_add_widget_method(container_names: &HashMap<Option<String>, (Ident, Path)
     }
 }
 
+/*
+ * Generate the code to do an action on the container that matches the parent id of the widget
+ * (or on the default container when no container matches).
+ */
+fn gen_parent_id_dispatch<F>(container_names: &HashMap<Option<String>, (Ident, Path)>, span: Span, action: F)
+    -> Tokens
+    where F: Fn(Tokens) -> Tokens,
+{
+    let mut default_container = Tokens::new();
+    let mut other_containers = Tokens::new();
+    for (parent_id, &(ref name, _)) in container_names {
+        if parent_id.is_none() {
+            default_container = action(quote! { container.container });
+        }
+        else {
+            let action = action(quote! { container.containers.#name });
+            if other_containers == quote! {} {
+                other_containers = quote_spanned! { span =>
+                    if WIDGET::parent_id() == Some(#parent_id) {
+                        #action
+                    }
+                };
+            }
+            else {
+                other_containers = quote_spanned! { span =>
+                    #other_containers
+                    else if WIDGET::parent_id() == Some(#parent_id) {
+                        #action
+                    }
+                };
+            }
+        }
+    }
+    if other_containers != quote! {} {
+        default_container = quote! {
+            else {
+                #default_container
+            }
+        };
+    }
+    quote! {
+        #other_containers
+        #default_container
+    }
+}
+
 fn gen_container_impl(generator: &Generator, widget: &Widget, generic_types: &Generics) -> Tokens {
     let where_clause = gen_where_clause(generic_types);
     let widget_type = gen_widget_type(widget);
@@ -696,6 +798,149 @@ fn gen_other_containers(generator: &Generator, widget_type: &Tokens, widget_iden
     }
 }
 
+// The targets of the #[drag_source] and #[drop_target] attributes are separated by commas, like
+// #[drop_target="text, uris"].
+fn gen_drag_and_drop(widget_name: &Ident, gtk_widget: &GtkWidget) -> Tokens {
+    let targets = |targets: &LitStr| -> Vec<String> {
+        targets.value().split(',')
+            .map(|target| target.trim().to_string())
+            .filter(|target| !target.is_empty())
+            .collect()
+    };
+    let drag_source = gtk_widget.drag_source.as_ref().map(|lit| {
+        let targets = targets(lit);
+        quote_spanned! { lit.span() =>
+            ::relm::DragExt::set_drag_source(&#widget_name, &[#(::relm::DragTarget::from(#targets)),*]);
+        }
+    });
+    let drop_target = gtk_widget.drop_target.as_ref().map(|lit| {
+        let targets = targets(lit);
+        quote_spanned! { lit.span() =>
+            ::relm::DragExt::set_drop_target(&#widget_name, &[#(::relm::DragTarget::from(#targets)),*]);
+        }
+    });
+    quote! {
+        #drag_source
+        #drop_target
+    }
+}
+
+// The translated properties which do not depend on the model are set again when the locale
+// changes; the others are set by the generated Widget::on_locale_changed() method, since they
+// read the model.
+fn gen_locale_setters(widget: &Widget) -> Vec<Tokens> {
+    let widget_name = &widget.name;
+    widget.translated_properties.iter()
+        .filter_map(|name| widget.properties.get(name).map(|value| (name, value)))
+        .filter(|&(_, value)| {
+            let mut visitor = ModelVariableVisitor::new();
+            visitor.visit_expr(value);
+            visitor.idents.is_empty()
+        })
+        .map(|(name, value)| {
+            let property_func = Ident::new(&format!("set_{}", name), name.span());
+            quote_spanned! { name.span() =>
+                ::relm::connect_locale_changed(&#widget_name, |#widget_name| {
+                    #widget_name.#property_func(#value);
+                });
+            }
+        })
+        .collect()
+}
+
+// Same as gen_locale_setters() for the translated accessibility properties.
+fn gen_accessible_locale_setters(widget_name: &Ident, gtk_widget: &GtkWidget) -> Vec<Tokens> {
+    gtk_widget.accessible.iter()
+        .filter(|&&(ref kind, ref value)| {
+            let mut visitor = ModelVariableVisitor::new();
+            visitor.visit_expr(value);
+            gtk_widget.translated_accessible.contains(kind) && visitor.idents.is_empty()
+        })
+        .map(|&(ref kind, ref value)| {
+            let function = Ident::new(&format!("set_accessible_{}", kind), kind.span());
+            quote_spanned! { kind.span() =>
+                ::relm::connect_locale_changed(&#widget_name, |#widget_name| {
+                    ::relm::#function(#widget_name, &#value);
+                });
+            }
+        })
+        .collect()
+}
+
+fn gen_accessible(widget_name: &Ident, gtk_widget: &GtkWidget) -> Vec<Tokens> {
+    gtk_widget.accessible.iter()
+        .filter(|&&(ref kind, _)| !is_accessible_relation(kind))
+        .map(|&(ref kind, ref value)| {
+            let mut remover = Transformer::new(MODEL_IDENT);
+            let value = remover.fold_expr(value.clone());
+            let function = Ident::new(&format!("set_accessible_{}", kind), kind.span());
+            // NOTE: the translated values are Strings.
+            let value =
+                if gtk_widget.translated_accessible.contains(kind) {
+                    quote! { &#value }
+                }
+                else {
+                    quote! { #value }
+                };
+            quote_spanned! { kind.span() =>
+                ::relm::#function(&#widget_name, #value);
+            }
+        })
+        .collect()
+}
+
+/*
+ * Warn about buttons only showing an icon, since screen readers have nothing to announce for them.
+ * There's no way to emit a warning from a procedural macro on stable, so call a deprecated
+ * function instead.
+ */
+fn gen_accessibility_lint(widget: &Widget, gtk_widget: &GtkWidget) -> Tokens {
+    let has_property = |name: &str| {
+        widget.properties.keys().chain(gtk_widget.construct_properties.keys())
+            .any(|property| property.as_ref() == name)
+    };
+    let is_type = |typ: &Path, suffix: &str| {
+        typ.segments.iter().last()
+            .map(|segment| segment.ident.as_ref().ends_with(suffix))
+            .unwrap_or(false)
+    };
+    let is_button = is_type(&widget.typ, "Button");
+    let has_icon = has_property("image") || has_property("icon_name") ||
+        widget.children.iter().any(|child| is_type(&child.typ, "Image"));
+    let has_label = has_property("label") || widget.children.iter().any(|child| is_type(&child.typ, "Label"));
+    // NOTE: GTK+ uses the tooltip as the accessible name of a widget without label.
+    let has_accessible_name = has_property("tooltip_text") || has_property("tooltip_markup") ||
+        gtk_widget.accessible.iter()
+            .any(|&(ref kind, _)| kind.as_ref() == "name" || kind.as_ref() == "labelled_by");
+    if is_button && has_icon && !has_label && !has_accessible_name {
+        // NOTE: the deprecated lint is reported in macro expansions and the call is spanned with
+        // the widget in the view, so the warning points to the button.
+        quote_spanned! { widget.name.span() =>
+            {
+                #[deprecated(note =
+                    "this icon-only button has no accessible name: add `accessible name: \"...\"` or a tooltip")]
+                fn icon_only_button_without_accessible_name() {}
+                icon_only_button_without_accessible_name();
+            }
+        }
+    }
+    else {
+        quote! {}
+    }
+}
+
+fn gen_style_classes(widget_name: &Ident, gtk_widget: &GtkWidget) -> Vec<Tokens> {
+    gtk_widget.style_classes.iter()
+        .map(|&(ref class, ref value)| {
+            let mut remover = Transformer::new(MODEL_IDENT);
+            let value = remover.fold_expr(value.clone());
+            quote_spanned! { widget_name.span() =>
+                ::relm::toggle_style_class(&#widget_name, #class, #value);
+            }
+        })
+        .collect()
+}
+
 fn gen_model_param(init_parameters: &[Expr], with_parens: WithParentheses) -> Tokens {
     let mut params = vec![];
     for param in init_parameters {
@@ -715,6 +960,28 @@ fn gen_model_param(init_parameters: &[Expr], with_parens: WithParentheses) -> To
     }
 }
 
+// Named parameters, like `Counter(step: 2)`, are set on the default value of the model parameter
+// (see `#[derive(ModelParam)]`), so that an unknown name gives a compilation error.
+fn gen_relm_model_param(widget_type: &Path, init_parameters: &[Expr], named_parameters: &[(Ident, Expr)]) -> Tokens {
+    if named_parameters.is_empty() {
+        return gen_model_param(init_parameters, WithParens);
+    }
+    let mut assignments = vec![];
+    for &(ref name, ref value) in named_parameters {
+        let mut remover = Transformer::new(MODEL_IDENT);
+        let value = remover.fold_expr(value.clone());
+        assignments.push(quote_spanned! { name.span() =>
+            __relm_model_param.#name = #value;
+        });
+    }
+    quote! {{
+        let mut __relm_model_param: <#widget_type as ::relm::Update>::ModelParam =
+            ::std::default::Default::default();
+        #(#assignments)*
+        __relm_model_param
+    }}
+}
+
 fn gen_relm_component_type(is_container: bool, name: &Path) -> Path {
     let tokens =
         if is_container {
@@ -783,3 +1050,70 @@ pub fn gen_where_clause(generics: &Generics) -> Tokens {
         }
     }*/
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use proc_macro2::TokenStream;
+
+    use parser::EitherWidget::{Gtk, Relm};
+    use parser::parse_widget;
+    use super::gen_accessibility_lint;
+
+    fn lint(view: &str) -> String {
+        let widget = parse_widget(TokenStream::from_str(view).expect("view tokens"));
+        let lint =
+            match widget.widget {
+                Gtk(ref gtk_widget) => gen_accessibility_lint(&widget, gtk_widget),
+                Relm(_) => panic!("Expected gtk widget"),
+            };
+        lint.to_string().chars()
+            .filter(|char| !char.is_whitespace())
+            .collect()
+    }
+
+    #[test]
+    fn icon_only_button() {
+        // The call to the deprecated function is what makes rustc warn about the button.
+        let warning = lint("gtk::Button {
+            image: Some(&image),
+        }");
+        assert!(warning.contains("#[deprecated(note="));
+        assert!(warning.contains("fnicon_only_button_without_accessible_name(){}"));
+        assert!(warning.contains("icon_only_button_without_accessible_name();"));
+        let warning = lint("gtk::ToggleButton {
+            gtk::Image {
+                icon_name: \"edit-find\",
+            },
+        }");
+        assert!(warning.contains("icon_only_button_without_accessible_name();"));
+    }
+
+    #[test]
+    fn icon_only_button_with_accessible_name() {
+        assert_eq!(lint("gtk::Button {
+            image: Some(&image),
+            accessible name: \"Search\",
+        }"), "");
+        assert_eq!(lint("gtk::Button {
+            image: Some(&image),
+            accessible labelled_by: label,
+        }"), "");
+        assert_eq!(lint("gtk::Button {
+            image: Some(&image),
+            tooltip_text: Some(\"Search\"),
+        }"), "");
+        assert_eq!(lint("gtk::Button {
+            image: Some(&image),
+            tooltip_markup: Some(\"<b>Search</b>\"),
+        }"), "");
+        assert_eq!(lint("gtk::Button {
+            image: Some(&image),
+            label: \"Search\",
+        }"), "");
+        assert_eq!(lint("gtk::Label {
+            text: \"Search\",
+        }"), "");
+    }
+}
@@ -41,6 +41,7 @@ mod adder;
 mod gen;
 mod parser;
 mod transformer;
+mod translation;
 mod walker;
 
 use std::collections::{HashMap, HashSet};
@@ -68,11 +69,11 @@ use syn::spanned::Spanned;
 use syn::Type;
 use syn::visit::Visit;
 
-use adder::{Adder, Message, Property};
+use adder::{Adder, Message, Property, PropertyKind};
 use gen::gen;
 pub use gen::gen_where_clause;
 use parser::EitherWidget::{Gtk, Relm};
-use parser::{Widget, parse_widget};
+use parser::{GtkWidget, Widget, is_accessible_relation, parse_widget};
 use walker::ModelVariableVisitor;
 
 const MODEL_IDENT: &str = "__relm_model";
@@ -84,6 +85,7 @@ type PropertyModelMap = HashMap<Ident, HashSet<Property>>;
 pub struct Driver {
     data_method: Option<ImplItem>,
     generic_types: Option<Generics>,
+    init_method: Option<ImplItem>,
     model_type: Option<ImplItem>,
     model_param_type: Option<ImplItem>,
     msg_model_map: Option<MsgModelMap>,
@@ -117,6 +119,7 @@ impl Driver {
         Driver {
             data_method: None,
             generic_types: None,
+            init_method: None,
             model_type: None,
             model_param_type: None,
             msg_model_map: None,
@@ -208,6 +211,7 @@ impl Driver {
                                 add_model_param(&mut i, &mut self.model_param_type);
                                 update_items.push(i);
                             },
+                            "init" => self.init_method = Some(i),
                             "subscriptions" => update_items.push(i),
                             "init_view" | "on_add" => new_items.push(i),
                             "update" => {
@@ -233,8 +237,17 @@ impl Driver {
             if let Some(on_add) = gen_set_child_prop_calls(&view.widget) {
                 new_items.push(on_add);
             }
+            if let Some(on_locale_changed) = gen_on_locale_changed(&view.widget) {
+                new_items.push(on_locale_changed);
+            }
             self.msg_model_map = Some(view.msg_model_map);
             self.properties_model_map = Some(view.properties_model_map);
+            // NOTE: like in update(), the properties bound to the model attributes assigned in
+            // init() are updated.
+            if let Some(mut init) = self.init_method.take() {
+                self.add_set_property_to_method(&mut init);
+                update_items.push(init);
+            }
             new_items.push(view.item);
             self.widgets.insert(self.root_widget.clone().expect("root widget"),
             self.root_widget_type.clone().expect("root widget type"));
@@ -514,6 +527,7 @@ macro_rules! get_map {
                 set.insert(Property {
                     expr: expr.clone(),
                     is_relm_widget: $is_relm,
+                    kind: PropertyKind::Setter,
                     name: name.clone(),
                     widget_name: $widget.name.clone(),
                 });
@@ -558,11 +572,52 @@ fn get_msg_model_map(widget: &Widget, map: &mut MsgModelMap) {
  */
 fn get_properties_model_map(widget: &Widget, map: &mut PropertyModelMap) {
     match widget.widget {
-        Gtk(_) => get_map!(widget, map, false),
+        Gtk(ref gtk_widget) => {
+            get_accessible_model_map(widget, gtk_widget, map);
+            get_style_classes_model_map(widget, gtk_widget, map);
+            get_map!(widget, map, false)
+        },
         Relm(_) => get_map!(widget, map, true),
     }
 }
 
+fn get_accessible_model_map(widget: &Widget, gtk_widget: &GtkWidget, map: &mut PropertyModelMap) {
+    for &(ref kind, ref expr) in &gtk_widget.accessible {
+        if is_accessible_relation(kind) {
+            continue;
+        }
+        let mut visitor = ModelVariableVisitor::new();
+        visitor.visit_expr(expr);
+        for var in visitor.idents {
+            let set = map.entry(var).or_insert_with(HashSet::new);
+            set.insert(Property {
+                expr: expr.clone(),
+                is_relm_widget: false,
+                kind: PropertyKind::Accessible,
+                name: kind.clone(),
+                widget_name: widget.name.clone(),
+            });
+        }
+    }
+}
+
+fn get_style_classes_model_map(widget: &Widget, gtk_widget: &GtkWidget, map: &mut PropertyModelMap) {
+    for &(ref class, ref expr) in &gtk_widget.style_classes {
+        let mut visitor = ModelVariableVisitor::new();
+        visitor.visit_expr(expr);
+        for var in visitor.idents {
+            let set = map.entry(var).or_insert_with(HashSet::new);
+            set.insert(Property {
+                expr: expr.clone(),
+                is_relm_widget: false,
+                kind: PropertyKind::StyleClass(class.clone()),
+                name: Ident::new("class", widget.name.span()),
+                widget_name: widget.name.clone(),
+            });
+        }
+    }
+}
+
 fn get_return_type(sig: MethodSig) -> Type {
     if let ReturnType::Type(_, ty) = sig.decl.output {
         *ty
@@ -608,6 +663,51 @@ fn gen_set_child_prop_calls(widget: &Widget) -> Option<ImplItem> {
     }
 }
 
+// Set the translated properties which depend on the model again when the locale changes.
+fn gen_on_locale_changed(widget: &Widget) -> Option<ImplItem> {
+    let mut setters = vec![];
+    collect_model_translations(widget, &mut setters);
+    if setters.is_empty() {
+        return None;
+    }
+    Some(block_to_impl_item(quote! {
+        fn on_locale_changed(&self) {
+            #(#setters)*
+        }
+    }))
+}
+
+fn collect_model_translations(widget: &Widget, setters: &mut Vec<Tokens>) {
+    if let Gtk(ref gtk_widget) = widget.widget {
+        let widget_name = &widget.name;
+        for name in &widget.translated_properties {
+            if let Some(value) = widget.properties.get(name) {
+                let mut visitor = ModelVariableVisitor::new();
+                visitor.visit_expr(value);
+                if !visitor.idents.is_empty() {
+                    let property_func = Ident::new(&format!("set_{}", name), name.span());
+                    setters.push(quote_spanned! { name.span() =>
+                        self.#widget_name.#property_func(#value);
+                    });
+                }
+            }
+        }
+        for &(ref kind, ref value) in &gtk_widget.accessible {
+            let mut visitor = ModelVariableVisitor::new();
+            visitor.visit_expr(value);
+            if gtk_widget.translated_accessible.contains(kind) && !visitor.idents.is_empty() {
+                let function = Ident::new(&format!("set_accessible_{}", kind), kind.span());
+                setters.push(quote_spanned! { kind.span() =>
+                    ::relm::#function(&self.#widget_name, &#value);
+                });
+            }
+        }
+    }
+    for child in &widget.children {
+        collect_model_translations(child, setters);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
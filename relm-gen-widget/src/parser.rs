@@ -35,6 +35,10 @@ use quote::ToTokens;
 use syn::{
     self,
     Expr,
+    ExprCall,
+    ExprField,
+    ExprMacro,
+    ExprPath,
     Ident,
     LitStr,
     Pat,
@@ -43,9 +47,12 @@ use syn::{
     parse,
     parse2,
 };
+use syn::Member::Named;
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 
+use translation::{translate_properties, translate_value};
+
 use self::ChildItem::*;
 use self::EventValue::*;
 use self::EventValueReturn::*;
@@ -101,8 +108,17 @@ impl Event {
     }
 }
 
+#[derive(Debug)]
+pub struct PropertyBinding {
+    pub options: Vec<Expr>,
+    pub property: Ident,
+    pub source: Expr,
+    pub source_property: Ident,
+}
+
 #[derive(Debug)]
 pub struct Widget {
+    pub bindings: Vec<PropertyBinding>,
     pub child_events: ChildEvents, // TODO: does it make sense for a relm widget?
     pub child_properties: ChildProperties, // TODO: does it make sense for a relm widget?
     pub children: Vec<Widget>,
@@ -112,16 +128,20 @@ pub struct Widget {
     pub name: Ident,
     pub parent_id: Option<String>,
     pub properties: HashMap<Ident, Expr>,
+    pub translated_properties: Vec<Ident>,
     pub typ: Path,
     pub widget: EitherWidget,
 }
 
 impl Widget {
     fn new_gtk(widget: GtkWidget, typ: Path, init_parameters: Vec<Expr>, children: Vec<Widget>,
-        properties: HashMap<Ident, Expr>, child_properties: ChildProperties, child_events: ChildEvents) -> Self
+        mut properties: HashMap<Ident, Expr>, child_properties: ChildProperties, child_events: ChildEvents) -> Self
     {
         let name = gen_widget_name(&typ);
+        let bindings = extract_bindings(&mut properties);
+        let translated_properties = translate_properties(&mut properties);
         Widget {
+            bindings,
             child_events,
             child_properties,
             children,
@@ -131,20 +151,24 @@ impl Widget {
             name,
             parent_id: None,
             properties,
+            translated_properties,
             typ,
             widget: Gtk(widget),
         }
     }
 
     fn new_relm(widget: RelmWidget, typ: Path, init_parameters: Vec<Expr>, children: Vec<Widget>,
-        properties: HashMap<Ident, Expr>, child_properties: ChildProperties, child_events: ChildEvents) -> Self
+        mut properties: HashMap<Ident, Expr>, child_properties: ChildProperties, child_events: ChildEvents) -> Self
     {
         let mut name = gen_widget_name(&typ);
         // Relm widgets are not used in the update() method; they are only saved to avoid dropping
         // their channel too soon.
         // So prepend an underscore to hide a warning.
         name = Ident::new(&format!("_{}", name), name.span());
+        let bindings = extract_bindings(&mut properties);
+        let translated_properties = translate_properties(&mut properties);
         Widget {
+            bindings,
             child_events,
             child_properties,
             children,
@@ -154,6 +178,7 @@ impl Widget {
             name,
             parent_id: None,
             properties,
+            translated_properties,
             typ,
             widget: Relm(widget),
         }
@@ -168,19 +193,29 @@ pub enum EitherWidget {
 
 #[derive(Debug)]
 pub struct GtkWidget {
+    pub accessible: Vec<(Ident, Expr)>,
     pub construct_properties: HashMap<Ident, Expr>,
+    pub drag_source: Option<LitStr>,
+    pub drop_target: Option<LitStr>,
     pub events: HashMap<Ident, Event>,
     pub relm_name: Option<Type>,
     pub save: bool,
+    pub style_classes: Vec<(String, Expr)>,
+    pub translated_accessible: Vec<Ident>,
 }
 
 impl GtkWidget {
     fn new() -> Self {
         GtkWidget {
+            accessible: vec![],
             construct_properties: HashMap::new(),
+            drag_source: None,
+            drop_target: None,
             events: HashMap::new(),
             relm_name: None,
             save: false,
+            style_classes: vec![],
+            translated_accessible: vec![],
         }
     }
 }
@@ -190,6 +225,7 @@ pub struct RelmWidget {
     pub events: HashMap<Ident, Vec<Event>>,
     pub gtk_events: HashMap<Ident, Event>,
     pub messages: HashMap<Ident, Expr>,
+    pub named_parameters: Vec<(Ident, Expr)>,
 }
 
 impl RelmWidget {
@@ -198,6 +234,7 @@ impl RelmWidget {
             events: HashMap::new(),
             gtk_events: HashMap::new(),
             messages: HashMap::new(),
+            named_parameters: vec![],
         }
     }
 }
@@ -297,6 +334,7 @@ named! { init_properties -> InitProperties,
 }
 
 enum ChildItem {
+    Accessible(Ident, Expr),
     ChildEvent(Ident, Ident, Event),
     ItemChildProperties(ChildProperties),
     ItemEvent(Ident, Event),
@@ -304,17 +342,20 @@ enum ChildItem {
     Property(Ident, Value),
     RelmMsg(Ident, Value),
     RelmMsgEvent(Ident, Event),
+    StyleClass(String, Expr),
 }
 
 impl ChildItem {
     fn unwrap_widget(self) -> Widget {
         match self {
+            Accessible(_, _) => panic!("Expected widget, found accessible property"),
             ChildEvent(_, _, _) => panic!("Expected widget, found child event"),
             ItemEvent(_, _) => panic!("Expected widget, found event"),
             ItemChildProperties(_) => panic!("Expected widget, found child properties"),
             Property(_, _) => panic!("Expected widget, found property"),
             RelmMsg(_, _) => panic!("Expected widget, found relm msg"),
             RelmMsgEvent(_, _) => panic!("Expected widget, found relm msg event"),
+            StyleClass(_, _) => panic!("Expected widget, found style class"),
             ChildWidget(widget) => widget,
         }
     }
@@ -415,6 +456,7 @@ named! { gtk_widget(typ: Path, save: bool) -> ChildItem, map!(do_parse!(
                 let mut child_properties = HashMap::new();
                 for item in child_items.into_iter() {
                     match item {
+                        Accessible(kind, value) => gtk_widget.accessible.push((kind, value)),
                         ChildEvent(event_name, child_name, event) => {
                             let _ = child_events.insert((child_name, event_name), event);
                         },
@@ -427,6 +469,12 @@ named! { gtk_widget(typ: Path, save: bool) -> ChildItem, map!(do_parse!(
                         ChildWidget(widget) => children.push(widget),
                         Property(ident, value) => { let _ = properties.insert(ident, value.value); },
                         RelmMsg(_, _) | RelmMsgEvent(_, _) => panic!("Unexpected relm msg in gtk widget"),
+                        StyleClass(name, value) => gtk_widget.style_classes.push((name, value)),
+                    }
+                }
+                for &mut (ref kind, ref mut value) in &mut gtk_widget.accessible {
+                    if translate_value(value) {
+                        gtk_widget.translated_accessible.push(kind.clone());
                     }
                 }
                 match init_properties {
@@ -448,13 +496,43 @@ named! { child_relm_item -> ChildItem, alt!
     )
 }
 
+// Parse the parameters of a relm widget, either positional, like `Counter(2, "label")`, or named,
+// like `Counter(step: 2)`.
+named! { relm_init_parameters -> (Vec<Expr>, Vec<(Ident, Expr)>), alt!
+    ( do_parse!(
+        first: named_parameter >>
+        rest: many0!(do_parse!(
+            punct!(,) >>
+            parameter: named_parameter >>
+            (parameter)
+        )) >>
+        option!(punct!(,)) >>
+        ({
+            let mut parameters = vec![first];
+            parameters.extend(rest);
+            (vec![], parameters)
+        })
+      )
+    | map!(expr_list, |exprs| (exprs, vec![]))
+    )
+}
+
+named! { named_parameter -> (Ident, Expr), do_parse!(
+    ident: syn!(Ident) >>
+    punct!(:) >>
+    expr: syn!(Expr) >>
+    (ident, expr)
+)}
+
 named! { relm_widget(typ: Path) -> ChildItem, do_parse!(
-        init_parameters: option!(map!(parens!(expr_list), |(_, exprs)| exprs)) >>
+        parameters: map!(option!(map!(parens!(relm_init_parameters), |(_, parameters)| parameters)),
+            |parameters| parameters.unwrap_or_else(|| (vec![], vec![]))) >>
         relm_widget: map!(option!(braces!(do_parse!(
             child_items: separated_by0!(punct!(,), call!(child_relm_item)) >>
             ({
-                let init_parameters = init_parameters.clone().unwrap_or_else(Vec::new);
+                let (init_parameters, named_parameters) = parameters.clone();
                 let mut relm_widget = RelmWidget::new();
+                relm_widget.named_parameters = named_parameters;
                 let mut children = vec![];
                 let mut child_properties = HashMap::new();
                 let mut child_events = HashMap::new();
@@ -477,6 +555,8 @@ named! { relm_widget(typ: Path) -> ChildItem, do_parse!(
                             let events = relm_widget.events.entry(ident).or_insert_with(Vec::new);
                             events.push(event);
                         },
+                        Accessible(_, _) => panic!("Unexpected accessible property in relm widget"),
+                        StyleClass(_, _) => panic!("Unexpected style class in relm widget"),
                     }
                 }
                 ChildWidget(Widget::new_relm(relm_widget, typ.clone(), init_parameters, children, properties, child_properties,
@@ -486,8 +566,10 @@ named! { relm_widget(typ: Path) -> ChildItem, do_parse!(
             widget
                 .map(|(_, widget)| widget)
                 .unwrap_or_else(|| {
-                    let init_parameters = init_parameters.unwrap_or_else(Vec::new);
-                    ChildWidget(Widget::new_relm(RelmWidget::new(), typ, init_parameters, vec![], HashMap::new(),
+                    let (init_parameters, named_parameters) = parameters;
+                    let mut relm_widget = RelmWidget::new();
+                    relm_widget.named_parameters = named_parameters;
+                    ChildWidget(Widget::new_relm(relm_widget, typ, init_parameters, vec![], HashMap::new(),
                         HashMap::new(), HashMap::new()))
                 })
         }) >>
@@ -696,9 +778,44 @@ named! { event -> Event, do_parse!(
     })
 )}
 
+// A style class toggled by a boolean expression, like `class error: self.model.invalid` or
+// `class "error-text": true`.
+named! { style_class -> ChildItem, do_parse!(
+    call!(tag, "class".to_string()) >>
+    name: alt!
+        ( map!(syn!(Ident), |ident| ident.to_string())
+        | map!(syn!(LitStr), |lit| lit.value())
+        ) >>
+    punct!(:) >>
+    value: value >>
+    (StyleClass(name, value.value))
+)}
+
+pub fn is_accessible_relation(kind: &Ident) -> bool {
+    kind.as_ref() == "described_by" || kind.as_ref() == "labelled_by"
+}
+
+// An accessibility property, like `accessible name: "Save"` or `accessible labelled_by: label`.
+named! { accessible -> ChildItem, do_parse!(
+    call!(tag, "accessible".to_string()) >>
+    kind: syn!(Ident) >>
+    punct!(:) >>
+    value: value >>
+    ({
+        match kind.as_ref() {
+            "description" | "described_by" | "labelled_by" | "name" | "role" => (),
+            _ => panic!("Unknown accessible property {}, expecting description, described_by, labelled_by, name or role",
+                        kind),
+        }
+        Accessible(kind, value.value)
+    })
+)}
+
 named! { child_gtk_item -> ChildItem,
     alt!
-    ( gtk_child_property_or_event
+    ( accessible
+    | style_class
+    | gtk_child_property_or_event
     | map!(call!(child_widget, DontSave), |(widget, _)| widget)
     )
 }
@@ -713,6 +830,93 @@ named! { child_properties -> HashMap<Ident, Expr>, map!(
     |properties| properties.into_iter().collect())
 }
 
+// Arguments of `bind!(source.property, options…)`.
+struct BindArguments {
+    options: Vec<Expr>,
+    source: ExprField,
+}
+
+named! { bind_arguments -> BindArguments, do_parse!(
+    source: syn!(Expr) >>
+    options: map!(option!(do_parse!(
+        punct!(,) >>
+        options: expr_list >>
+        (options)
+    )), |options| options.unwrap_or_else(Vec::new)) >>
+    (match source {
+        Expr::Field(source) => BindArguments {
+            options,
+            source,
+        },
+        _ => panic!("Expecting bind!(widget.property)"),
+    })
+)}
+
+impl syn::synom::Synom for BindArguments {
+    named! { parse -> Self, alt!
+        ( map!(parens!(bind_arguments), |(_, arguments)| arguments)
+        | bind_arguments
+        )
+    }
+}
+
+// Properties whose value is `bind!(source.property, options…)` are bound with a GBinding instead
+// of being set from the model.
+fn extract_bindings(properties: &mut HashMap<Ident, Expr>) -> Vec<PropertyBinding> {
+    let names: Vec<_> = properties.iter()
+        .filter(|&(_, value)| is_bind_macro(value))
+        .map(|(name, _)| name.clone())
+        .collect();
+    let mut bindings = vec![];
+    for property in names {
+        if let Some(Expr::Macro(ExprMacro { mac, .. })) = properties.remove(&property) {
+            let arguments: BindArguments = parse2(mac.tts).expect("Expecting bind!(widget.property, options…)");
+            for option in &arguments.options {
+                if !is_binding_option(option) {
+                    panic!("Expecting a method of the binding builder, like bidirectional() or invert_boolean(), \
+                            as option of bind!(), found {}", quote! { #option });
+                }
+            }
+            let source_property =
+                match arguments.source.member {
+                    Named(ident) => ident,
+                    _ => panic!("Expecting a property name in bind!()"),
+                };
+            bindings.push(PropertyBinding {
+                options: arguments.options,
+                property,
+                source: *arguments.source.base,
+                source_property,
+            });
+        }
+    }
+    bindings
+}
+
+// The options of bind!() are called as methods of the binding builder.
+fn is_binding_option(expr: &Expr) -> bool {
+    match *expr {
+        Expr::Call(ExprCall { ref func, .. }) =>
+            if let Expr::Path(ExprPath { ref path, .. }) = **func {
+                path.segments.len() == 1 && path.leading_colon.is_none()
+            }
+            else {
+                false
+            },
+        Expr::MethodCall(_) => true,
+        _ => false,
+    }
+}
+
+fn is_bind_macro(expr: &Expr) -> bool {
+    if let Expr::Macro(ExprMacro { ref mac, .. }) = *expr {
+        mac.path.segments.len() == 1 && mac.path.segments[0].ident.as_ref() == "bind"
+    }
+    else {
+        false
+    }
+}
+
 fn wild_pat() -> Pat {
     parse(quote! {
         _
@@ -773,6 +977,10 @@ fn adjust_widget_with_attributes(mut widget: ChildItem, attributes: &HashMap<Str
             widget.is_container = !widget.children.is_empty();
             widget.container_type = container_type;
             parent_id = attributes.get("parent").and_then(|opt_str| opt_str.as_ref().map(|lit| lit.value()));
+            if let Gtk(ref mut gtk_widget) = widget.widget {
+                gtk_widget.drag_source = attributes.get("drag_source").and_then(|targets| targets.clone());
+                gtk_widget.drop_target = attributes.get("drop_target").and_then(|targets| targets.clone());
+            }
         },
         _ => panic!("Expecting widget"),
     }
@@ -799,3 +1007,168 @@ pub fn respan_with(tokens: proc_macro::TokenStream, span: proc_macro::Span) -> p
     }
     FromIterator::from_iter(result.into_iter())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use proc_macro2::TokenStream;
+    use syn::Expr;
+
+    use super::{GtkWidget, Widget, parse_widget};
+    use super::EitherWidget::{Gtk, Relm};
+
+    fn parse_view(view: &str) -> Widget {
+        parse_widget(TokenStream::from_str(view).expect("view tokens"))
+    }
+
+    fn gtk_widget(widget: &Widget) -> &GtkWidget {
+        match widget.widget {
+            Gtk(ref gtk_widget) => gtk_widget,
+            Relm(_) => panic!("Expected gtk widget"),
+        }
+    }
+
+    fn to_string(expr: &Expr) -> String {
+        quote! { #expr }.to_string().chars()
+            .filter(|char| !char.is_whitespace())
+            .collect()
+    }
+
+    #[test]
+    fn bind() {
+        let widget = parse_view("gtk::Label {
+            text: bind!(entry.text, bidirectional()),
+            visible: bind!(check_button.active, invert_boolean(), transform_to(|value| Some(value.clone()))),
+            selectable: true,
+        }");
+        assert_eq!(widget.properties.len(), 1);
+        assert!(widget.properties.keys().any(|property| property.as_ref() == "selectable"));
+        let mut bindings: Vec<_> = widget.bindings.iter().collect();
+        bindings.sort_by_key(|binding| binding.property.to_string());
+        assert_eq!(bindings.len(), 2);
+        assert_eq!(bindings[0].property.as_ref(), "text");
+        assert_eq!(to_string(&bindings[0].source), "entry");
+        assert_eq!(bindings[0].source_property.as_ref(), "text");
+        assert_eq!(bindings[0].options.len(), 1);
+        assert_eq!(to_string(&bindings[0].options[0]), "bidirectional()");
+        assert_eq!(bindings[1].property.as_ref(), "visible");
+        assert_eq!(to_string(&bindings[1].source), "check_button");
+        assert_eq!(bindings[1].source_property.as_ref(), "active");
+        assert_eq!(bindings[1].options.len(), 2);
+        assert_eq!(to_string(&bindings[1].options[0]), "invert_boolean()");
+        assert_eq!(to_string(&bindings[1].options[1]), "transform_to(|value|Some(value.clone()))");
+    }
+
+    #[test]
+    #[should_panic(expected = "Expecting bind!(widget.property)")]
+    fn bind_without_property() {
+        let _ = parse_view("gtk::Label {
+            text: bind!(entry),
+        }");
+    }
+
+    #[test]
+    #[should_panic(expected = "Expecting a method of the binding builder")]
+    fn bind_with_flags() {
+        let _ = parse_view("gtk::Label {
+            visible: bind!(check_button.active, ::gtk::BindingFlags::INVERT_BOOLEAN),
+        }");
+    }
+
+    #[test]
+    #[should_panic(expected = "Expecting a method of the binding builder")]
+    fn bind_with_function_path() {
+        let _ = parse_view("gtk::Label {
+            visible: bind!(check_button.active, ::relm::invert_boolean()),
+        }");
+    }
+
+    #[test]
+    fn relm_parameters() {
+        let widget = parse_view("gtk::Box {
+            Counter(step: 2, label: \"Big\".to_string(),),
+            Counter(2, \"Big\"),
+            Counter {},
+        }");
+        let parameters = widget.children.iter()
+            .map(|child| match child.widget {
+                Gtk(_) => panic!("Expected relm widget"),
+                Relm(ref relm_widget) => {
+                    let named_parameters: Vec<_> = relm_widget.named_parameters.iter()
+                        .map(|&(ref name, ref value)| format!("{}={}", name, to_string(value)))
+                        .collect();
+                    let init_parameters: Vec<_> = child.init_parameters.iter().map(to_string).collect();
+                    (init_parameters, named_parameters)
+                },
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(parameters, vec![
+            (vec![], vec!["step=2".to_string(), "label=\"Big\".to_string()".to_string()]),
+            (vec!["2".to_string(), "\"Big\"".to_string()], vec![]),
+            (vec![], vec![]),
+        ]);
+    }
+
+    #[test]
+    fn tr() {
+        let widget = parse_view("gtk::Button {
+            accessible description: tr!(\"Save the file\"),
+            accessible name: \"Save\",
+            label: tr!(\"Save\"),
+            tooltip_text: \"Save\",
+        }");
+        assert_eq!(widget.translated_properties.len(), 1);
+        assert_eq!(widget.translated_properties[0].as_ref(), "label");
+        let gtk_widget = gtk_widget(&widget);
+        assert_eq!(gtk_widget.translated_accessible.len(), 1);
+        assert_eq!(gtk_widget.translated_accessible[0].as_ref(), "description");
+        assert_eq!(to_string(&gtk_widget.accessible[0].1), "::relm::gettext(\"Savethefile\")");
+    }
+
+    #[test]
+    fn style_class() {
+        let widget = parse_view("gtk::Entry {
+            class error: self.model.invalid,
+            class \"large-text\": true,
+            text: \"\",
+        }");
+        let style_classes = &gtk_widget(&widget).style_classes;
+        assert_eq!(style_classes.len(), 2);
+        assert_eq!(style_classes[0].0, "error");
+        assert_eq!(to_string(&style_classes[0].1), "self.model.invalid");
+        assert_eq!(style_classes[1].0, "large-text");
+        assert_eq!(to_string(&style_classes[1].1), "true");
+        assert_eq!(widget.properties.len(), 1);
+    }
+
+    #[test]
+    fn accessible() {
+        let widget = parse_view("gtk::Box {
+            accessible role: \"heading\",
+            accessible labelled_by: label,
+            #[name=\"label\"]
+            gtk::Label {
+                accessible name: \"Title\",
+            },
+        }");
+        let accessible = &gtk_widget(&widget).accessible;
+        assert_eq!(accessible.len(), 2);
+        assert_eq!(accessible[0].0.as_ref(), "role");
+        assert_eq!(to_string(&accessible[0].1), "\"heading\"");
+        assert_eq!(accessible[1].0.as_ref(), "labelled_by");
+        assert_eq!(to_string(&accessible[1].1), "label");
+        assert_eq!(widget.children.len(), 1);
+        let accessible = &gtk_widget(&widget.children[0]).accessible;
+        assert_eq!(accessible[0].0.as_ref(), "name");
+        assert_eq!(to_string(&accessible[0].1), "\"Title\"");
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown accessible property label")]
+    fn unknown_accessible_property() {
+        let _ = parse_view("gtk::Button {
+            accessible label: \"Save\",
+        }");
+    }
+}
@@ -0,0 +1,182 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Translator to transform the tr!() macros by calls to the gettext functions.
+
+use std::collections::HashMap;
+
+use syn::{
+    Expr,
+    ExprMacro,
+    Ident,
+    LitStr,
+    parse2,
+};
+use syn::fold::{Fold, fold_expr};
+use syn::spanned::Spanned;
+
+// Arguments of `tr!(context = "context", "message", "plural message", n)`.
+struct TrArguments {
+    context: Option<LitStr>,
+    msgid: LitStr,
+    plural: Option<(LitStr, Expr)>,
+}
+
+named! { context -> LitStr, do_parse!(
+    name: syn!(Ident) >>
+    cond_reduce!(name.as_ref() == "context") >>
+    punct!(=) >>
+    context: syn!(LitStr) >>
+    punct!(,) >>
+    (context)
+)}
+
+impl ::syn::synom::Synom for TrArguments {
+    named! { parse -> Self, do_parse!(
+        context: option!(context) >>
+        msgid: syn!(LitStr) >>
+        plural: option!(do_parse!(
+            punct!(,) >>
+            plural: syn!(LitStr) >>
+            punct!(,) >>
+            count: syn!(Expr) >>
+            (plural, count)
+        )) >>
+        option!(punct!(,)) >>
+        (TrArguments {
+            context,
+            msgid,
+            plural,
+        })
+    )}
+}
+
+struct Translator {
+    found: bool,
+}
+
+impl Fold for Translator {
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        if let Expr::Macro(ExprMacro { ref mac, .. }) = expr {
+            if mac.path.segments.len() == 1 && mac.path.segments[0].ident.as_ref() == "tr" {
+                self.found = true;
+                let arguments: TrArguments = parse2(mac.tts.clone())
+                    .expect("Expecting tr!([context = \"context\",] \"message\"[, \"plural message\", count])");
+                let msgid = &arguments.msgid;
+                let tokens =
+                    match (arguments.context, arguments.plural) {
+                        (None, None) => quote_spanned! { expr.span() =>
+                            ::relm::gettext(#msgid)
+                        },
+                        (Some(context), None) => quote_spanned! { expr.span() =>
+                            ::relm::pgettext(#context, #msgid)
+                        },
+                        (None, Some((plural, count))) => {
+                            let count = self.fold_expr(count);
+                            quote_spanned! { expr.span() =>
+                                ::relm::ngettext(#msgid, #plural, (#count) as u64)
+                            }
+                        },
+                        (Some(context), Some((plural, count))) => {
+                            let count = self.fold_expr(count);
+                            quote_spanned! { expr.span() =>
+                                ::relm::npgettext(#context, #msgid, #plural, (#count) as u64)
+                            }
+                        },
+                    };
+                return parse2(tokens.into()).expect("gettext call");
+            }
+        }
+        fold_expr(self, expr)
+    }
+}
+
+/*
+ * Replace the tr!() macros in the property values by calls to the gettext functions, so that the
+ * model attributes used in them are found, and return the names of the translated properties.
+ */
+pub fn translate_properties(properties: &mut HashMap<Ident, Expr>) -> Vec<Ident> {
+    let mut translated_properties = vec![];
+    for (name, value) in properties.iter_mut() {
+        if translate_value(value) {
+            translated_properties.push(name.clone());
+        }
+    }
+    translated_properties
+}
+
+// Replace the tr!() macros in `value` and return whether there was one.
+pub fn translate_value(value: &mut Expr) -> bool {
+    let mut translator = Translator {
+        found: false,
+    };
+    *value = translator.fold_expr(value.clone());
+    translator.found
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::str::FromStr;
+
+    use proc_macro2::{Span, TokenStream};
+    use syn::{Expr, Ident, parse2};
+
+    use super::translate_properties;
+
+    // Translate the value of a property and return the new value, without whitespaces, if it was
+    // translated.
+    fn translate(value: &str) -> Option<String> {
+        let value: Expr = parse2(TokenStream::from_str(value).expect("value tokens")).expect("value");
+        let mut properties = HashMap::new();
+        let _ = properties.insert(Ident::new("label", Span::call_site()), value);
+        let translated_properties = translate_properties(&mut properties);
+        let value = properties.values().next().expect("property value");
+        let value: String = quote! { #value }.to_string().chars()
+            .filter(|char| !char.is_whitespace())
+            .collect();
+        if translated_properties.is_empty() {
+            None
+        }
+        else {
+            assert_eq!(translated_properties[0].as_ref(), "label");
+            Some(value)
+        }
+    }
+
+    #[test]
+    fn tr() {
+        assert_eq!(translate("\"Save\""), None);
+        assert_eq!(translate("tr!(\"Save\")"), Some("::relm::gettext(\"Save\")".to_string()));
+        assert_eq!(translate("tr!(context = \"menu\", \"Open\")"),
+            Some("::relm::pgettext(\"menu\",\"Open\")".to_string()));
+        assert_eq!(translate("tr!(\"One file\", \"{} files\", self.model.count)"),
+            Some("::relm::ngettext(\"Onefile\",\"{}files\",(self.model.count)asu64)".to_string()));
+        assert_eq!(translate("tr!(context = \"list\", \"One file\", \"{} files\", self.model.count,)"),
+            Some("::relm::npgettext(\"list\",\"Onefile\",\"{}files\",(self.model.count)asu64)".to_string()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Expecting tr!")]
+    fn tr_without_message() {
+        let _ = translate("tr!(context = \"menu\")");
+    }
+}
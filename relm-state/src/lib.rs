@@ -35,6 +35,8 @@
 
 extern crate futures;
 extern crate futures_glib;
+#[cfg(feature = "std_future")]
+extern crate futures_util;
 #[macro_use]
 extern crate log;
 extern crate relm_core;
@@ -43,43 +45,47 @@ mod into;
 mod macros;
 mod stream;
 
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
 use std::time::SystemTime;
 
 use futures::{Future, Stream};
 use futures::future::Executor as FutureExecutor;
 use futures_glib::{Executor, MainContext};
-pub use relm_core::EventStream;
+pub use relm_core::{EventStream, WeakEventStream};
 
 pub use into::{IntoOption, IntoPair};
-use stream::ToStream;
+use stream::{ToFuture, ToStream};
 
 macro_rules! relm_connect {
     ($_self:expr, $to_stream:expr, $success_callback:expr, $failure_callback:expr) => {{
-        let event_stream = $_self.stream.clone();
-        let fail_event_stream = $_self.stream.clone();
+        // NOTE: only keep weak handles to the stream so that the future does not keep the
+        // component alive. The future stops when the stream is dropped.
+        let event_stream = $_self.stream.downgrade();
+        let fail_event_stream = $_self.stream.downgrade();
         let stream = $to_stream.to_stream();
         stream.map_err(move |error| {
             fail_event_stream.emit($failure_callback(error));
             ()
         })
             .for_each(move |result| {
+                let event_stream = event_stream.upgrade().ok_or(())?;
                 event_stream.emit($success_callback(result));
-                Ok::<(), STREAM::Error>(())
-            }
-            .map_err(|_| ()))
+                Ok(())
+            })
     }};
 }
 
 macro_rules! relm_connect_ignore {
     ($_self:expr, $to_stream:expr, $success_callback:expr) => {{
-        let event_stream = $_self.stream.clone();
+        let event_stream = $_self.stream.downgrade();
         let stream = $to_stream.to_stream();
         stream.map_err(|_| ())
             .for_each(move |result| {
+                let event_stream = event_stream.upgrade().ok_or(())?;
                 event_stream.emit($success_callback(result));
-                Ok::<(), STREAM::Error>(())
-            }
-            .map_err(|_| ()))
+                Ok(())
+            })
     }};
 }
 
@@ -169,6 +175,13 @@ impl<UPDATE: Update> Relm<UPDATE> {
     }
 
     /// Connect the future `to_stream` and spawn it on the tokio main loop.
+    ///
+    /// With the `std_future` feature, `to_stream` can also be a `std::future::Future` (like an
+    /// `async` block) or a `Stream` from futures 0.3, as long as it produces `Result`s:
+    ///
+    /// ```ignore
+    /// relm.connect_exec(async { fetch(url).await }, Msg::Done, Msg::Failed);
+    /// ```
     pub fn connect_exec<CALLBACK, FAILCALLBACK, STREAM, TOSTREAM>(&self, to_stream: TOSTREAM, callback: CALLBACK,
             failure_callback: FAILCALLBACK)
         where CALLBACK: Fn(STREAM::Item) -> UPDATE::Msg + 'static,
@@ -193,9 +206,15 @@ impl<UPDATE: Update> Relm<UPDATE> {
     }
 
     /// Spawn a future in the tokio event loop.
-    pub fn exec<FUTURE: Future<Item=(), Error=()> + 'static>(&self, future: FUTURE) {
+    ///
+    /// With the `std_future` feature, `future` can also be a `std::future::Future` with an `Output`
+    /// of `()`, like an `async` block.
+    pub fn exec<FUTURE, TOFUTURE>(&self, future: TOFUTURE)
+        where FUTURE: Future<Item=(), Error=()> + 'static,
+              TOFUTURE: ToFuture<FUTURE>,
+    {
         // NOTE: no error can be returned from execute(), hence unwrap().
-        self.executor.execute(future).unwrap();
+        self.executor.execute(future.to_future()).unwrap();
     }
 
     /// Get the handle of this stream.
@@ -224,6 +243,14 @@ pub trait Update
     /// The type of the messages sent to the [`update()`](trait.Update.html#tymethod.update) method.
     type Msg;
 
+    /// Method called once the component is created and ready to receive messages.
+    ///
+    /// Unlike `model()` and `view()`, it has access to the whole component, so this is where to do
+    /// the initialization that needs the widgets, or to send the initial messages, which are
+    /// dispatched in order after the ones sent while creating the component.
+    fn init(&mut self, _relm: &Relm<Self>) {
+    }
+
     /// Create the initial model.
     fn model(relm: &Relm<Self>, param: Self::ModelParam) -> Self::Model;
 
@@ -286,19 +313,35 @@ where UPDATE: Update + UpdateNew + 'static
 
 /// Initialize a component by creating its subscriptions and dispatching the messages from the
 /// stream.
-pub fn init_component<UPDATE>(stream: &EventStream<UPDATE::Msg>, mut component: UPDATE, executor: &Executor,
+pub fn init_component<UPDATE>(stream: &EventStream<UPDATE::Msg>, component: UPDATE, executor: &Executor,
     relm: &Relm<UPDATE>)
     where UPDATE: Update + 'static,
           UPDATE::Msg: DisplayVariant + 'static,
+{
+    let _ = init_shared_component(stream, component, executor, relm);
+}
+
+/// Same as [`init_component()`](fn.init_component.html), but return a weak reference to the
+/// component, which can be used to access it outside of the `update()` method.
+/// The component is borrowed mutably while it is updated.
+#[doc(hidden)]
+pub fn init_shared_component<UPDATE>(stream: &EventStream<UPDATE::Msg>, mut component: UPDATE, executor: &Executor,
+    relm: &Relm<UPDATE>) -> Weak<RefCell<UPDATE>>
+    where UPDATE: Update + 'static,
+          UPDATE::Msg: DisplayVariant + 'static,
 {
     let stream = stream.clone();
     component.subscriptions(relm);
+    component.init(relm);
+    let component = Rc::new(RefCell::new(component));
+    let weak_component = Rc::downgrade(&component);
     let event_future = stream.for_each(move |event| {
-        update_component(&mut component, event);
+        update_component(&mut *component.borrow_mut(), event);
         Ok(())
     });
     // NOTE: no error can be returned from execute(), hence unwrap().
     executor.execute(event_future).unwrap();
+    weak_component
 }
 
 fn update_component<COMPONENT>(component: &mut COMPONENT, event: COMPONENT::Msg)
@@ -33,11 +33,16 @@
 /// 3. Send `$msg` when the GTK+ `$event` is emitted on `$widget`.
 ///
 /// 4. Send `$msg` to `$widget` when the `$message` is received on `$stream`.
+///
+/// The callbacks only keep weak handles to the streams they send messages to, so connecting
+/// components together does not create reference cycles.
+/// `$other_component` can be either a `Component` or a `WeakComponent`.
+/// Use `connect_stream!` if the callback needs to keep the stream alive.
 #[macro_export]
 macro_rules! connect {
     // Connect to a GTK+ widget event, sending a message to another widget.
     ($widget:expr, $event:ident($($args:pat),*), $other_component:expr, $msg:expr) => {
-        connect_stream!($widget, $event($($args),*), $other_component.stream(), $msg);
+        connect_stream!($widget, $event($($args),*), $other_component.stream().downgrade(), $msg);
     };
 
     // Connect to a GTK+ widget event.
@@ -45,15 +50,15 @@ macro_rules! connect {
     // ReturnValue) where the ReturnValue is the value to return in the GTK+ callback.
     // Option<MSG> can be None if no message needs to be emitted.
     ($relm:expr, $widget:expr, $event:ident($($args:pat),*), return $msg:expr) => {{
-        connect_stream!(return $relm.stream(), $widget, $event($($args),*), $msg);
+        connect_stream!(return $relm.stream().downgrade(), $widget, $event($($args),*), $msg);
     }};
 
     // Connect to a GTK+ widget event.
     ($relm:expr, $widget:expr, $event:ident($($args:pat),*), $msg:expr) => {{
-        let stream = $relm.stream().clone();
+        let stream = $relm.stream().downgrade();
         let _ = $widget.$event(move |$($args),*| {
             let msg: Option<_> = $crate::IntoOption::into_option($msg);
-            if let Some(msg) = msg {
+            if let (Some(msg), Some(stream)) = (msg, stream.upgrade()) {
                 stream.emit(msg);
             }
         });
@@ -63,7 +68,7 @@ macro_rules! connect {
     // TODO: create another macro rule accepting multiple patterns.
     ($src_component:ident @ $message:pat, $dst_component:expr, $msg:expr) => {
         let stream = $src_component.stream().clone();
-        connect_stream!(stream@$message, $dst_component.stream(), $msg);
+        connect_stream!(stream@$message, $dst_component.stream().downgrade(), $msg);
     };
 }
 
@@ -74,6 +79,11 @@ macro_rules! connect {
 /// 1. Send `$msg` to `$other_stream` when the GTK+ `$event` is emitted on `$widget`.
 ///
 /// 2. Send `$msg` to `$widget` when the `$message` is received on `$stream`.
+///
+/// The callbacks keep a clone of the destination stream, so an `EventStream` is kept alive as long
+/// as the callbacks are connected.
+/// Pass a `WeakEventStream` (from `EventStream::downgrade()`) to only send the messages while the
+/// stream is alive.
 #[macro_export]
 macro_rules! connect_stream {
     // Connect to a GTK+ widget event.
@@ -162,8 +172,8 @@ macro_rules! connect_async {
             }
         });
 
-        let event_stream = $relm.stream().clone();
-        let fail_event_stream = $relm.stream().clone();
+        let event_stream = $relm.stream().downgrade();
+        let fail_event_stream = $relm.stream().downgrade();
         let future =
             rx.for_each(move |result| {
                 match result {
@@ -216,8 +226,8 @@ macro_rules! connect_async_func {
             }
         });
 
-        let event_stream = $relm.stream().clone();
-        let fail_event_stream = $relm.stream().clone();
+        let event_stream = $relm.stream().downgrade();
+        let fail_event_stream = $relm.stream().downgrade();
         let future =
             rx.for_each(move |result| {
                 match result {
@@ -271,8 +281,8 @@ macro_rules! connect_async_full {
             }
         });
 
-        let event_stream = $relm.stream().clone();
-        let fail_event_stream = $relm.stream().clone();
+        let event_stream = $relm.stream().downgrade();
+        let fail_event_stream = $relm.stream().downgrade();
         let future =
             rx.for_each(move |result| {
                 match result {
@@ -327,8 +337,8 @@ macro_rules! connect_async_func_full {
             }
         });
 
-        let event_stream = $relm.stream().clone();
-        let fail_event_stream = $relm.stream().clone();
+        let event_stream = $relm.stream().downgrade();
+        let fail_event_stream = $relm.stream().downgrade();
         let future =
             rx.for_each(move |result| {
                 match result {
@@ -19,7 +19,20 @@
  * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
  */
 
+#[cfg(feature = "std_future")]
+use std::future::Future as StdFuture;
+#[cfg(feature = "std_future")]
+use std::pin::Pin;
+
 use futures::{Future, IntoStream, Poll, Stream};
+#[cfg(feature = "std_future")]
+use futures_util::FutureExt;
+#[cfg(feature = "std_future")]
+use futures_util::compat::Compat;
+#[cfg(feature = "std_future")]
+use futures_util::future::UnitError;
+#[cfg(feature = "std_future")]
+use futures_util::stream::Stream as StdStream;
 
 pub struct RelmStream<ERROR, ITEM, STREAM: Stream<Item=ITEM, Error=ERROR>> {
     stream: STREAM,
@@ -62,3 +75,48 @@ impl<ERROR, FUTURE: Future<Item=ITEM, Error=ERROR>, ITEM> ToStream<IntoStream<FU
         }
     }
 }
+
+#[cfg(feature = "std_future")]
+impl<ERROR, FUTURE, ITEM> ToStream<IntoStream<Compat<Pin<Box<FUTURE>>>>> for FUTURE
+    where FUTURE: StdFuture<Output=Result<ITEM, ERROR>>,
+{
+    type Error = ERROR;
+    type Item = ITEM;
+
+    fn to_stream(self) -> RelmStream<ERROR, ITEM, IntoStream<Compat<Pin<Box<FUTURE>>>>> {
+        RelmStream {
+            stream: Compat::new(Box::pin(self)).into_stream(),
+        }
+    }
+}
+
+#[cfg(feature = "std_future")]
+impl<ERROR, ITEM, STREAM> ToStream<Compat<Pin<Box<STREAM>>>> for STREAM
+    where STREAM: StdStream<Item=Result<ITEM, ERROR>>,
+{
+    type Error = ERROR;
+    type Item = ITEM;
+
+    fn to_stream(self) -> RelmStream<ERROR, ITEM, Compat<Pin<Box<STREAM>>>> {
+        RelmStream {
+            stream: Compat::new(Box::pin(self)),
+        }
+    }
+}
+
+pub trait ToFuture<FUTURE: Future<Item=(), Error=()>> {
+    fn to_future(self) -> FUTURE;
+}
+
+impl<FUTURE: Future<Item=(), Error=()>> ToFuture<FUTURE> for FUTURE {
+    fn to_future(self) -> FUTURE {
+        self
+    }
+}
+
+#[cfg(feature = "std_future")]
+impl<FUTURE: StdFuture<Output=()>> ToFuture<Compat<Pin<Box<UnitError<FUTURE>>>>> for FUTURE {
+    fn to_future(self) -> Compat<Pin<Box<UnitError<FUTURE>>>> {
+        Compat::new(Box::pin(self.unit_error()))
+    }
+}
@@ -0,0 +1,510 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Extract the messages of the `tr!()` macros, in Rust source files and `.relm` view files, into a
+//! `.pot` file to be translated.
+//!
+//! Usage: `relm-xgettext [-o messages.pot] <file or directory>…`
+
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::iter::Peekable;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::str::Chars;
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Punct(char),
+    Str(String),
+}
+
+#[derive(Debug, PartialEq)]
+struct Message {
+    context: Option<String>,
+    msgid: String,
+    plural: Option<String>,
+    references: Vec<String>,
+}
+
+struct Lexer<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(source: &'a str) -> Self {
+        Lexer {
+            chars: source.chars().peekable(),
+            line: 1,
+        }
+    }
+
+    fn next_char(&mut self) -> Option<char> {
+        let character = self.chars.next();
+        if character == Some('\n') {
+            self.line += 1;
+        }
+        character
+    }
+
+    fn skip_block_comment(&mut self) {
+        let mut depth = 1;
+        while let Some(character) = self.next_char() {
+            match character {
+                '*' if self.chars.peek() == Some(&'/') => {
+                    let _ = self.next_char();
+                    depth -= 1;
+                    if depth == 0 {
+                        return;
+                    }
+                },
+                '/' if self.chars.peek() == Some(&'*') => {
+                    let _ = self.next_char();
+                    depth += 1;
+                },
+                _ => (),
+            }
+        }
+    }
+
+    // Check whether the next characters start a raw string, after its `r` prefix.
+    fn is_raw_string(&self) -> bool {
+        let mut chars = self.chars.clone();
+        while chars.peek() == Some(&'#') {
+            let _ = chars.next();
+        }
+        chars.peek() == Some(&'"')
+    }
+
+    fn ident(&mut self, first_character: char) -> String {
+        let mut ident = first_character.to_string();
+        while let Some(&character) = self.chars.peek() {
+            if character.is_alphanumeric() || character == '_' {
+                ident.push(character);
+                let _ = self.next_char();
+            }
+            else {
+                break;
+            }
+        }
+        ident
+    }
+
+    fn raw_string(&mut self) -> String {
+        let mut hashes = 0;
+        while self.chars.peek() == Some(&'#') {
+            let _ = self.next_char();
+            hashes += 1;
+        }
+        let _ = self.next_char(); // Opening quote.
+        let mut string = String::new();
+        while let Some(character) = self.next_char() {
+            if character == '"' {
+                let mut closing_hashes = 0;
+                while closing_hashes < hashes && self.chars.peek() == Some(&'#') {
+                    let _ = self.next_char();
+                    closing_hashes += 1;
+                }
+                if closing_hashes == hashes {
+                    break;
+                }
+                string.push('"');
+                string.extend((0..closing_hashes).map(|_| '#'));
+            }
+            else {
+                string.push(character);
+            }
+        }
+        string
+    }
+
+    fn string(&mut self) -> String {
+        let mut string = String::new();
+        while let Some(character) = self.next_char() {
+            match character {
+                '"' => break,
+                '\\' => match self.next_char() {
+                    Some('n') => string.push('\n'),
+                    Some('r') => string.push('\r'),
+                    Some('t') => string.push('\t'),
+                    Some('0') => string.push('\0'),
+                    Some('x') => {
+                        let code: String = (0..2).filter_map(|_| self.next_char()).collect();
+                        if let Ok(code) = u8::from_str_radix(&code, 16) {
+                            string.push(code as char);
+                        }
+                    },
+                    Some('u') => {
+                        let mut code = String::new();
+                        while let Some(character) = self.next_char() {
+                            match character {
+                                '{' => (),
+                                '}' => break,
+                                _ => code.push(character),
+                            }
+                        }
+                        if let Some(character) = u32::from_str_radix(&code, 16).ok().and_then(::std::char::from_u32) {
+                            string.push(character);
+                        }
+                    },
+                    // A backslash at the end of a line skips the following whitespaces.
+                    Some('\n') => {
+                        while self.chars.peek().map_or(false, |character| character.is_whitespace()) {
+                            let _ = self.next_char();
+                        }
+                    },
+                    Some(character) => string.push(character),
+                    None => break,
+                },
+                _ => string.push(character),
+            }
+        }
+        string
+    }
+
+    // Skip a character literal or a lifetime.
+    fn skip_quote(&mut self) {
+        match self.next_char() {
+            Some('\\') => {
+                while let Some(character) = self.next_char() {
+                    if character == '\'' {
+                        break;
+                    }
+                }
+            },
+            Some(_) if self.chars.peek() == Some(&'\'') => {
+                let _ = self.next_char();
+            },
+            _ => (),
+        }
+    }
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = (Token, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let character = self.next_char()?;
+            let line = self.line;
+            match character {
+                '/' if self.chars.peek() == Some(&'/') => {
+                    while let Some(character) = self.next_char() {
+                        if character == '\n' {
+                            break;
+                        }
+                    }
+                },
+                '/' if self.chars.peek() == Some(&'*') => {
+                    let _ = self.next_char();
+                    self.skip_block_comment();
+                },
+                '"' => return Some((Token::Str(self.string()), line)),
+                '\'' => self.skip_quote(),
+                _ if character.is_alphabetic() || character == '_' => {
+                    let ident = self.ident(character);
+                    // Raw strings and byte strings.
+                    if (ident == "r" || ident == "br") && self.is_raw_string() {
+                        return Some((Token::Str(self.raw_string()), line));
+                    }
+                    if ident == "b" && self.chars.peek() == Some(&'"') {
+                        let _ = self.next_char();
+                        return Some((Token::Str(self.string()), line));
+                    }
+                    // Raw identifiers, like r#type.
+                    if ident == "r" && self.chars.peek() == Some(&'#') {
+                        let _ = self.next_char();
+                        if let Some(character) = self.next_char() {
+                            return Some((Token::Ident(self.ident(character)), line));
+                        }
+                    }
+                    return Some((Token::Ident(ident), line));
+                },
+                _ if character.is_whitespace() || character.is_numeric() => (),
+                _ => return Some((Token::Punct(character), line)),
+            }
+        }
+    }
+}
+
+// Get the arguments of a macro invocation, whose opening delimiter was already consumed.
+fn macro_arguments<I: Iterator<Item=(Token, usize)>>(tokens: &mut I) -> Vec<Vec<Token>> {
+    let mut arguments = vec![vec![]];
+    let mut depth = 0;
+    for (token, _) in tokens {
+        match token {
+            Token::Punct('(') | Token::Punct('[') | Token::Punct('{') => depth += 1,
+            Token::Punct(')') | Token::Punct(']') | Token::Punct('}') => {
+                if depth == 0 {
+                    break;
+                }
+                depth -= 1;
+            },
+            Token::Punct(',') if depth == 0 => {
+                arguments.push(vec![]);
+                continue;
+            },
+            _ => (),
+        }
+        if let Some(argument) = arguments.last_mut() {
+            argument.push(token);
+        }
+    }
+    arguments.retain(|argument| !argument.is_empty());
+    arguments
+}
+
+fn message_from_arguments(mut arguments: Vec<Vec<Token>>, reference: String) -> Option<Message> {
+    let context =
+        match arguments.first().map(Vec::as_slice) {
+            Some(&[Token::Ident(ref name), Token::Punct('='), Token::Str(ref context)]) if name == "context" =>
+                Some(context.clone()),
+            _ => None,
+        };
+    if context.is_some() {
+        let _ = arguments.remove(0);
+    }
+    let mut arguments = arguments.into_iter();
+    let msgid =
+        match arguments.next().as_ref().map(Vec::as_slice) {
+            Some(&[Token::Str(ref msgid)]) => msgid.clone(),
+            _ => return None,
+        };
+    let plural =
+        match arguments.next().as_ref().map(Vec::as_slice) {
+            Some(&[Token::Str(ref plural)]) => Some(plural.clone()),
+            _ => None,
+        };
+    Some(Message {
+        context,
+        msgid,
+        plural,
+        references: vec![reference],
+    })
+}
+
+fn extract_messages(source: &str, path: &Path, messages: &mut Vec<Message>) {
+    let mut tokens = Lexer::new(source);
+    let mut previous = (None, None);
+    while let Some((token, line)) = tokens.next() {
+        let is_invocation =
+            match (&previous, &token) {
+                (&(Some(Token::Ident(ref name)), Some(Token::Punct('!'))), &Token::Punct(delimiter)) =>
+                    name == "tr" && (delimiter == '(' || delimiter == '[' || delimiter == '{'),
+                _ => false,
+            };
+        if is_invocation {
+            let arguments = macro_arguments(&mut tokens);
+            let reference = format!("{}:{}", path.display(), line);
+            if let Some(message) = message_from_arguments(arguments, reference) {
+                add_message(messages, message);
+            }
+            previous = (None, None);
+        }
+        else {
+            previous = (previous.1, Some(token));
+        }
+    }
+}
+
+fn add_message(messages: &mut Vec<Message>, message: Message) {
+    let existing = messages.iter_mut()
+        .find(|existing| existing.context == message.context && existing.msgid == message.msgid);
+    match existing {
+        Some(existing) => {
+            existing.references.extend(message.references);
+            if existing.plural.is_none() {
+                existing.plural = message.plural;
+            }
+        },
+        None => messages.push(message),
+    }
+}
+
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    if path.is_dir() {
+        let mut entries = fs::read_dir(path)?.collect::<Result<Vec<_>, _>>()?;
+        entries.sort_by_key(|entry| entry.path());
+        for entry in entries {
+            collect_files(&entry.path(), files)?;
+        }
+    }
+    else if path.extension().map_or(false, |extension| extension == "rs" || extension == "relm") {
+        files.push(path.to_path_buf());
+    }
+    Ok(())
+}
+
+fn escape(string: &str) -> String {
+    let mut escaped = String::new();
+    for character in string.chars() {
+        match character {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+fn write_pot<W: Write>(output: &mut W, messages: &[Message]) -> io::Result<()> {
+    writeln!(output, "msgid \"\"")?;
+    writeln!(output, "msgstr \"\"")?;
+    writeln!(output, "\"Content-Type: text/plain; charset=UTF-8\\n\"")?;
+    writeln!(output, "\"Content-Transfer-Encoding: 8bit\\n\"")?;
+    writeln!(output, "\"Plural-Forms: nplurals=INTEGER; plural=EXPRESSION;\\n\"")?;
+    for message in messages {
+        writeln!(output)?;
+        for reference in &message.references {
+            writeln!(output, "#: {}", reference)?;
+        }
+        if let Some(ref context) = message.context {
+            writeln!(output, "msgctxt \"{}\"", escape(context))?;
+        }
+        writeln!(output, "msgid \"{}\"", escape(&message.msgid))?;
+        match message.plural {
+            Some(ref plural) => {
+                writeln!(output, "msgid_plural \"{}\"", escape(plural))?;
+                writeln!(output, "msgstr[0] \"\"")?;
+                writeln!(output, "msgstr[1] \"\"")?;
+            },
+            None => writeln!(output, "msgstr \"\"")?,
+        }
+    }
+    Ok(())
+}
+
+fn run() -> io::Result<()> {
+    let mut output_path = None;
+    let mut inputs = vec![];
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "-o" {
+            output_path = args.next();
+        }
+        else {
+            inputs.push(PathBuf::from(arg));
+        }
+    }
+    if inputs.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput,
+            "usage: relm-xgettext [-o messages.pot] <file or directory>…"));
+    }
+
+    let mut files = vec![];
+    for input in &inputs {
+        collect_files(input, &mut files)?;
+    }
+    let mut messages = vec![];
+    for path in &files {
+        let mut source = String::new();
+        let _ = File::open(path)?.read_to_string(&mut source)?;
+        extract_messages(&source, path, &mut messages);
+    }
+
+    match output_path {
+        Some(path) => write_pot(&mut File::create(path)?, &messages),
+        None => write_pot(&mut io::stdout(), &messages),
+    }
+}
+
+fn main() {
+    if let Err(error) = run() {
+        eprintln!("relm-xgettext: {}", error);
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{Message, extract_messages};
+
+    #[test]
+    fn extract() {
+        let source = r#"
+            gtk::Button {
+                // tr!("Commented")
+                label: &tr!(context = "menu", "Open"),
+            },
+            gtk::Label {
+                text: &tr!("One file", "{} files", self.model.files.len()),
+            },
+            let title = tr!("Say \"hello\"");
+            let open = tr!(context = "menu", "Open");
+        "#;
+        let mut messages = vec![];
+        extract_messages(source, Path::new("view.relm"), &mut messages);
+        assert_eq!(messages, vec![
+            Message {
+                context: Some("menu".to_string()),
+                msgid: "Open".to_string(),
+                plural: None,
+                references: vec!["view.relm:4".to_string(), "view.relm:10".to_string()],
+            },
+            Message {
+                context: None,
+                msgid: "One file".to_string(),
+                plural: Some("{} files".to_string()),
+                references: vec!["view.relm:7".to_string()],
+            },
+            Message {
+                context: None,
+                msgid: "Say \"hello\"".to_string(),
+                plural: None,
+                references: vec!["view.relm:9".to_string()],
+            },
+        ]);
+    }
+
+    #[test]
+    fn raw_tokens() {
+        let source = r###"
+            let r#type = tr!(r#"Say "hi""#);
+            let bytes = br#"tr!("Not a message")"#;
+            let byte_string = b"tr!(\"Not a message\")";
+            let plural = tr!(r"One", r##"{} "files""##, count);
+        "###;
+        let mut messages = vec![];
+        extract_messages(source, Path::new("main.rs"), &mut messages);
+        assert_eq!(messages, vec![
+            Message {
+                context: None,
+                msgid: "Say \"hi\"".to_string(),
+                plural: None,
+                references: vec!["main.rs:2".to_string()],
+            },
+            Message {
+                context: None,
+                msgid: "One".to_string(),
+                plural: Some("{} \"files\"".to_string()),
+                references: vec!["main.rs:5".to_string()],
+            },
+        ]);
+    }
+}
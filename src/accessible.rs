@@ -0,0 +1,121 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::ffi::CString;
+
+use atk_sys::{
+    self,
+    AtkObject,
+    AtkRelationType,
+    ATK_RELATION_DESCRIBED_BY,
+    ATK_RELATION_DESCRIPTION_FOR,
+    ATK_RELATION_LABEL_FOR,
+    ATK_RELATION_LABELLED_BY,
+    ATK_ROLE_INVALID,
+};
+use glib::translate::ToGlibPtr;
+use gtk::{self, IsA};
+use gtk_sys;
+
+/// A relation between two accessible widgets.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AccessibleRelation {
+    /// The widget is described by the target, like a label explaining an entry.
+    DescribedBy,
+    /// The widget is labelled by the target, like the label in front of an entry.
+    LabelledBy,
+}
+
+impl AccessibleRelation {
+    fn relation_types(self) -> (AtkRelationType, AtkRelationType) {
+        match self {
+            AccessibleRelation::DescribedBy => (ATK_RELATION_DESCRIBED_BY, ATK_RELATION_DESCRIPTION_FOR),
+            AccessibleRelation::LabelledBy => (ATK_RELATION_LABELLED_BY, ATK_RELATION_LABEL_FOR),
+        }
+    }
+}
+
+fn accessible<WIDGET: IsA<gtk::Widget>>(widget: &WIDGET) -> Option<*mut AtkObject> {
+    let accessible = unsafe { gtk_sys::gtk_widget_get_accessible(widget.to_glib_none().0) };
+    if accessible.is_null() {
+        None
+    }
+    else {
+        Some(accessible)
+    }
+}
+
+/// Add the `relation` from `widget` to `target`, as well as the reciprocal relation from
+/// `target` to `widget`.
+///
+/// This is used by the `accessible labelled_by: widget` and `accessible described_by: widget`
+/// items of the `view!` macro.
+pub fn add_accessible_relation<WIDGET, TARGET>(widget: &WIDGET, relation: AccessibleRelation, target: &TARGET)
+where WIDGET: IsA<gtk::Widget>,
+      TARGET: IsA<gtk::Widget>,
+{
+    if let (Some(accessible), Some(target)) = (accessible(widget), accessible(target)) {
+        let (relation, reciprocal) = relation.relation_types();
+        unsafe {
+            let _ = atk_sys::atk_object_add_relationship(accessible, relation, target);
+            let _ = atk_sys::atk_object_add_relationship(target, reciprocal, accessible);
+        }
+    }
+}
+
+/// Set the description that assistive technologies give for `widget`.
+pub fn set_accessible_description<WIDGET: IsA<gtk::Widget>>(widget: &WIDGET, description: &str) {
+    if let Some(accessible) = accessible(widget) {
+        let description = CString::new(description).expect("accessible description");
+        unsafe {
+            atk_sys::atk_object_set_description(accessible, description.as_ptr());
+        }
+    }
+}
+
+/// Set the name that assistive technologies announce for `widget`.
+pub fn set_accessible_name<WIDGET: IsA<gtk::Widget>>(widget: &WIDGET, name: &str) {
+    if let Some(accessible) = accessible(widget) {
+        let name = CString::new(name).expect("accessible name");
+        unsafe {
+            atk_sys::atk_object_set_name(accessible, name.as_ptr());
+        }
+    }
+}
+
+/// Set the role of `widget`, using the ATK role names, like "heading" or "push button".
+/// A warning is logged and the role is left unchanged if `role` is not a known ATK role.
+pub fn set_accessible_role<WIDGET: IsA<gtk::Widget>>(widget: &WIDGET, role: &str) {
+    let atk_role =
+        match CString::new(role) {
+            Ok(role_name) => unsafe { atk_sys::atk_role_for_name(role_name.as_ptr()) },
+            Err(_) => ATK_ROLE_INVALID,
+        };
+    if atk_role == ATK_ROLE_INVALID {
+        warn!("Unknown accessible role {}", role);
+        return;
+    }
+    if let Some(accessible) = accessible(widget) {
+        unsafe {
+            atk_sys::atk_object_set_role(accessible, atk_role);
+        }
+    }
+}
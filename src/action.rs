@@ -0,0 +1,266 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::cell::RefCell;
+
+use gio::{self, ActionExt, ActionMapExt};
+use glib::{Cast, FromVariant, Object, ObjectExt, StaticVariantType, ToVariant, Variant, WeakRef};
+use gtk::IsA;
+
+use super::{Relm, Update};
+
+thread_local! {
+    // NOTE: the values set from the view before the action is added with Actions.
+    static PENDING_STATES: RefCell<Vec<PendingState>> = RefCell::new(vec![]);
+}
+
+#[derive(Default)]
+struct PendingState {
+    enabled: Option<bool>,
+    state: Option<Variant>,
+}
+
+struct PendingEntry {
+    map: WeakRef<Object>,
+    name: String,
+    state: PendingState,
+}
+
+/// Actions of a `gio::ActionMap`, like a `gtk::ApplicationWindow` (`win.` prefix) or a
+/// `gtk::Application` (`app.` prefix), sending messages to a component when they are activated,
+/// for instance from a `gio::Menu`:
+///
+/// ```ignore
+/// let actions = Actions::new(&self.window, &self.model.relm);
+/// actions.add("save", || Save);
+/// actions.add_stateful("dark-theme", false, DarkTheme);
+/// menu.append("Save", "win.save");
+/// ```
+pub struct Actions<UPDATE: Update> {
+    map: gio::ActionMap,
+    relm: Relm<UPDATE>,
+}
+
+impl<UPDATE> Actions<UPDATE>
+    where UPDATE: Update + 'static,
+          UPDATE::Msg: 'static,
+{
+    /// Create the actions of `map`, which will send their messages to `relm`.
+    pub fn new<MAP: IsA<gio::ActionMap>>(map: &MAP, relm: &Relm<UPDATE>) -> Self {
+        Actions {
+            map: map.clone().upcast(),
+            relm: relm.clone(),
+        }
+    }
+
+    /// Add an action without parameter sending the message created by `callback` when activated.
+    pub fn add<CALLBACK>(&self, name: &str, callback: CALLBACK) -> gio::SimpleAction
+        where CALLBACK: Fn() -> UPDATE::Msg + 'static,
+    {
+        let action = gio::SimpleAction::new(name, None);
+        let stream = self.relm.stream().downgrade();
+        let _ = action.connect_activate(move |_, _| {
+            if let Some(stream) = stream.upgrade() {
+                stream.emit(callback());
+            }
+        });
+        self.add_action(&action, take_pending_state(&self.map, name));
+        action
+    }
+
+    /// Add an action with a parameter of type `PARAM`, like the target of a menu item, sending the
+    /// message created by `callback` with this parameter when activated.
+    pub fn add_with_parameter<CALLBACK, PARAM>(&self, name: &str, callback: CALLBACK) -> gio::SimpleAction
+        where CALLBACK: Fn(PARAM) -> UPDATE::Msg + 'static,
+              PARAM: FromVariant + StaticVariantType,
+    {
+        let action = gio::SimpleAction::new(name, Some(&PARAM::static_variant_type()));
+        let stream = self.relm.stream().downgrade();
+        let _ = action.connect_activate(move |_, parameter| {
+            let parameter = parameter.as_ref().and_then(Variant::get);
+            if let (Some(parameter), Some(stream)) = (parameter, stream.upgrade()) {
+                stream.emit(callback(parameter));
+            }
+        });
+        self.add_action(&action, take_pending_state(&self.map, name));
+        action
+    }
+
+    /// Add an action with a state, initially `state`, sending the message created by `callback`
+    /// with the new state when it changes.
+    ///
+    /// A boolean state, shown as a check menu item, is toggled when the action is activated.
+    /// Any other state is set from the parameter of the activation, like the target of a radio menu
+    /// item.
+    pub fn add_stateful<CALLBACK, STATE>(&self, name: &str, state: STATE, callback: CALLBACK) -> gio::SimpleAction
+        where CALLBACK: Fn(STATE) -> UPDATE::Msg + 'static,
+              STATE: FromVariant + StaticVariantType + ToVariant,
+    {
+        let state_type = STATE::static_variant_type();
+        let parameter_type =
+            if state_type == bool::static_variant_type() {
+                None
+            }
+            else {
+                Some(&*state_type)
+            };
+        // NOTE: keep the state bound with the action_state property in the view.
+        let mut pending = take_pending_state(&self.map, name);
+        let state = pending.state.take()
+            .filter(|pending_state| pending_state.get::<STATE>().is_some())
+            .unwrap_or_else(|| state.to_variant());
+        let action = gio::SimpleAction::new_stateful(name, parameter_type, &state);
+        let stream = self.relm.stream().downgrade();
+        let _ = action.connect_change_state(move |action, new_state| {
+            if let Some(ref new_state) = *new_state {
+                action.set_state(new_state);
+                if let (Some(new_state), Some(stream)) = (new_state.get(), stream.upgrade()) {
+                    stream.emit(callback(new_state));
+                }
+            }
+        });
+        self.add_action(&action, pending);
+        action
+    }
+
+    /// Check whether the action called `name` is enabled.
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.map.lookup_action(name).map_or(false, |action| action.get_enabled())
+    }
+
+    /// Remove the action called `name`.
+    pub fn remove(&self, name: &str) {
+        self.map.remove_action(name);
+    }
+
+    fn add_action(&self, action: &gio::SimpleAction, pending: PendingState) {
+        if let Some(enabled) = pending.enabled {
+            action.set_enabled(enabled);
+        }
+        if let Some(state) = pending.state {
+            // NOTE: a state of another type than the one of the action is ignored.
+            if action.get_state().map_or(false, |current_state| current_state.type_() == state.type_()) {
+                action.set_state(&state);
+            }
+        }
+        self.map.add_action(action);
+    }
+}
+
+/// Methods to bind the state of the actions of a `gio::ActionMap` to the model with properties in
+/// the `view!` macro:
+///
+/// ```ignore
+/// gtk::ApplicationWindow {
+///     action_enabled: ("save", self.model.modified),
+///     action_state: ("dark-theme", self.model.dark_theme.to_variant()),
+/// }
+/// ```
+///
+/// When the action does not exist yet, the value is kept for when it is added with
+/// [`Actions`](struct.Actions.html): no placeholder action is added to the map.
+pub trait ActionStateExt {
+    /// Enable or disable an action.
+    fn set_action_enabled(&self, action: (&str, bool));
+
+    /// Set the state of a stateful action.
+    fn set_action_state(&self, action: (&str, Variant));
+}
+
+impl<MAP: IsA<gio::ActionMap>> ActionStateExt for MAP {
+    fn set_action_enabled(&self, (name, enabled): (&str, bool)) {
+        match self.lookup_action(name) {
+            Some(action) => {
+                if let Ok(action) = action.downcast::<gio::SimpleAction>() {
+                    action.set_enabled(enabled);
+                }
+            },
+            None => set_pending_state(self, name, |pending| pending.enabled = Some(enabled)),
+        }
+    }
+
+    fn set_action_state(&self, (name, state): (&str, Variant)) {
+        match self.lookup_action(name) {
+            Some(action) => {
+                if let Ok(action) = action.downcast::<gio::SimpleAction>() {
+                    action.set_state(&state);
+                }
+            },
+            None => set_pending_state(self, name, |pending| pending.state = Some(state)),
+        }
+    }
+}
+
+fn is_entry<MAP: IsA<gio::ActionMap>>(entry: &PendingEntry, map: &MAP, name: &str) -> bool {
+    entry.name == name && entry.map.upgrade().map_or(false, |entry_map| entry_map == map.clone().upcast::<Object>())
+}
+
+fn set_pending_state<CALLBACK, MAP>(map: &MAP, name: &str, callback: CALLBACK)
+    where CALLBACK: FnOnce(&mut PendingState),
+          MAP: IsA<gio::ActionMap>,
+{
+    PENDING_STATES.with(|entries| {
+        let mut entries = entries.borrow_mut();
+        // Forget the values of the maps that were destroyed.
+        entries.retain(|entry| entry.map.upgrade().is_some());
+        let index = match entries.iter().position(|entry| is_entry(entry, map, name)) {
+            Some(index) => index,
+            None => {
+                let map: Object = map.clone().upcast();
+                entries.push(PendingEntry {
+                    map: map.downgrade(),
+                    name: name.to_string(),
+                    state: PendingState::default(),
+                });
+                entries.len() - 1
+            },
+        };
+        callback(&mut entries[index].state);
+    });
+}
+
+fn take_pending_state<MAP: IsA<gio::ActionMap>>(map: &MAP, name: &str) -> PendingState {
+    PENDING_STATES.with(|entries| {
+        let mut entries = entries.borrow_mut();
+        match entries.iter().position(|entry| is_entry(entry, map, name)) {
+            Some(index) => entries.remove(index).state,
+            None => PendingState::default(),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use gio::{self, ActionMapExt};
+
+    use super::{ActionStateExt, take_pending_state};
+
+    #[test]
+    fn pending_state() {
+        let group = gio::SimpleActionGroup::new();
+        let other_group = gio::SimpleActionGroup::new();
+        group.set_action_enabled(("save", false));
+        assert!(group.lookup_action("save").is_none());
+        assert_eq!(take_pending_state(&other_group, "save").enabled, None);
+        assert_eq!(take_pending_state(&group, "save").enabled, Some(false));
+        assert_eq!(take_pending_state(&group, "save").enabled, None);
+    }
+}
@@ -0,0 +1,208 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::cell::RefCell;
+use std::env;
+use std::rc::Rc;
+
+use futures_glib;
+use gio::{self, ApplicationCommandLineExt, ApplicationExt, ApplicationExtManual};
+use glib::Cast;
+use gtk::{self, GtkApplicationExt, GtkWindowExt};
+
+use super::{Component, DisplayVariant, create_executor, create_widget, init_component};
+use widget::Widget;
+
+/// Event of the `gtk::Application` that can be sent as a message to the root widget.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AppEvent {
+    /// The application was activated, for instance because it was launched a second time.
+    Activate,
+    /// The application is about to quit.
+    /// This message is processed before the root widget is destroyed.
+    Shutdown,
+    /// The application was started and the root widget was just created.
+    Startup,
+}
+
+/// Trait to implement for the root widget of an application running on a `gtk::Application`.
+///
+/// The application quits when its last window is closed, so the root widget should call
+/// `gtk::Application::quit()` instead of `gtk::main_quit()` if it needs to quit by itself.
+pub trait AppWidget: Widget {
+    /// Get the unique identifier of the application, like `"org.example.Editor"`.
+    /// When it is `None`, the application is not unique.
+    fn application_id() -> Option<&'static str> {
+        None
+    }
+
+    /// Get the message to send to the root widget when the application receives `event`.
+    fn app_msg(_event: AppEvent) -> Option<Self::Msg> {
+        None
+    }
+
+    /// Create the parameter of the [`model()`](trait.Update.html#tymethod.model) method from the
+    /// command-line arguments.
+    fn model_param(application: &gtk::Application, args: &[String]) -> Self::ModelParam;
+
+    /// Create the application and run it until its last window is closed.
+    fn run_app() -> Result<i32, ()>
+        where Self: 'static,
+    {
+        run_app::<Self>()
+    }
+}
+
+/// Create a `gtk::Application` running the specified relm `Widget` and return its exit status.
+///
+/// The widget is created when the application is activated for the first time, with the model
+/// parameter returned by
+/// [`AppWidget::model_param()`](trait.AppWidget.html#tymethod.model_param) from the command-line
+/// arguments of the primary instance.
+/// If its root is a `gtk::Window`, the window is added to the application so that the application
+/// quits when it is closed.
+/// When the application is unique and launched again, the arguments of the new instance are
+/// ignored and the `Activate` message is sent.
+///
+/// This is an alternative to [`run()`](fn.run.html) which also handles the application id and
+/// the startup, activate and shutdown signals.
+pub fn run_app<WIDGET>() -> Result<i32, ()>
+    where WIDGET: AppWidget + 'static,
+          WIDGET::Msg: DisplayVariant + 'static,
+{
+    futures_glib::init();
+    let application = gtk::Application::new(WIDGET::application_id(), gio::ApplicationFlags::HANDLES_COMMAND_LINE)
+        .map_err(|_| ())?;
+    let args: Vec<String> = env::args().collect();
+    let command_line_args = Rc::new(RefCell::new(args.clone()));
+
+    {
+        let command_line_args = command_line_args.clone();
+        // NOTE: handling the command line prevents GApplication from refusing the positional
+        // arguments as files to open.
+        application.connect_command_line(move |application, command_line| {
+            *command_line_args.borrow_mut() = command_line.get_arguments().iter()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect();
+            application.activate();
+            0
+        });
+    }
+
+    let component = connect_application(&application, move |application| {
+        let args = command_line_args.borrow();
+        WIDGET::model_param(application, &args)
+    }, WIDGET::app_msg);
+    {
+        let component = component.clone();
+        application.connect_shutdown(move |_| {
+            if let Some(component) = component.borrow_mut().take() {
+                if let Some(msg) = WIDGET::app_msg(AppEvent::Shutdown) {
+                    component.emit(msg);
+                }
+                // The main loop is stopped at this point, so process the message now.
+                while gtk::events_pending() {
+                    let _ = gtk::main_iteration();
+                }
+            }
+        });
+    }
+
+    Ok(application.run(&args))
+}
+
+/*
+ * Run the relm `Widget` on a `gtk::Application` until `gtk::main_quit()` is called or its last
+ * window is closed.
+ * This is used by run(): unlike run_app(), the main loop is the one of gtk::main() so that the
+ * widgets can still call gtk::main_quit().
+ */
+pub fn run_with_application<WIDGET>(model_param: WIDGET::ModelParam) -> Result<(), ()>
+    where WIDGET: Widget + 'static,
+          WIDGET::Msg: DisplayVariant + 'static,
+{
+    futures_glib::init();
+    let application = gtk::Application::new(None, gio::ApplicationFlags::NON_UNIQUE)
+        .map_err(|_| ())?;
+    let model_param = RefCell::new(Some(model_param));
+    let component = connect_application::<WIDGET, _, _>(&application, move |_| {
+        model_param.borrow_mut().take().expect("model parameter")
+    }, |_| None);
+    let _ = application.connect_window_removed(|application, _| {
+        if application.get_windows().is_empty() && gtk::main_level() > 0 {
+            gtk::main_quit();
+        }
+    });
+
+    application.register(None::<&gio::Cancellable>).map_err(|_| ())?;
+    application.activate();
+    if component.borrow().is_none() {
+        return Err(());
+    }
+    gtk::main();
+    let _ = component.borrow_mut().take();
+    Ok(())
+}
+
+// Create the component when the application is activated for the first time, present its window
+// and send the app messages.
+fn connect_application<WIDGET, APPMSG, MODELPARAM>(application: &gtk::Application, model_param: MODELPARAM,
+        app_msg: APPMSG) -> Rc<RefCell<Option<Component<WIDGET>>>>
+    where WIDGET: Widget + 'static,
+          WIDGET::Msg: DisplayVariant + 'static,
+          APPMSG: Fn(AppEvent) -> Option<WIDGET::Msg> + 'static,
+          MODELPARAM: Fn(&gtk::Application) -> WIDGET::ModelParam + 'static,
+{
+    let component: Rc<RefCell<Option<Component<WIDGET>>>> = Rc::new(RefCell::new(None));
+    let emit_app_msg = move |component: &Component<WIDGET>, event| {
+        if let Some(msg) = app_msg(event) {
+            component.emit(msg);
+        }
+    };
+    let activate_component = component.clone();
+    application.connect_activate(move |application| {
+        let created = activate_component.borrow().is_none();
+        if created {
+            let executor = create_executor();
+            let (widget, component_widget, relm) = create_widget::<WIDGET>(&executor, model_param(application), None);
+            init_component::<WIDGET>(widget.stream(), component_widget, &executor, &relm);
+            if let Some(window) = root_window(&widget) {
+                application.add_window(&window);
+            }
+            *activate_component.borrow_mut() = Some(widget);
+        }
+        if let Some(ref component) = *activate_component.borrow() {
+            if let Some(window) = root_window(component) {
+                window.present();
+            }
+            if created {
+                emit_app_msg(component, AppEvent::Startup);
+            }
+            emit_app_msg(component, AppEvent::Activate);
+        }
+    });
+    component
+}
+
+
+fn root_window<WIDGET: Widget>(component: &Component<WIDGET>) -> Option<gtk::Window> {
+    component.widget().clone().upcast::<gtk::Widget>().downcast::<gtk::Window>().ok()
+}
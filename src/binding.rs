@@ -0,0 +1,221 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::ffi::CString;
+
+use glib::{Cast, Object, ObjectExt, Value};
+use glib::translate::{FromGlibPtrNone, ToGlibPtr};
+use glib_sys::{GFALSE, gboolean, gpointer};
+use gobject_sys::{GObject, GValue, g_value_transform};
+use gtk::IsA;
+use libc::{c_char, c_uint};
+
+const G_BINDING_BIDIRECTIONAL: c_uint = 1 << 0;
+const G_BINDING_SYNC_CREATE: c_uint = 1 << 1;
+const G_BINDING_INVERT_BOOLEAN: c_uint = 1 << 2;
+
+type BindingTransformFunc = unsafe extern "C" fn(*mut GObject, *const GValue, *mut GValue, gpointer) -> gboolean;
+type TransformFunc = Box<Fn(&Value) -> Option<Value>>;
+
+extern "C" {
+    fn g_binding_unbind(binding: *mut GObject);
+    fn g_object_bind_property_full(source: *mut GObject, source_property: *const c_char, target: *mut GObject,
+        target_property: *const c_char, flags: c_uint, transform_to: Option<BindingTransformFunc>,
+        transform_from: Option<BindingTransformFunc>, user_data: gpointer,
+        notify: Option<unsafe extern "C" fn(gpointer)>) -> *mut GObject;
+}
+
+struct Transforms {
+    from: Option<TransformFunc>,
+    to: Option<TransformFunc>,
+}
+
+/// A binding between two GObject properties, created with [`bind_property()`](fn.bind_property.html).
+///
+/// The binding is kept alive by its source and target objects: dropping this handle does not
+/// remove it, call [`unbind()`](#method.unbind) to do so.
+pub struct Binding {
+    binding: Object,
+}
+
+impl Binding {
+    /// Remove the binding.
+    pub fn unbind(self) {
+        unsafe {
+            g_binding_unbind(self.binding.to_glib_none().0);
+        }
+    }
+}
+
+/// Builder of a [`Binding`](struct.Binding.html).
+pub struct BindingBuilder {
+    flags: c_uint,
+    source: Object,
+    source_property: String,
+    target: Object,
+    target_property: String,
+    transforms: Transforms,
+}
+
+impl BindingBuilder {
+    /// Also update the source property when the target property changes.
+    pub fn bidirectional(mut self) -> Self {
+        self.flags |= G_BINDING_BIDIRECTIONAL;
+        self
+    }
+
+    /// Create the binding.
+    /// The target property is set to the value of the source property right away.
+    /// Return `None`, after logging a warning, if one of the properties does not exist or cannot
+    /// be bound.
+    pub fn build(self) -> Option<Binding> {
+        let source_property = CString::new(self.source_property.as_str());
+        let target_property = CString::new(self.target_property.as_str());
+        let (source_property, target_property) =
+            match (source_property, target_property) {
+                (Ok(source_property), Ok(target_property)) => (source_property, target_property),
+                _ => {
+                    warn_binding_failure(&self.source, &self.source_property, &self.target, &self.target_property);
+                    return None;
+                },
+            };
+        let transform_to: Option<BindingTransformFunc> =
+            if self.transforms.to.is_some() { Some(transform_to_trampoline) } else { None };
+        let transform_from: Option<BindingTransformFunc> =
+            if self.transforms.from.is_some() { Some(transform_from_trampoline) } else { None };
+        let transforms = Box::into_raw(Box::new(self.transforms));
+        unsafe {
+            let binding = g_object_bind_property_full(self.source.to_glib_none().0, source_property.as_ptr(),
+                self.target.to_glib_none().0, target_property.as_ptr(), self.flags | G_BINDING_SYNC_CREATE,
+                transform_to, transform_from, transforms as gpointer, Some(destroy_transforms));
+            if binding.is_null() {
+                // NOTE: the transform functions are not owned by GLib when the binding fails.
+                destroy_transforms(transforms as gpointer);
+                warn_binding_failure(&self.source, &self.source_property, &self.target, &self.target_property);
+                None
+            }
+            else {
+                Some(Binding {
+                    binding: Object::from_glib_none(binding),
+                })
+            }
+        }
+    }
+
+    /// Negate the boolean value of the properties when it is copied.
+    pub fn invert_boolean(mut self) -> Self {
+        self.flags |= G_BINDING_INVERT_BOOLEAN;
+        self
+    }
+
+    /// Convert the value of the target property before setting the source property of a
+    /// bidirectional binding.
+    /// The source property is left unchanged when `transform` returns `None`.
+    pub fn transform_from<TRANSFORM>(mut self, transform: TRANSFORM) -> Self
+        where TRANSFORM: Fn(&Value) -> Option<Value> + 'static,
+    {
+        self.transforms.from = Some(Box::new(transform));
+        self
+    }
+
+    /// Convert the value of the source property before setting the target property.
+    /// The target property is left unchanged when `transform` returns `None`.
+    pub fn transform_to<TRANSFORM>(mut self, transform: TRANSFORM) -> Self
+        where TRANSFORM: Fn(&Value) -> Option<Value> + 'static,
+    {
+        self.transforms.to = Some(Box::new(transform));
+        self
+    }
+}
+
+/// Source of the bindings created with [`bind_property()`](fn.bind_property.html): a GObject or
+/// the [`Settings`](struct.Settings.html).
+pub trait BindingSource {
+    /// The builder of the binding.
+    type Builder;
+
+    /// Start building a binding of the property `target_property` of `target` to the property
+    /// `source_property` of this source.
+    fn bind_property_to(&self, source_property: &str, target: Object, target_property: &str) -> Self::Builder;
+}
+
+impl<SOURCE: Clone + IsA<Object>> BindingSource for SOURCE {
+    type Builder = BindingBuilder;
+
+    fn bind_property_to(&self, source_property: &str, target: Object, target_property: &str) -> BindingBuilder {
+        BindingBuilder {
+            flags: 0,
+            source: self.clone().upcast(),
+            source_property: source_property.to_string(),
+            target,
+            target_property: target_property.to_string(),
+            transforms: Transforms {
+                from: None,
+                to: None,
+            },
+        }
+    }
+}
+
+/// Bind the property `target_property` of `target` to the property `source_property` of `source`
+/// with a `GBinding`, so that the target is updated without going through `update()`.
+///
+/// ```ignore
+/// relm::bind_property(&scale.get_adjustment(), "value", &spin_button.get_adjustment(), "value")
+///     .bidirectional()
+///     .build();
+/// ```
+pub fn bind_property<SOURCE, TARGET>(source: &SOURCE, source_property: &str, target: &TARGET,
+    target_property: &str) -> SOURCE::Builder
+    where SOURCE: BindingSource,
+          TARGET: Clone + IsA<Object>,
+{
+    source.bind_property_to(source_property, target.clone().upcast(), target_property)
+}
+
+fn warn_binding_failure(source: &Object, source_property: &str, target: &Object, target_property: &str) {
+    warn!("Cannot bind the property {} of {} to the property {} of {}", target_property, target.get_type().name(),
+          source_property, source.get_type().name());
+}
+
+unsafe extern "C" fn destroy_transforms(user_data: gpointer) {
+    drop(Box::from_raw(user_data as *mut Transforms));
+}
+
+unsafe fn transform(transform: &Option<TransformFunc>, from: *const GValue, to: *mut GValue) -> gboolean {
+    let value = Value::from_glib_none(from);
+    match transform.as_ref().and_then(|transform| transform(&value)) {
+        Some(value) => g_value_transform(value.to_glib_none().0, to),
+        None => GFALSE,
+    }
+}
+
+unsafe extern "C" fn transform_from_trampoline(_binding: *mut GObject, from: *const GValue, to: *mut GValue,
+    user_data: gpointer) -> gboolean
+{
+    transform(&(*(user_data as *const Transforms)).from, from, to)
+}
+
+unsafe extern "C" fn transform_to_trampoline(_binding: *mut GObject, from: *const GValue, to: *mut GValue,
+    user_data: gpointer) -> gboolean
+{
+    transform(&(*(user_data as *const Transforms)).to, from, to)
+}
@@ -0,0 +1,123 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use futures::unsync::mpsc::{UnboundedSender, unbounded};
+use gdk::Atom;
+use gdk_pixbuf::Pixbuf;
+use gtk;
+
+use super::{Relm, SignalGuard, Update};
+
+/// Content to put in the clipboard with [`set_clipboard()`](fn.set_clipboard.html).
+pub enum ClipboardContent {
+    /// An image.
+    Image(Pixbuf),
+    /// Text.
+    Text(String),
+}
+
+/// Get the clipboard used by the copy and paste operations.
+pub fn clipboard() -> gtk::Clipboard {
+    gtk::Clipboard::get(&Atom::intern("CLIPBOARD"))
+}
+
+/// Send the message created by `callback` every time the owner of the `clipboard` changes, i.e. when
+/// some content is copied, by this application or another one.
+/// This is usually called from [`Update::subscriptions()`](trait.Update.html#method.subscriptions).
+///
+/// The handler is disconnected when the returned guard is dropped, so it must be stored in the
+/// model or the widget.
+/// No message is sent once the component is dropped.
+pub fn connect_clipboard_owner_change<CALLBACK, UPDATE>(clipboard: &gtk::Clipboard, relm: &Relm<UPDATE>,
+        callback: CALLBACK) -> SignalGuard
+    where CALLBACK: Fn() -> UPDATE::Msg + 'static,
+          UPDATE: Update + 'static,
+          UPDATE::Msg: 'static,
+{
+    let stream = relm.stream().downgrade();
+    let handler_id = clipboard.connect_owner_change(move |_, _| stream.emit(callback()));
+    SignalGuard::new(clipboard, handler_id)
+}
+
+/// Request the image in the `clipboard` and send the message created by `callback` with it, or
+/// `None` if the clipboard does not contain an image.
+pub fn request_clipboard_image<CALLBACK, UPDATE>(clipboard: &gtk::Clipboard, relm: &Relm<UPDATE>, callback: CALLBACK)
+    where CALLBACK: Fn(Option<Pixbuf>) -> UPDATE::Msg + 'static,
+          UPDATE: Update + 'static,
+          UPDATE::Msg: 'static,
+{
+    request(relm, callback, |sender| {
+        clipboard.request_image(move |_, image| {
+            let _ = sender.unbounded_send(image.cloned());
+        });
+    });
+}
+
+/// Request the text in the `clipboard` and send the message created by `callback` with it, or
+/// `None` if the clipboard does not contain text.
+pub fn request_clipboard_text<CALLBACK, UPDATE>(clipboard: &gtk::Clipboard, relm: &Relm<UPDATE>, callback: CALLBACK)
+    where CALLBACK: Fn(Option<String>) -> UPDATE::Msg + 'static,
+          UPDATE: Update + 'static,
+          UPDATE::Msg: 'static,
+{
+    request(relm, callback, |sender| {
+        clipboard.request_text(move |_, text| {
+            let _ = sender.unbounded_send(text.map(|text| text.to_string()));
+        });
+    });
+}
+
+/// Request the URIs in the `clipboard`, like the files copied in a file manager, and send the message
+/// created by `callback` with them.
+pub fn request_clipboard_uris<CALLBACK, UPDATE>(clipboard: &gtk::Clipboard, relm: &Relm<UPDATE>, callback: CALLBACK)
+    where CALLBACK: Fn(Vec<String>) -> UPDATE::Msg + 'static,
+          UPDATE: Update + 'static,
+          UPDATE::Msg: 'static,
+{
+    request(relm, callback, |sender| {
+        clipboard.request_uris(move |_, uris| {
+            let _ = sender.unbounded_send(uris.iter().map(|uri| uri.to_string()).collect());
+        });
+    });
+}
+
+/// Set the `content` of the `clipboard`.
+pub fn set_clipboard(clipboard: &gtk::Clipboard, content: ClipboardContent) {
+    match content {
+        ClipboardContent::Image(image) => clipboard.set_image(&image),
+        ClipboardContent::Text(text) => clipboard.set_text(&text),
+    }
+}
+
+// Like connect_async!, the result given to the GTK+ callback is sent through a channel which is
+// connected to the component.
+fn request<CALLBACK, ITEM, REQUEST, UPDATE>(relm: &Relm<UPDATE>, callback: CALLBACK, request: REQUEST)
+    where CALLBACK: Fn(ITEM) -> UPDATE::Msg + 'static,
+          ITEM: 'static,
+          REQUEST: FnOnce(UnboundedSender<ITEM>),
+          UPDATE: Update + 'static,
+          UPDATE::Msg: 'static,
+{
+    let (sender, receiver) = unbounded();
+    request(sender);
+    relm.connect_exec_ignore_err(receiver, callback);
+}
+
@@ -19,7 +19,47 @@
  * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
  */
 
-use super::{EventStream, Widget};
+use std::any::type_name;
+use std::cell::Cell;
+use std::panic::Location;
+use std::rc::Rc;
+use std::thread;
+
+use gtk::WidgetExt;
+
+use i18n;
+use super::{EventStream, WeakEventStream, Widget};
+
+thread_local! {
+    static STRICT_MODE: Cell<bool> = Cell::new(false);
+}
+
+/// Set whether relm panics instead of printing a warning when it detects a misuse.
+///
+/// For now, this only concerns the `Component`s dropped while their widget is still in a
+/// container of a live parent: this is useful to catch them in tests.
+pub fn set_strict_mode(strict: bool) {
+    STRICT_MODE.with(|strict_mode| strict_mode.set(strict));
+}
+
+/// Type-erased handle to the event stream of a parent component.
+pub trait ParentStream {
+    /// Get the identifier of the stream.
+    fn id(&self) -> usize;
+
+    /// Check whether the parent component is still alive.
+    fn is_alive(&self) -> bool;
+}
+
+impl<MSG> ParentStream for WeakEventStream<MSG> {
+    fn id(&self) -> usize {
+        self.upgrade().map(|stream| stream.id()).unwrap_or(0)
+    }
+
+    fn is_alive(&self) -> bool {
+        self.upgrade().map(|stream| !stream.is_closed()).unwrap_or(false)
+    }
+}
 
 /// Widget that was added by the `ContainerWidget::add_widget()` method.
 ///
@@ -32,25 +72,65 @@ use super::{EventStream, Widget};
 #[must_use]
 #[derive(Clone)]
 pub struct Component<WIDGET: Widget> {
+    created_at: &'static Location<'static>,
+    // Shared by the clones of the component to know when the last one is dropped.
+    owners: Rc<()>,
+    parent: Option<Rc<ParentStream>>,
     stream: EventStream<WIDGET::Msg>,
     widget: WIDGET::Root,
 }
 
 impl<WIDGET: Widget> Drop for Component<WIDGET> {
     fn drop(&mut self) {
-        let _ = self.stream.close();
+        // NOTE: the clones of the component share its stream, so it is only closed when the last
+        // clone is dropped.
+        if Rc::strong_count(&self.owners) == 1 && !self.stream.is_closed() {
+            self.check_drop();
+            i18n::disconnect_component_locale_changed(self.stream.id());
+            let _ = self.stream.close();
+        }
     }
 }
 
 impl<WIDGET: Widget> Component<WIDGET> {
     #[doc(hidden)]
-    pub fn new(stream: EventStream<WIDGET::Msg>, widget: WIDGET::Root) -> Self {
+    #[track_caller]
+    pub fn new(stream: EventStream<WIDGET::Msg>, widget: WIDGET::Root, parent: Option<Rc<ParentStream>>) -> Self {
         Component {
+            created_at: Location::caller(),
+            owners: Rc::new(()),
+            parent,
             stream,
             widget,
         }
     }
 
+    // Warn when the component is dropped while its widget is still shown in its parent: the widget
+    // would silently stop responding since its stream is closed.
+    // The widget of a component dropped because its parent was dropped, or because it was removed
+    // from its container, is not reported.
+    fn check_drop(&self) {
+        let parent_alive = self.parent.as_ref().map(|parent| parent.is_alive()).unwrap_or(false);
+        if !parent_alive || self.widget.get_parent().is_none() {
+            return;
+        }
+        let message = format!("the Component of the relm widget {} created at {} was dropped while its widget \
+            is still in a container; store the Component in the parent widget to keep receiving its messages",
+            type_name::<WIDGET>(), self.created_at);
+        if STRICT_MODE.with(|strict_mode| strict_mode.get()) && !thread::panicking() {
+            panic!("{}", message);
+        }
+        warn!("{}", message);
+    }
+
+    /// Create a weak handle to this component.
+    /// Unlike a clone, the handle neither keeps the component alive nor stops it when dropped.
+    pub fn downgrade(&self) -> WeakComponent<WIDGET> {
+        WeakComponent {
+            stream: self.stream.downgrade(),
+        }
+    }
+
     /// Emit a message of the widget stream.
     pub fn emit(&self, msg: WIDGET::Msg) {
         self.stream.emit(msg);
@@ -67,3 +147,44 @@ impl<WIDGET: Widget> Component<WIDGET> {
         &self.widget
     }
 }
+
+/// Weak handle to a `Component`, created by [`Component::downgrade()`](struct.Component.html#method.downgrade).
+///
+/// This is useful to send messages to a component from a child component or from a closure without
+/// creating a reference cycle.
+pub struct WeakComponent<WIDGET: Widget> {
+    stream: WeakEventStream<WIDGET::Msg>,
+}
+
+impl<WIDGET: Widget> Clone for WeakComponent<WIDGET> {
+    fn clone(&self) -> Self {
+        WeakComponent {
+            stream: self.stream.clone(),
+        }
+    }
+}
+
+impl<WIDGET: Widget> WeakComponent<WIDGET> {
+    /// Emit a message of the widget stream.
+    /// Return the message back if the component was dropped.
+    pub fn emit(&self, msg: WIDGET::Msg) -> Result<(), WIDGET::Msg> {
+        match self.stream.upgrade() {
+            Some(ref stream) if !stream.is_closed() => {
+                stream.emit(msg);
+                Ok(())
+            },
+            _ => Err(msg),
+        }
+    }
+
+    /// Check whether the component is still alive.
+    pub fn is_alive(&self) -> bool {
+        self.stream.upgrade().map(|stream| !stream.is_closed()).unwrap_or(false)
+    }
+
+    /// Get the weak event stream of the component.
+    /// This is used internally by the library.
+    pub fn stream(&self) -> &WeakEventStream<WIDGET::Msg> {
+        &self.stream
+    }
+}
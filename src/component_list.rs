@@ -0,0 +1,212 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use glib::Cast;
+use gtk::{self, IsA, Object, WidgetExt};
+
+use container::reorder_child;
+use super::{Component, ContainerWidget, DisplayVariant, Relm, Widget};
+
+struct Child<WIDGET: Widget> {
+    component: Component<WIDGET>,
+    param: WIDGET::ModelParam,
+}
+
+/// Keyed list of relm widgets inside a GTK+ container.
+///
+/// Every time [`set_items()`](struct.ComponentList.html#method.set_items) is called, the children
+/// of the container are reconciled with the new items: a widget is created for every new key, the
+/// widgets whose key is missing are removed and the remaining ones are moved to their new position.
+/// The `Component` of a widget is kept alive as long as its key is in the list.
+///
+/// The container should only contain the widgets of this list.
+/// Any container can be used, but the ones that cannot position their children (like `gtk::Grid`)
+/// lose the child properties of the widgets that are moved (see
+/// [`reorder_child()`](fn.reorder_child.html)).
+pub struct ComponentList<KEY, WIDGET: Widget> {
+    children: HashMap<KEY, Child<WIDGET>>,
+    container: gtk::Container,
+    keys: Vec<KEY>,
+    update_msg: Option<Box<Fn(WIDGET::ModelParam) -> WIDGET::Msg>>,
+}
+
+impl<KEY, WIDGET> ComponentList<KEY, WIDGET>
+    where KEY: Clone + Eq + Hash,
+          WIDGET: Widget + 'static,
+          WIDGET::Msg: DisplayVariant + 'static,
+          WIDGET::ModelParam: Clone + PartialEq,
+          WIDGET::Root: IsA<gtk::Widget> + IsA<Object> + WidgetExt,
+{
+    /// Create an empty list of widgets that will be added to `container`.
+    pub fn new<CONTAINER: IsA<gtk::Container>>(container: &CONTAINER) -> Self {
+        ComponentList {
+            children: HashMap::new(),
+            container: container.clone().upcast(),
+            keys: vec![],
+            update_msg: None,
+        }
+    }
+
+    /// Create an empty list of widgets that will be added to `container`.
+    /// When the model parameter of an existing key changes, the message created by `update_msg`
+    /// is sent to the widget of this key.
+    pub fn with_update_msg<CONTAINER, UPDATEMSG>(container: &CONTAINER, update_msg: UPDATEMSG) -> Self
+        where CONTAINER: IsA<gtk::Container>,
+              UPDATEMSG: Fn(WIDGET::ModelParam) -> WIDGET::Msg + 'static,
+    {
+        let mut list = Self::new(container);
+        list.update_msg = Some(Box::new(update_msg));
+        list
+    }
+
+    /// Get the component of the widget associated with `key`.
+    pub fn get(&self, key: &KEY) -> Option<&Component<WIDGET>> {
+        self.children.get(key).map(|child| &child.component)
+    }
+
+    /// Check whether the list is empty.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Iterate over the keys and components, in the order they appear in the container.
+    pub fn iter<'a>(&'a self) -> Box<Iterator<Item=(&'a KEY, &'a Component<WIDGET>)> + 'a> {
+        Box::new(self.keys.iter().map(move |key| (key, &self.children[key].component)))
+    }
+
+    /// Get the keys in the order they appear in the container.
+    pub fn keys(&self) -> &[KEY] {
+        &self.keys
+    }
+
+    /// Get the number of widgets in the list.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Reconcile the widgets of the container with `items`, a list of keys with the model
+    /// parameter of their widget.
+    ///
+    /// ## Panics
+    /// Panics if a key appears more than once in `items`.
+    #[track_caller]
+    pub fn set_items<PARENTWIDGET, ITEMS>(&mut self, relm: &Relm<PARENTWIDGET>, items: ITEMS)
+        where PARENTWIDGET: Widget,
+              ITEMS: IntoIterator<Item=(KEY, WIDGET::ModelParam)>,
+    {
+        let items: Vec<_> = items.into_iter().collect();
+        let new_keys = index_keys(items.iter().map(|&(ref key, _)| key));
+
+        // Remove the widgets whose key is missing.
+        for key in remove_missing_keys(&mut self.keys, &new_keys) {
+            if let Some(child) = self.children.remove(&key) {
+                self.container.remove_widget(child.component);
+            }
+        }
+
+        for (index, (key, param)) in items.into_iter().enumerate() {
+            if let Some(child) = self.children.get_mut(&key) {
+                if child.param != param {
+                    if let Some(ref update_msg) = self.update_msg {
+                        child.component.emit(update_msg(param.clone()));
+                    }
+                    child.param = param;
+                }
+            }
+            else {
+                let component = self.container.add_widget::<WIDGET, _>(relm, param.clone());
+                let _ = self.children.insert(key.clone(), Child {
+                    component,
+                    param,
+                });
+                self.keys.push(key.clone());
+            }
+
+            // Move the widget if it is not at the right position.
+            if move_key(&mut self.keys, &key, index) {
+                reorder_child(&self.container, self.children[&key].component.widget(), index as i32);
+            }
+        }
+    }
+}
+
+// Map the keys to their index.
+// Panics if a key appears more than once.
+fn index_keys<'a, KEY, KEYS>(keys: KEYS) -> HashMap<KEY, usize>
+    where KEY: Clone + Eq + Hash + 'a,
+          KEYS: Iterator<Item=&'a KEY>,
+{
+    let mut indexes = HashMap::new();
+    for (index, key) in keys.enumerate() {
+        if indexes.insert(key.clone(), index).is_some() {
+            panic!("Duplicate key in ComponentList::set_items()");
+        }
+    }
+    indexes
+}
+
+// Move `key` to `index` in `keys`.
+// Return whether it was moved.
+fn move_key<KEY: PartialEq>(keys: &mut Vec<KEY>, key: &KEY, index: usize) -> bool {
+    if keys[index] == *key {
+        return false;
+    }
+    let current_index = keys.iter().position(|current_key| current_key == key)
+        .expect("key position");
+    let key = keys.remove(current_index);
+    keys.insert(index, key);
+    true
+}
+
+// Remove the keys missing from `new_keys` and return them.
+fn remove_missing_keys<KEY: Eq + Hash>(keys: &mut Vec<KEY>, new_keys: &HashMap<KEY, usize>) -> Vec<KEY> {
+    let (kept, removed) = keys.drain(..).partition(|key| new_keys.contains_key(key));
+    *keys = kept;
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{index_keys, move_key, remove_missing_keys};
+
+    #[test]
+    #[should_panic(expected = "Duplicate key")]
+    fn duplicate_keys() {
+        let _ = index_keys([1, 2, 1].iter());
+    }
+
+    #[test]
+    fn reconcile_keys() {
+        let mut keys = vec![1, 2, 3, 4];
+        let new_keys = index_keys([4, 5, 2].iter());
+        assert_eq!(remove_missing_keys(&mut keys, &new_keys), vec![1, 3]);
+        assert_eq!(keys, vec![2, 4]);
+        assert!(move_key(&mut keys, &4, 0));
+        assert_eq!(keys, vec![4, 2]);
+        keys.insert(1, 5);
+        assert!(!move_key(&mut keys, &5, 1));
+        assert!(!move_key(&mut keys, &2, 2));
+        assert_eq!(keys, vec![4, 5, 2]);
+    }
+}
@@ -19,12 +19,25 @@
  * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
  */
 
-use glib::Cast;
+use std::ffi::{CStr, CString};
+use std::iter;
+use std::rc::Rc;
+
+use glib::{Cast, ObjectExt, ToValue, Type, Value};
+use glib::translate::{ToGlibPtr, ToGlibPtrMut, from_glib};
+use glib_sys::{g_free, gpointer};
+use gobject_sys::{G_PARAM_READWRITE, GObjectClass, GTypeInstance};
 use gtk;
-use gtk::{ContainerExt, IsA, Object, WidgetExt};
+use gtk::{BoxExt, ContainerExt, FlowBoxExt, IsA, ListBoxExt, NotebookExt, Object, WidgetExt};
+use gtk_sys::{
+    gtk_container_child_get_property,
+    gtk_container_child_set_property,
+    gtk_container_class_find_child_property,
+    gtk_container_class_list_child_properties,
+};
 
 use relm_state::EventStream;
-use super::{Component, DisplayVariant, Relm, create_widget, init_component};
+use super::{Component, DisplayVariant, Relm, WeakComponent, create_widget, init_component};
 use widget::Widget;
 
 /// Struct for relm containers to add GTK+ and relm `Widget`s.
@@ -52,32 +65,76 @@ impl<WIDGET: Container + Widget> ContainerComponent<WIDGET> {
     }
 
     /// Add a relm widget to a relm container.
+    #[track_caller]
     pub fn add_widget<CHILDWIDGET, PARENTWIDGET>(&self, relm: &Relm<PARENTWIDGET>, model_param: CHILDWIDGET::ModelParam)
         -> Component<CHILDWIDGET>
         where CHILDWIDGET: Widget + 'static,
               PARENTWIDGET: Widget,
               WIDGET::Container: ContainerExt + IsA<gtk::Widget> + IsA<Object>,
     {
-        let (widget, component, child_relm) = create_widget::<CHILDWIDGET>(relm.executor(), model_param);
+        let (widget, component, child_relm) = create_widget::<CHILDWIDGET>(relm.executor(), model_param,
+            Some(Rc::new(relm.stream().downgrade())));
         let container = WIDGET::add_widget(self, &widget);
         component.on_add(container);
         init_component::<CHILDWIDGET>(widget.stream(), component, relm.executor(), &child_relm);
         widget
     }
 
+    /// Remove all the widgets from this relm container.
+    ///
+    /// # Note
+    ///
+    /// The `Component`s of the removed relm widgets are still alive until they are dropped.
+    pub fn clear(&self) {
+        WIDGET::clear(self);
+    }
+
+    /// Create a weak handle to this component.
+    pub fn downgrade(&self) -> WeakComponent<WIDGET> {
+        self.component.downgrade()
+    }
+
     /// Emit a message of the widget stream.
     pub fn emit(&self, msg: WIDGET::Msg) {
         self.stream().emit(msg);
     }
 
+    /// Add a relm widget to a relm container at the specified `position`.
+    #[track_caller]
+    pub fn insert_widget_at<CHILDWIDGET, PARENTWIDGET>(&self, relm: &Relm<PARENTWIDGET>,
+        model_param: CHILDWIDGET::ModelParam, position: i32) -> Component<CHILDWIDGET>
+        where CHILDWIDGET: Widget + 'static,
+              PARENTWIDGET: Widget,
+              WIDGET::Container: ContainerExt + IsA<gtk::Widget> + IsA<Object>,
+    {
+        let (widget, component, child_relm) = create_widget::<CHILDWIDGET>(relm.executor(), model_param,
+            Some(Rc::new(relm.stream().downgrade())));
+        let container = WIDGET::insert_widget(self, &widget, position);
+        component.on_add(container);
+        init_component::<CHILDWIDGET>(widget.stream(), component, relm.executor(), &child_relm);
+        widget
+    }
+
+    /// Move a relm widget of this relm container to the specified `position`.
+    pub fn move_widget<CHILDWIDGET>(&self, component: &Component<CHILDWIDGET>, position: i32)
+        where CHILDWIDGET: Widget,
+    {
+        WIDGET::move_widget(self, component, position);
+    }
+
+    /// Remove a relm widget from this relm container.
+    pub fn remove_widget<CHILDWIDGET>(&self, component: Component<CHILDWIDGET>)
+        where CHILDWIDGET: Widget,
+    {
+        WIDGET::remove_widget(self, &component);
+    }
+
     /// Get the event stream of the component.
     /// This is used internally by the library.
     pub fn stream(&self) -> &EventStream<WIDGET::Msg> {
         self.component.stream()
     }
 
-    // TODO: add delete methods?
-
     /// Get the widget of the component.
     pub fn widget(&self) -> &WIDGET::Root {
         self.component.widget()
@@ -101,12 +158,46 @@ pub trait Container: Widget {
         container.container.clone().upcast()
     }
 
+    /// Remove all the widgets from this container.
+    fn clear(container: &ContainerComponent<Self>) {
+        for child in container.container.get_children() {
+            container.container.remove(&child);
+        }
+    }
+
     /// Get the containing widget, i.e. the widget where the children will be added.
     fn container(&self) -> &Self::Container;
 
+    /// Add a relm widget to this container at the specified `position`.
+    /// Return the widget that will be send to Widget::on_add().
+    fn insert_widget<WIDGET: Widget>(container: &ContainerComponent<Self>, component: &Component<WIDGET>,
+        position: i32) -> gtk::Container
+    {
+        let parent = Self::add_widget(container, component);
+        reorder_child(&parent, component.widget(), position);
+        parent
+    }
+
+    /// Move a relm widget of this container to the specified `position`.
+    fn move_widget<WIDGET: Widget>(_container: &ContainerComponent<Self>, component: &Component<WIDGET>,
+        position: i32)
+    {
+        if let Some(parent) = child_container(component.widget()) {
+            reorder_child(&parent, component.widget(), position);
+        }
+    }
+
     /// Get additional container widgets.
     /// This is useful to create a multi-container.
     fn other_containers(&self) -> Self::Containers;
+
+    /// Remove a relm widget from this container.
+    /// This must undo what was done in [`add_widget()`](trait.Container.html#method.add_widget).
+    fn remove_widget<WIDGET: Widget>(container: &ContainerComponent<Self>, component: &Component<WIDGET>) {
+        let container = child_container(component.widget())
+            .unwrap_or_else(|| container.container.clone().upcast());
+        remove_child(&container, component.widget());
+    }
 }
 
 /// Extension trait for GTK+ containers to add and remove relm `Widget`s.
@@ -131,6 +222,7 @@ pub trait ContainerWidget {
     ///
     /// The returned `Component` must be stored in a `Widget`. If it is not stored, a communication
     /// receiver will be droped which will cause events to be ignored for this widget.
+    /// A warning is printed when this happens (see [`set_strict_mode()`](fn.set_strict_mode.html)).
     fn add_widget<CHILDWIDGET, WIDGET>(&self, relm: &Relm<WIDGET>, model_param: CHILDWIDGET::ModelParam)
             -> Component<CHILDWIDGET>
         where CHILDWIDGET: Widget + 'static,
@@ -145,6 +237,7 @@ pub trait ContainerWidget {
 }
 
 impl<W: Clone + ContainerExt + IsA<gtk::Widget> + IsA<Object>> ContainerWidget for W {
+    #[track_caller]
     fn add_container<CHILDWIDGET, WIDGET>(&self, relm: &Relm<WIDGET>, model_param: CHILDWIDGET::ModelParam)
             -> ContainerComponent<CHILDWIDGET>
         where CHILDWIDGET: Container + Widget + 'static,
@@ -152,7 +245,8 @@ impl<W: Clone + ContainerExt + IsA<gtk::Widget> + IsA<Object>> ContainerWidget f
               CHILDWIDGET::Root: IsA<gtk::Widget> + IsA<Object> + WidgetExt,
               WIDGET: Widget,
     {
-        let (widget, component, child_relm) = create_widget::<CHILDWIDGET>(relm.executor(), model_param);
+        let (widget, component, child_relm) = create_widget::<CHILDWIDGET>(relm.executor(), model_param,
+            Some(Rc::new(relm.stream().downgrade())));
         let container = component.container().clone();
         let containers = component.other_containers();
         let root = component.root().clone();
@@ -162,6 +256,7 @@ impl<W: Clone + ContainerExt + IsA<gtk::Widget> + IsA<Object>> ContainerWidget f
         ContainerComponent::new(widget, container, containers)
     }
 
+    #[track_caller]
     fn add_widget<CHILDWIDGET, WIDGET>(&self, relm: &Relm<WIDGET>, model_param: CHILDWIDGET::ModelParam)
             -> Component<CHILDWIDGET>
         where CHILDWIDGET: Widget + 'static,
@@ -169,7 +264,8 @@ impl<W: Clone + ContainerExt + IsA<gtk::Widget> + IsA<Object>> ContainerWidget f
               CHILDWIDGET::Root: IsA<gtk::Widget> + IsA<Object> + WidgetExt,
               WIDGET: Widget,
     {
-        let (widget, component, child_relm) = create_widget::<CHILDWIDGET>(relm.executor(), model_param);
+        let (widget, component, child_relm) = create_widget::<CHILDWIDGET>(relm.executor(), model_param,
+            Some(Rc::new(relm.stream().downgrade())));
         self.add(widget.widget());
         component.on_add(self.clone());
         init_component::<CHILDWIDGET>(widget.stream(), component, relm.executor(), &child_relm);
@@ -180,6 +276,129 @@ impl<W: Clone + ContainerExt + IsA<gtk::Widget> + IsA<Object>> ContainerWidget f
         where WIDGET: Widget,
               WIDGET::Root: IsA<gtk::Widget>,
     {
-        self.remove(component.widget());
+        remove_child(&self.clone().upcast(), component.widget());
+    }
+}
+
+/// Move the `child` widget to `position` in its `container`.
+///
+/// The rows of a `gtk::ListBox` and the children of a `gtk::FlowBox` wrapping `child` are moved
+/// with it.
+/// The other containers are reordered with their `position` or `index` child property (like
+/// `gtk::Stack` or `gtk::Overlay`) or, when they have none (like `gtk::Grid`), by removing the
+/// children from `position` and adding them back after `child`, with their child properties.
+pub fn reorder_child<CHILDWIDGET: IsA<gtk::Widget>>(container: &gtk::Container, child: &CHILDWIDGET, position: i32) {
+    let item = wrapper(child).map(|wrapper| wrapper.upcast()).unwrap_or_else(|| child.clone().upcast());
+    if let Ok(gtk_box) = container.clone().downcast::<gtk::Box>() {
+        gtk_box.reorder_child(&item, position);
+    }
+    else if let Ok(notebook) = container.clone().downcast::<gtk::Notebook>() {
+        notebook.reorder_child(&item, position);
+    }
+    else if let Ok(list_box) = container.clone().downcast::<gtk::ListBox>() {
+        list_box.remove(&item);
+        list_box.insert(&item, position);
+    }
+    else if let Ok(flow_box) = container.clone().downcast::<gtk::FlowBox>() {
+        flow_box.remove(&item);
+        flow_box.insert(&item, position);
+    }
+    else if !container.get_children().contains(&item) {
+        // The child is not in this container.
+    }
+    else if let Some(property) = position_property(container) {
+        set_child_property(container, &item, &property, &position.max(0).to_value());
+    }
+    else {
+        // NOTE: the widgets are kept alive by the vector while they are out of the container.
+        let children: Vec<_> = container.get_children().into_iter()
+            .filter(|widget| *widget != item)
+            .collect();
+        let position = (position.max(0) as usize).min(children.len());
+        let moved: Vec<_> = iter::once(item).chain(children[position..].iter().cloned()).collect();
+        let properties: Vec<_> = moved.iter()
+            .map(|widget| child_properties(container, widget))
+            .collect();
+        for widget in &moved {
+            container.remove(widget);
+        }
+        for (widget, properties) in moved.iter().zip(properties) {
+            container.add(widget);
+            for (name, value) in properties {
+                set_child_property(container, widget, &name, &value);
+            }
+        }
+    }
+}
+
+/// Remove the `child` widget from its `container`, along with the row of a `gtk::ListBox` or the
+/// child of a `gtk::FlowBox` wrapping it.
+pub fn remove_child<CHILDWIDGET: IsA<gtk::Widget>>(container: &gtk::Container, child: &CHILDWIDGET) {
+    match wrapper(child) {
+        Some(wrapper) => {
+            wrapper.remove(child);
+            container.remove(&wrapper);
+        },
+        None => container.remove(child),
+    }
+}
+
+/// Get the container `child` was added to, skipping the row of a `gtk::ListBox` or the child of
+/// a `gtk::FlowBox` wrapping it.
+pub fn child_container<CHILDWIDGET: IsA<gtk::Widget>>(child: &CHILDWIDGET) -> Option<gtk::Container> {
+    let parent =
+        match wrapper(child) {
+            Some(wrapper) => wrapper.get_parent(),
+            None => child.get_parent(),
+        };
+    parent.and_then(|parent| parent.downcast::<gtk::Container>().ok())
+}
+
+// Get the widget created by a ListBox or a FlowBox to hold `child`.
+fn wrapper<CHILDWIDGET: IsA<gtk::Widget>>(child: &CHILDWIDGET) -> Option<gtk::Container> {
+    child.get_parent()
+        .filter(|parent| parent.is::<gtk::ListBoxRow>() || parent.is::<gtk::FlowBoxChild>())
+        .and_then(|parent| parent.downcast::<gtk::Container>().ok())
+}
+
+fn container_class(container: &gtk::Container) -> *mut GObjectClass {
+    let instance: *mut GTypeInstance = container.to_glib_none().0 as *mut _;
+    unsafe { (*instance).g_class as *mut GObjectClass }
+}
+
+// Get the value of the child properties of `child` that can be set back, by name.
+fn child_properties(container: &gtk::Container, child: &gtk::Widget) -> Vec<(CString, Value)> {
+    let mut properties = vec![];
+    unsafe {
+        let mut count = 0;
+        let specs = gtk_container_class_list_child_properties(container_class(container), &mut count);
+        for index in 0..count as isize {
+            let spec = *specs.offset(index);
+            if (*spec).flags & G_PARAM_READWRITE == G_PARAM_READWRITE {
+                let mut value = Value::from_type(from_glib((*spec).value_type));
+                gtk_container_child_get_property(container.to_glib_none().0, child.to_glib_none().0, (*spec).name,
+                    value.to_glib_none_mut().0);
+                properties.push((CStr::from_ptr((*spec).name).to_owned(), value));
+            }
+        }
+        g_free(specs as gpointer);
+    }
+    properties
+}
+
+// Get the integer child property giving the position of the children of `container`, if any.
+fn position_property(container: &gtk::Container) -> Option<CString> {
+    ["position", "index"].iter()
+        .map(|name| CString::new(*name).expect("property name"))
+        .find(|name| unsafe {
+            let spec = gtk_container_class_find_child_property(container_class(container), name.as_ptr());
+            !spec.is_null() && from_glib::<_, Type>((*spec).value_type) == Type::I32
+        })
+}
+
+fn set_child_property(container: &gtk::Container, child: &gtk::Widget, name: &CStr, value: &Value) {
+    unsafe {
+        gtk_container_child_set_property(container.to_glib_none().0, child.to_glib_none().0, name.as_ptr(),
+            value.to_glib_none().0);
     }
 }
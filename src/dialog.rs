@@ -0,0 +1,325 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use futures::{Async, Future, Poll};
+use futures::unsync::oneshot;
+use glib::Cast;
+use gtk::{
+    self,
+    DialogExt,
+    FileChooserExt,
+    GtkWindowExt,
+    IsA,
+    ResponseType,
+    WidgetExt,
+};
+
+use super::{Component, DisplayVariant, Relm, Update, create_component};
+use widget::Widget;
+
+/// Trait to implement for a relm `Widget` whose root is a `gtk::Dialog` so that it can be shown
+/// with [`dialog()`](fn.dialog.html).
+pub trait DialogWidget: Widget
+    where Self::Root: IsA<gtk::Dialog>,
+{
+    /// The type of the result of the dialog.
+    type Output;
+
+    /// Get the result of the dialog from a message it received, usually the one sent when a
+    /// button of the dialog is clicked.
+    /// The dialog is closed as soon as this function returns `Some`.
+    fn output(msg: &Self::Msg) -> Option<Self::Output>;
+}
+
+/// Future resolving to the result of a dialog shown with [`dialog()`](fn.dialog.html).
+///
+/// It fails if the dialog is destroyed before returning a result.
+pub struct DialogFuture<OUTPUT> {
+    receiver: oneshot::Receiver<OUTPUT>,
+}
+
+impl<OUTPUT> Future for DialogFuture<OUTPUT> {
+    type Item = OUTPUT;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<OUTPUT, ()> {
+        match self.receiver.poll() {
+            Ok(Async::Ready(output)) => Ok(Async::Ready(output)),
+            Ok(Async::NotReady) => Ok(Async::NotReady),
+            Err(_) => Err(()),
+        }
+    }
+}
+
+/// Show the relm dialog `DIALOG` created with `model_param` and get a future resolving to its
+/// result.
+///
+/// The dialog is kept alive until it returns a result or it is destroyed, so the future can
+/// simply be connected to a message:
+///
+/// ```ignore
+/// relm.connect_exec_ignore_err(relm::dialog::<Confirm, _>(&relm, "Delete the file?".to_string()),
+///     Msg::Delete);
+/// ```
+pub fn dialog<DIALOG, WIDGET>(relm: &Relm<WIDGET>, model_param: DIALOG::ModelParam) -> DialogFuture<DIALOG::Output>
+    where DIALOG: DialogWidget + 'static,
+          DIALOG::Msg: DisplayVariant + 'static,
+          DIALOG::Output: 'static,
+          DIALOG::Root: IsA<gtk::Dialog>,
+          WIDGET: Widget,
+{
+    let component = create_component::<DIALOG, WIDGET>(relm, model_param);
+    let dialog: gtk::Dialog = component.widget().clone().upcast();
+    let (sender, receiver) = oneshot::channel();
+    let state: Rc<RefCell<Option<(Component<DIALOG>, oneshot::Sender<DIALOG::Output>)>>> =
+        Rc::new(RefCell::new(None));
+
+    {
+        let dialog = dialog.clone();
+        let state = state.clone();
+        component.stream().observe(move |msg| {
+            if let Some(output) = DIALOG::output(msg) {
+                // NOTE: take the state out before destroying the dialog since the destroy handler
+                // accesses it.
+                let state = state.borrow_mut().take();
+                if let Some((component, sender)) = state {
+                    dialog.destroy();
+                    let _ = sender.send(output);
+                    drop(component);
+                }
+            }
+        });
+    }
+
+    {
+        let state = state.clone();
+        dialog.connect_destroy(move |_| {
+            // Dropping the sender makes the future fail.
+            let _ = state.borrow_mut().take();
+        });
+    }
+
+    *state.borrow_mut() = Some((component, sender));
+    dialog.present();
+
+    DialogFuture {
+        receiver,
+    }
+}
+
+/// Parameters of a [`FileChooser`](struct.FileChooser.html) dialog.
+#[derive(Clone)]
+pub struct FileChooserParams {
+    /// Whether to open or save files, or select a folder.
+    pub action: gtk::FileChooserAction,
+    /// Whether more than one file can be selected.
+    pub multiple: bool,
+    /// The window the dialog is transient for.
+    pub parent: Option<gtk::Window>,
+    /// The title of the dialog.
+    pub title: String,
+}
+
+/// Messages of the [`FileChooser`](struct.FileChooser.html) dialog.
+pub enum FileChooserMsg {
+    /// A button was clicked with the selected files.
+    Response(ResponseType, Vec<PathBuf>),
+}
+
+impl DisplayVariant for FileChooserMsg {
+    fn display_variant(&self) -> &'static str {
+        match *self {
+            FileChooserMsg::Response(..) => "Response",
+        }
+    }
+}
+
+/// A relm dialog to choose files, resolving to the selected files, which are empty if the dialog
+/// is cancelled.
+///
+/// ```ignore
+/// relm.connect_exec_ignore_err(relm::dialog::<FileChooser, _>(&relm, FileChooserParams {
+///     action: gtk::FileChooserAction::Open,
+///     multiple: false,
+///     parent: Some(window.clone()),
+///     title: "Open a file".to_string(),
+/// }), Msg::Open);
+/// ```
+pub struct FileChooser {
+    dialog: gtk::FileChooserDialog,
+}
+
+impl Update for FileChooser {
+    type Model = FileChooserParams;
+    type ModelParam = FileChooserParams;
+    type Msg = FileChooserMsg;
+
+    fn model(_: &Relm<Self>, params: FileChooserParams) -> FileChooserParams {
+        params
+    }
+
+    fn update(&mut self, _event: FileChooserMsg) {
+    }
+}
+
+impl Widget for FileChooser {
+    type Root = gtk::FileChooserDialog;
+
+    fn root(&self) -> Self::Root {
+        self.dialog.clone()
+    }
+
+    fn view(relm: &Relm<Self>, model: Self::Model) -> Self {
+        let dialog = gtk::FileChooserDialog::new(Some(model.title.as_str()), model.parent.as_ref(), model.action);
+        let accept_label =
+            match model.action {
+                gtk::FileChooserAction::Save => "_Save",
+                gtk::FileChooserAction::SelectFolder => "_Select",
+                _ => "_Open",
+            };
+        dialog.add_button("_Cancel", ResponseType::Cancel.into());
+        dialog.add_button(accept_label, ResponseType::Accept.into());
+        dialog.set_default_response(ResponseType::Accept.into());
+        dialog.set_modal(true);
+        dialog.set_select_multiple(model.multiple);
+
+        let stream = relm.stream().downgrade();
+        dialog.connect_response(move |dialog, response| {
+            let response = ResponseType::from(response);
+            let files =
+                if response == ResponseType::Accept {
+                    dialog.get_filenames()
+                }
+                else {
+                    vec![]
+                };
+            if let Some(stream) = stream.upgrade() {
+                stream.emit(FileChooserMsg::Response(response, files));
+            }
+        });
+
+        FileChooser {
+            dialog,
+        }
+    }
+}
+
+impl DialogWidget for FileChooser {
+    type Output = Vec<PathBuf>;
+
+    fn output(msg: &FileChooserMsg) -> Option<Vec<PathBuf>> {
+        match *msg {
+            FileChooserMsg::Response(_, ref files) => Some(files.clone()),
+        }
+    }
+}
+
+/// Parameters of a [`MessageBox`](struct.MessageBox.html) dialog.
+#[derive(Clone)]
+pub struct MessageBoxParams {
+    /// The buttons shown in the dialog.
+    pub buttons: gtk::ButtonsType,
+    /// The kind of message, which determines the icon of the dialog.
+    pub message_type: gtk::MessageType,
+    /// The window the dialog is transient for.
+    pub parent: Option<gtk::Window>,
+    /// The text of the message.
+    pub text: String,
+}
+
+/// Messages of the [`MessageBox`](struct.MessageBox.html) dialog.
+pub enum MessageBoxMsg {
+    /// A button was clicked or the dialog was closed.
+    Response(ResponseType),
+}
+
+impl DisplayVariant for MessageBoxMsg {
+    fn display_variant(&self) -> &'static str {
+        match *self {
+            MessageBoxMsg::Response(_) => "Response",
+        }
+    }
+}
+
+/// A relm message dialog, resolving to the response of the button clicked by the user.
+///
+/// ```ignore
+/// relm.connect_exec_ignore_err(relm::dialog::<MessageBox, _>(&relm, MessageBoxParams {
+///     buttons: gtk::ButtonsType::YesNo,
+///     message_type: gtk::MessageType::Question,
+///     parent: Some(window.clone()),
+///     text: "Quit without saving?".to_string(),
+/// }), |response| Msg::QuitConfirmed(response == gtk::ResponseType::Yes));
+/// ```
+pub struct MessageBox {
+    dialog: gtk::MessageDialog,
+}
+
+impl Update for MessageBox {
+    type Model = MessageBoxParams;
+    type ModelParam = MessageBoxParams;
+    type Msg = MessageBoxMsg;
+
+    fn model(_: &Relm<Self>, params: MessageBoxParams) -> MessageBoxParams {
+        params
+    }
+
+    fn update(&mut self, _event: MessageBoxMsg) {
+    }
+}
+
+impl Widget for MessageBox {
+    type Root = gtk::MessageDialog;
+
+    fn root(&self) -> Self::Root {
+        self.dialog.clone()
+    }
+
+    fn view(relm: &Relm<Self>, model: Self::Model) -> Self {
+        let dialog = gtk::MessageDialog::new(model.parent.as_ref(), gtk::DialogFlags::MODAL, model.message_type,
+            model.buttons, &model.text);
+
+        let stream = relm.stream().downgrade();
+        dialog.connect_response(move |_, response| {
+            if let Some(stream) = stream.upgrade() {
+                stream.emit(MessageBoxMsg::Response(ResponseType::from(response)));
+            }
+        });
+
+        MessageBox {
+            dialog,
+        }
+    }
+}
+
+impl DialogWidget for MessageBox {
+    type Output = ResponseType;
+
+    fn output(msg: &MessageBoxMsg) -> Option<ResponseType> {
+        match *msg {
+            MessageBoxMsg::Response(response) => Some(response),
+        }
+    }
+}
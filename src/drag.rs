@@ -0,0 +1,321 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::ffi::CString;
+
+use gdk::{DragAction, ModifierType};
+use glib::{Object, ObjectExt, SignalHandlerId};
+use glib::translate::ToGlibPtr;
+use glib_sys::gpointer;
+use gobject_sys::{GObject, g_object_get_data, g_object_set_data, g_object_set_data_full, g_object_steal_data};
+use gtk::{
+    self,
+    BoxExt,
+    ContainerExt,
+    DestDefaults,
+    IsA,
+    OrientableExt,
+    SelectionData,
+    TargetEntry,
+    TargetFlags,
+    TargetList,
+    WidgetExt,
+};
+
+use super::{DisplayVariant, Relm};
+use widget::Widget;
+
+const DRAG_DATA_KEY: &str = "relm-drag-data";
+const REORDERABLE_KEY: &str = "relm-reorderable";
+const REORDER_HANDLERS_KEY: &str = "relm-reorder-handlers";
+const REORDER_TARGET: &str = "application/x-relm-reorder";
+
+const INFO_TEXT: u32 = 0;
+const INFO_URIS: u32 = 1;
+const INFO_CUSTOM: u32 = 2;
+const INFO_REORDER: u32 = 3;
+
+/// Data transferred by drag-and-drop.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DragData {
+    /// Data of an application-defined target, with the name of the target.
+    Custom(String, Vec<u8>),
+    /// Text.
+    Text(String),
+    /// A list of URIs, like files dragged from a file manager.
+    Uris(Vec<String>),
+}
+
+/// Kind of data accepted by a drag source or a drop target.
+///
+/// It can be created from its name: `"text"`, `"uris"` or any other name for an
+/// application-defined target, which only works within the same application.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DragTarget {
+    /// Application-defined target.
+    Custom(String),
+    /// Text.
+    Text,
+    /// A list of URIs.
+    Uris,
+}
+
+impl<'a> From<&'a str> for DragTarget {
+    fn from(name: &'a str) -> Self {
+        match name {
+            "text" => DragTarget::Text,
+            "uris" => DragTarget::Uris,
+            _ => DragTarget::Custom(name.to_string()),
+        }
+    }
+}
+
+/// Drag-and-drop methods for GTK+ widgets.
+///
+/// In the `view!` macro, the targets are set with the `#[drag_source="text"]` and
+/// `#[drop_target="text, uris"]` attributes, the dragged data with the `drag_data` property and the
+/// dropped data is received with the `drop(_, data)` event:
+///
+/// ```ignore
+/// #[drop_target="uris"]
+/// gtk::Label {
+///     drop(_, data) => Dropped(data),
+/// },
+/// ```
+pub trait DragExt {
+    /// Call `callback` with the data dropped on this widget.
+    fn connect_drop<CALLBACK: Fn(&Self, DragData) + 'static>(&self, callback: CALLBACK) -> SignalHandlerId;
+
+    /// Set the data sent when this widget is dragged.
+    fn set_drag_data(&self, data: DragData);
+
+    /// Allow dragging this widget to the drop targets accepting one of `targets`.
+    fn set_drag_source(&self, targets: &[DragTarget]);
+
+    /// Allow dropping data of one of `targets` on this widget.
+    fn set_drop_target(&self, targets: &[DragTarget]);
+}
+
+impl<W: IsA<gtk::Widget> + IsA<Object>> DragExt for W {
+    fn connect_drop<CALLBACK: Fn(&Self, DragData) + 'static>(&self, callback: CALLBACK) -> SignalHandlerId {
+        self.connect_drag_data_received(move |widget, _, _, _, selection, info, _| {
+            let data =
+                match info {
+                    INFO_TEXT => selection.get_text().map(DragData::Text),
+                    INFO_URIS => Some(DragData::Uris(selection.get_uris())),
+                    INFO_CUSTOM => Some(DragData::Custom(selection.get_target().name(), selection.get_data())),
+                    _ => None,
+                };
+            if let Some(data) = data {
+                callback(widget, data);
+            }
+        })
+    }
+
+    fn set_drag_data(&self, data: DragData) {
+        let key = CString::new(DRAG_DATA_KEY).expect("drag data key");
+        let object: *mut GObject = self.to_glib_none().0;
+        let data = Box::into_raw(Box::new(data));
+        // NOTE: the previous data is freed by GLib.
+        unsafe {
+            g_object_set_data_full(object, key.as_ptr(), data as gpointer, Some(free_drag_data));
+        }
+    }
+
+    fn set_drag_source(&self, targets: &[DragTarget]) {
+        self.drag_source_set(ModifierType::BUTTON1_MASK, &[], DragAction::COPY | DragAction::MOVE);
+        self.drag_source_set_target_list(Some(&target_list(targets)));
+        let _ = self.connect_drag_data_get(|widget, _, selection, info, _| {
+            if let Some(data) = drag_data(widget) {
+                set_selection_data(selection, info, data);
+            }
+        });
+    }
+
+    fn set_drop_target(&self, targets: &[DragTarget]) {
+        self.drag_dest_set(DestDefaults::ALL, &[], DragAction::COPY | DragAction::MOVE);
+        self.drag_dest_set_target_list(Some(&target_list(targets)));
+    }
+}
+
+/// Allow reordering the children of `container` by drag-and-drop.
+///
+/// The message created by `callback` with the previous and the new position of the moved child is
+/// sent to `relm` after the child is moved, so that the model can be updated accordingly.
+/// The children added with `ContainerExt::add()`, which is used by `ContainerWidget::add_widget()`,
+/// can be dragged as well as the existing ones.
+/// Calling this function again for the same container replaces the previous `callback`.
+pub fn connect_reorder<CALLBACK, WIDGET>(container: &gtk::Box, relm: &Relm<WIDGET>, callback: CALLBACK)
+    where CALLBACK: Fn(usize, usize) -> WIDGET::Msg + 'static,
+          WIDGET: Widget,
+          WIDGET::Msg: DisplayVariant + 'static,
+{
+    disconnect_reorder_handlers(container);
+    for child in container.get_children() {
+        set_reorderable(&child);
+    }
+    let add_handler = container.connect_add(|_, child| set_reorderable(child));
+
+    let entry = TargetEntry::new(REORDER_TARGET, TargetFlags::SAME_APP, INFO_REORDER);
+    container.drag_dest_set(DestDefaults::ALL, &[entry], DragAction::MOVE);
+    let stream = relm.stream().downgrade();
+    let drop_handler = container.connect_drag_data_received(move |container, _, x, y, selection, info, _| {
+        if info != INFO_REORDER {
+            return;
+        }
+        let from = String::from_utf8(selection.get_data()).ok()
+            .and_then(|position| position.parse::<usize>().ok());
+        let children = container.get_children();
+        if let Some(from) = from.filter(|&from| from < children.len()) {
+            let to = drop_position(container, &children, from, x, y);
+            if to != from {
+                container.reorder_child(&children[from], to as i32);
+                if let Some(stream) = stream.upgrade() {
+                    stream.emit(callback(from, to));
+                }
+            }
+        }
+    });
+    let key = CString::new(REORDER_HANDLERS_KEY).expect("reorder handlers key");
+    let object: *mut GObject = container.to_glib_none().0;
+    let handlers = Box::into_raw(Box::new(vec![add_handler, drop_handler]));
+    unsafe {
+        g_object_set_data_full(object, key.as_ptr(), handlers as gpointer, Some(free_reorder_handlers));
+    }
+}
+
+// Disconnect the handlers connected by a previous call to connect_reorder(), so that the moves are
+// only sent once.
+fn disconnect_reorder_handlers(container: &gtk::Box) {
+    let key = CString::new(REORDER_HANDLERS_KEY).expect("reorder handlers key");
+    let object: *mut GObject = container.to_glib_none().0;
+    let handlers = unsafe { g_object_steal_data(object, key.as_ptr()) as *mut Vec<SignalHandlerId> };
+    if !handlers.is_null() {
+        let handlers = unsafe { Box::from_raw(handlers) };
+        for handler in *handlers {
+            container.disconnect(handler);
+        }
+    }
+}
+
+// Get the position of the dragged child when it is dropped at (x, y), which is relative to the
+// container.
+fn drop_position(container: &gtk::Box, children: &[gtk::Widget], from: usize, x: i32, y: i32) -> usize {
+    let origin = container.get_allocation();
+    let vertical = container.get_orientation() == gtk::Orientation::Vertical;
+    let position = children.iter()
+        .position(|child| {
+            let allocation = child.get_allocation();
+            if vertical {
+                y < allocation.y - origin.y + allocation.height / 2
+            }
+            else {
+                x < allocation.x - origin.x + allocation.width / 2
+            }
+        })
+        .unwrap_or_else(|| children.len());
+    // The dragged child is removed before being inserted at its new position.
+    if position > from {
+        position - 1
+    }
+    else {
+        position
+    }
+}
+
+fn drag_data<W: IsA<Object>>(widget: &W) -> Option<DragData> {
+    let key = CString::new(DRAG_DATA_KEY).expect("drag data key");
+    let object: *mut GObject = widget.to_glib_none().0;
+    unsafe {
+        let data = g_object_get_data(object, key.as_ptr()) as *const DragData;
+        if data.is_null() {
+            None
+        }
+        else {
+            Some((*data).clone())
+        }
+    }
+}
+
+unsafe extern "C" fn free_drag_data(data: gpointer) {
+    drop(Box::from_raw(data as *mut DragData));
+}
+
+unsafe extern "C" fn free_reorder_handlers(handlers: gpointer) {
+    drop(Box::from_raw(handlers as *mut Vec<SignalHandlerId>));
+}
+
+fn set_reorderable(child: &gtk::Widget) {
+    // NOTE: a child is only made reorderable once, since it sends its position to the container
+    // from every handler connected.
+    let key = CString::new(REORDERABLE_KEY).expect("reorderable key");
+    let object: *mut GObject = child.to_glib_none().0;
+    unsafe {
+        if !g_object_get_data(object, key.as_ptr()).is_null() {
+            return;
+        }
+        g_object_set_data(object, key.as_ptr(), object as gpointer);
+    }
+    let entry = TargetEntry::new(REORDER_TARGET, TargetFlags::SAME_APP, INFO_REORDER);
+    child.drag_source_set(ModifierType::BUTTON1_MASK, &[entry], DragAction::MOVE);
+    let _ = child.connect_drag_data_get(|child, _, selection, info, _| {
+        if info != INFO_REORDER {
+            return;
+        }
+        let position = child.get_parent()
+            .and_then(|parent| parent.get_children().iter().position(|sibling| sibling == child));
+        if let Some(position) = position {
+            selection.set(&selection.get_target(), 8, position.to_string().as_bytes());
+        }
+    });
+}
+
+fn set_selection_data(selection: &SelectionData, info: u32, data: DragData) {
+    match (info, data) {
+        (INFO_TEXT, DragData::Text(text)) => {
+            let _ = selection.set_text(&text);
+        },
+        (INFO_TEXT, DragData::Uris(uris)) => {
+            let _ = selection.set_text(&uris.join("\n"));
+        },
+        (INFO_URIS, DragData::Uris(uris)) => {
+            let uris: Vec<_> = uris.iter().map(String::as_str).collect();
+            let _ = selection.set_uris(&uris);
+        },
+        (INFO_CUSTOM, DragData::Custom(ref name, ref bytes)) if *name == selection.get_target().name() =>
+            selection.set(&selection.get_target(), 8, bytes),
+        (INFO_CUSTOM, DragData::Text(text)) => selection.set(&selection.get_target(), 8, text.as_bytes()),
+        _ => (),
+    }
+}
+
+fn target_list(targets: &[DragTarget]) -> TargetList {
+    let list = TargetList::new(&[]);
+    for target in targets {
+        match *target {
+            DragTarget::Custom(ref name) =>
+                list.add_table(&[TargetEntry::new(name, TargetFlags::SAME_APP, INFO_CUSTOM)]),
+            DragTarget::Text => list.add_text_targets(INFO_TEXT),
+            DragTarget::Uris => list.add_uri_targets(INFO_URIS),
+        }
+    }
+    list
+}
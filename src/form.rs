@@ -0,0 +1,447 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::str::FromStr;
+
+use futures::Future;
+use glib::{self, Cast, Continue, SignalHandlerId};
+use gtk::{
+    self,
+    ComboBoxExt,
+    ComboBoxTextExt,
+    EditableSignals,
+    EntryExt,
+    IsA,
+    LabelExt,
+    SpinButtonExt,
+    SpinButtonSignals,
+};
+
+use signal_stream::SignalGuard;
+use style::toggle_style_class;
+use super::{Relm, Update};
+use self::Event::*;
+
+const DEFAULT_DEBOUNCE: u32 = 300;
+
+/// Style class added to the widget of an invalid field.
+pub const ERROR_CLASS: &str = "error";
+
+type AsyncRule = Box<Fn(&str) -> Box<Future<Item=(), Error=String>>>;
+
+/// Widget that can be used as a field of a [`Form`](struct.Form.html).
+pub trait FormField {
+    /// Call `callback` every time the value of the field changes.
+    /// The handler is disconnected when the field is dropped.
+    fn connect_field_changed<CALLBACK: Fn() + 'static>(&self, callback: CALLBACK) -> SignalHandlerId;
+
+    /// Get the value of the field, as given to the validation rules.
+    fn field_value(&self) -> String;
+}
+
+impl FormField for gtk::ComboBox {
+    fn connect_field_changed<CALLBACK: Fn() + 'static>(&self, callback: CALLBACK) -> SignalHandlerId {
+        self.connect_changed(move |_| callback())
+    }
+
+    /// Get the id of the active item.
+    fn field_value(&self) -> String {
+        self.get_active_id().unwrap_or_default()
+    }
+}
+
+impl FormField for gtk::ComboBoxText {
+    fn connect_field_changed<CALLBACK: Fn() + 'static>(&self, callback: CALLBACK) -> SignalHandlerId {
+        self.connect_changed(move |_| callback())
+    }
+
+    /// Get the text of the active item.
+    fn field_value(&self) -> String {
+        self.get_active_text().unwrap_or_default()
+    }
+}
+
+impl FormField for gtk::Entry {
+    fn connect_field_changed<CALLBACK: Fn() + 'static>(&self, callback: CALLBACK) -> SignalHandlerId {
+        self.connect_changed(move |_| callback())
+    }
+
+    fn field_value(&self) -> String {
+        self.get_text().unwrap_or_default()
+    }
+}
+
+impl FormField for gtk::SpinButton {
+    fn connect_field_changed<CALLBACK: Fn() + 'static>(&self, callback: CALLBACK) -> SignalHandlerId {
+        self.connect_value_changed(move |_| callback())
+    }
+
+    fn field_value(&self) -> String {
+        self.get_value().to_string()
+    }
+}
+
+/// Validation rule of a field: it returns the error message when the value is invalid.
+pub struct Rule(Box<Fn(&str) -> Result<(), String>>);
+
+impl Rule {
+    /// Create a rule from a function returning the error message when the value is invalid.
+    pub fn new<VALIDATE: Fn(&str) -> Result<(), String> + 'static>(validate: VALIDATE) -> Self {
+        Rule(Box::new(validate))
+    }
+
+    /// The value must have at least `length` characters.
+    pub fn min_length(length: usize, message: &str) -> Self {
+        let message = message.to_string();
+        Rule::new(move |value| {
+            if value.chars().count() >= length {
+                Ok(())
+            }
+            else {
+                Err(message.clone())
+            }
+        })
+    }
+
+    /// The value must be parsable as a `T`.
+    pub fn parse<T: FromStr>(message: &str) -> Self {
+        let message = message.to_string();
+        Rule::new(move |value| value.trim().parse::<T>().map(|_| ()).map_err(|_| message.clone()))
+    }
+
+    /// The value must not be empty nor only contain whitespaces.
+    pub fn required(message: &str) -> Self {
+        let message = message.to_string();
+        Rule::new(move |value| {
+            if value.trim().is_empty() {
+                Err(message.clone())
+            }
+            else {
+                Ok(())
+            }
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum FieldState {
+    Invalid(String),
+    NotValidated,
+    Valid,
+    Validating,
+}
+
+/// Field of a [`Form`](struct.Form.html), created by [`Form::field()`](struct.Form.html#method.field).
+pub struct Field {
+    // NOTE: only used to disconnect the changed handler when the field is dropped, so that it
+    // does not send messages to a form which was replaced.
+    _changed_handler: SignalGuard,
+    async_rule: Option<AsyncRule>,
+    debounce: Rc<Cell<u32>>,
+    error_label: Option<gtk::Label>,
+    generation: u64,
+    name: String,
+    pending_validation: Rc<RefCell<Option<glib::SourceId>>>,
+    rules: Vec<Rule>,
+    state: FieldState,
+    value: Box<Fn() -> String>,
+    widget: gtk::Widget,
+}
+
+impl Field {
+    /// Add an asynchronous validation rule, like checking whether a username is taken.
+    /// It is only run when the other rules pass: the future returns the error message when the
+    /// value is invalid.
+    pub fn async_rule<FUTURE, VALIDATE>(&mut self, validate: VALIDATE) -> &mut Self
+        where FUTURE: Future<Item=(), Error=String> + 'static,
+              VALIDATE: Fn(&str) -> FUTURE + 'static,
+    {
+        self.async_rule = Some(Box::new(move |value| Box::new(validate(value))));
+        self
+    }
+
+    /// Set the delay, in milliseconds, between the last change of the field and its validation.
+    pub fn debounce(&mut self, delay: u32) -> &mut Self {
+        self.debounce.set(delay);
+        self
+    }
+
+    /// Show the error message of the field in `label`.
+    pub fn error_label(&mut self, label: &gtk::Label) -> &mut Self {
+        self.error_label = Some(label.clone());
+        self
+    }
+
+    /// Add a validation rule.
+    /// The rules are run in the order they were added and the first error is shown.
+    pub fn rule(&mut self, rule: Rule) -> &mut Self {
+        self.rules.push(rule);
+        self
+    }
+
+    fn cancel_pending_validation(&self) {
+        if let Some(source) = self.pending_validation.borrow_mut().take() {
+            glib::source_remove(source);
+        }
+    }
+
+    fn set_state(&mut self, state: FieldState) {
+        let error =
+            if let FieldState::Invalid(ref error) = state {
+                Some(error.clone())
+            }
+            else {
+                None
+            };
+        toggle_style_class(&self.widget, ERROR_CLASS, error.is_some());
+        if let Some(ref label) = self.error_label {
+            label.set_text(error.as_ref().map(String::as_str).unwrap_or(""));
+        }
+        self.state = state;
+    }
+}
+
+impl Drop for Field {
+    fn drop(&mut self) {
+        self.cancel_pending_validation();
+    }
+}
+
+#[derive(Debug)]
+enum Event {
+    AsyncResult(usize, u64, Result<(), String>),
+    Validate(usize),
+}
+
+/// Message of a [`Form`](struct.Form.html), to give back to
+/// [`Form::update()`](struct.Form.html#method.update).
+#[derive(Debug)]
+pub struct FormMsg(Event);
+
+/// Validation of the fields of a form.
+///
+/// The form is usually stored in the model: it sends its `FormMsg`s to the component through the
+/// message created by the `to_msg` function given to [`Form::new()`](struct.Form.html#method.new),
+/// and they must be given back to [`update()`](struct.Form.html#method.update).
+///
+/// The fields are validated after they change, with a delay to avoid validating on every key
+/// press, and when the form is submitted.
+/// The style class `error` is added to the widget of an invalid field.
+pub struct Form<UPDATE: Update> {
+    fields: Vec<Field>,
+    relm: Relm<UPDATE>,
+    submit_msg: Option<UPDATE::Msg>,
+    to_msg: Rc<Fn(FormMsg) -> UPDATE::Msg>,
+}
+
+impl<UPDATE> Form<UPDATE>
+    where UPDATE: Update + 'static,
+          UPDATE::Msg: 'static,
+{
+    /// Create a form sending its messages to the component of `relm` through `to_msg`.
+    pub fn new<TOMSG>(relm: &Relm<UPDATE>, to_msg: TOMSG) -> Self
+        where TOMSG: Fn(FormMsg) -> UPDATE::Msg + 'static,
+    {
+        Form {
+            fields: vec![],
+            relm: relm.clone(),
+            submit_msg: None,
+            to_msg: Rc::new(to_msg),
+        }
+    }
+
+    /// Get the error message of the field `name`, if it is invalid.
+    pub fn error(&self, name: &str) -> Option<&str> {
+        self.fields.iter()
+            .find(|field| field.name == name)
+            .and_then(|field| match field.state {
+                FieldState::Invalid(ref error) => Some(error.as_str()),
+                _ => None,
+            })
+    }
+
+    /// Add the field `name` using the value of `widget`.
+    pub fn field<FIELD>(&mut self, name: &str, widget: &FIELD) -> &mut Field
+        where FIELD: Clone + FormField + IsA<gtk::Widget> + 'static,
+    {
+        let index = self.fields.len();
+        let debounce = Rc::new(Cell::new(DEFAULT_DEBOUNCE));
+        let pending_validation = Rc::new(RefCell::new(None));
+        let handler_id = {
+            let debounce = debounce.clone();
+            let pending_validation = pending_validation.clone();
+            let relm = self.relm.clone();
+            let to_msg = self.to_msg.clone();
+            widget.connect_field_changed(move || {
+                if let Some(source) = pending_validation.borrow_mut().take() {
+                    glib::source_remove(source);
+                }
+                let pending = pending_validation.clone();
+                let relm = relm.clone();
+                let to_msg = to_msg.clone();
+                let source = gtk::timeout_add(debounce.get(), move || {
+                    let _ = pending.borrow_mut().take();
+                    relm.stream().emit(to_msg(FormMsg(Validate(index))));
+                    Continue(false)
+                });
+                *pending_validation.borrow_mut() = Some(source);
+            })
+        };
+        let value_widget = widget.clone();
+        let widget: gtk::Widget = widget.clone().upcast();
+        self.fields.push(Field {
+            _changed_handler: SignalGuard::new(&widget, handler_id),
+            async_rule: None,
+            debounce,
+            error_label: None,
+            generation: 0,
+            name: name.to_string(),
+            pending_validation,
+            rules: vec![],
+            state: FieldState::NotValidated,
+            value: Box::new(move || value_widget.field_value()),
+            widget,
+        });
+        &mut self.fields[index]
+    }
+
+    /// Check whether every field was validated successfully.
+    pub fn is_valid(&self) -> bool {
+        self.fields.iter().all(|field| field.state == FieldState::Valid)
+    }
+
+    /// Check whether an asynchronous validation rule is running.
+    pub fn is_validating(&self) -> bool {
+        self.fields.iter().any(|field| field.state == FieldState::Validating)
+    }
+
+    /// Validate every field and send `msg` to the component if they are all valid.
+    /// When asynchronous rules are running, `msg` is sent once they all succeed.
+    pub fn submit(&mut self, msg: UPDATE::Msg) {
+        for index in 0..self.fields.len() {
+            self.validate(index);
+        }
+        self.submit_msg = Some(msg);
+        self.check_submit();
+    }
+
+    /// Handle a message of the form.
+    /// The messages of the fields which do not exist anymore are ignored.
+    pub fn update(&mut self, msg: FormMsg) {
+        match msg.0 {
+            AsyncResult(index, generation, result) => {
+                if let Some(field) = self.fields.get_mut(index) {
+                    // Ignore the result of a value that changed since the validation started.
+                    if field.generation == generation {
+                        match result {
+                            Ok(()) => field.set_state(FieldState::Valid),
+                            Err(error) => field.set_state(FieldState::Invalid(error)),
+                        }
+                    }
+                }
+            },
+            Validate(index) => self.validate(index),
+        }
+        self.check_submit();
+    }
+
+    fn check_submit(&mut self) {
+        if self.is_validating() {
+            return;
+        }
+        if let Some(msg) = self.submit_msg.take() {
+            if self.is_valid() {
+                self.relm.stream().emit(msg);
+            }
+        }
+    }
+
+    fn validate(&mut self, index: usize) {
+        let field =
+            match self.fields.get_mut(index) {
+                Some(field) => field,
+                None => return,
+            };
+        field.cancel_pending_validation();
+        field.generation += 1;
+        let value = (field.value)();
+        let result = field.rules.iter()
+            .map(|rule| (rule.0)(&value))
+            .find(Result::is_err)
+            .unwrap_or(Ok(()));
+        match result {
+            Ok(()) => {
+                let future =
+                    match field.async_rule {
+                        Some(ref async_rule) => async_rule(&value),
+                        None => {
+                            field.set_state(FieldState::Valid);
+                            return;
+                        },
+                    };
+                field.set_state(FieldState::Validating);
+                let generation = field.generation;
+                let to_msg = self.to_msg.clone();
+                let to_error_msg = self.to_msg.clone();
+                self.relm.connect_exec(future,
+                    move |()| to_msg(FormMsg(AsyncResult(index, generation, Ok(())))),
+                    move |error| to_error_msg(FormMsg(AsyncResult(index, generation, Err(error)))));
+            },
+            Err(error) => field.set_state(FieldState::Invalid(error)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rule;
+
+    fn check(rule: &Rule, value: &str) -> Result<(), String> {
+        (rule.0)(value)
+    }
+
+    #[test]
+    fn rules() {
+        let rule = Rule::new(|value| if value.contains('@') { Ok(()) } else { Err("Invalid email".to_string()) });
+        assert_eq!(check(&rule, "user@example.com"), Ok(()));
+        assert_eq!(check(&rule, "user"), Err("Invalid email".to_string()));
+
+        let rule = Rule::min_length(3, "Too short");
+        assert_eq!(check(&rule, "abc"), Ok(()));
+        assert_eq!(check(&rule, "été"), Ok(()));
+        assert_eq!(check(&rule, "ab"), Err("Too short".to_string()));
+        assert_eq!(check(&rule, ""), Err("Too short".to_string()));
+
+        let rule = Rule::parse::<u32>("Not a number");
+        assert_eq!(check(&rule, "42"), Ok(()));
+        assert_eq!(check(&rule, " 42 "), Ok(()));
+        assert_eq!(check(&rule, "-1"), Err("Not a number".to_string()));
+        assert_eq!(check(&rule, "4.2"), Err("Not a number".to_string()));
+        assert_eq!(check(&rule, ""), Err("Not a number".to_string()));
+
+        let rule = Rule::required("Required");
+        assert_eq!(check(&rule, "a"), Ok(()));
+        assert_eq!(check(&rule, ""), Err("Required".to_string()));
+        assert_eq!(check(&rule, " \t\n"), Err("Required".to_string()));
+    }
+}
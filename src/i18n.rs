@@ -0,0 +1,231 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::env;
+use std::ffi::{CStr, CString};
+use std::io;
+use std::path::Path;
+use std::ptr;
+use std::rc::{Rc, Weak};
+
+use glib::{Object, ObjectExt};
+use glib_sys::{g_dgettext, g_dngettext, g_dpgettext2};
+use gtk::{self, IsA};
+use libc::{LC_ALL, c_char, c_ulong, setlocale};
+
+use widget::Widget;
+
+// Separator between the context and the message in the message ids of the .mo files.
+const CONTEXT_SEPARATOR: char = '\u{4}';
+
+extern "C" {
+    fn bind_textdomain_codeset(domain: *const c_char, codeset: *const c_char) -> *mut c_char;
+    fn bindtextdomain(domain: *const c_char, directory: *const c_char) -> *mut c_char;
+    fn textdomain(domain: *const c_char) -> *mut c_char;
+}
+
+thread_local! {
+    // NOTE: the components are keyed by the id of their stream, to remove them when they are dropped.
+    static LOCALE_COMPONENTS: RefCell<HashMap<usize, Rc<TranslatedComponent>>> = RefCell::new(HashMap::new());
+    static LOCALE_SETTERS: RefCell<Vec<Rc<Fn() -> bool>>> = RefCell::new(vec![]);
+}
+
+trait TranslatedComponent {
+    // Set the translated properties of the component again.
+    // Return false if the component is being updated.
+    fn update_translations(&self) -> bool;
+}
+
+impl<WIDGET: Widget> TranslatedComponent for Weak<RefCell<WIDGET>> {
+    fn update_translations(&self) -> bool {
+        match self.upgrade() {
+            Some(component) => {
+                match component.try_borrow() {
+                    Ok(component) => {
+                        component.on_locale_changed();
+                        true
+                    },
+                    Err(_) => false,
+                }
+            },
+            None => true,
+        }
+    }
+}
+
+// Call Widget::on_locale_changed() on `component`, whose stream is `stream_id`, when the locale is
+// changed.
+pub fn connect_component_locale_changed<WIDGET: Widget + 'static>(stream_id: usize,
+    component: Weak<RefCell<WIDGET>>)
+{
+    LOCALE_COMPONENTS.with(|components| {
+        let _ = components.borrow_mut().insert(stream_id, Rc::new(component));
+    });
+}
+
+// Forget the component whose stream is `stream_id`, since it is dropped.
+pub fn disconnect_component_locale_changed(stream_id: usize) {
+    LOCALE_COMPONENTS.with(|components| {
+        let _ = components.borrow_mut().remove(&stream_id);
+    });
+}
+
+/// Call `setter` with `widget` when the locale is changed with [`set_locale()`](fn.set_locale.html),
+/// to set its translated properties again.
+///
+/// This is used by the `view!` macro for the properties using `tr!()`.
+pub fn connect_locale_changed<SETTER, WIDGET>(widget: &WIDGET, setter: SETTER)
+    where SETTER: Fn(&WIDGET) + 'static,
+          WIDGET: IsA<Object>,
+{
+    let widget = widget.downgrade();
+    let setter: Rc<Fn() -> bool> = Rc::new(move || {
+        match widget.upgrade() {
+            Some(widget) => {
+                setter(&widget);
+                true
+            },
+            None => false,
+        }
+    });
+    LOCALE_SETTERS.with(|setters| setters.borrow_mut().push(setter));
+}
+
+/// Translate `msgid` with the catalog of the text domain.
+pub fn gettext(msgid: &str) -> String {
+    let c_msgid = match CString::new(msgid) {
+        Ok(msgid) => msgid,
+        Err(_) => return msgid.to_string(),
+    };
+    unsafe {
+        from_c_str(g_dgettext(ptr::null(), c_msgid.as_ptr()))
+    }
+}
+
+/// Translate `msgid`, or its plural form `msgid_plural`, according to `count`.
+pub fn ngettext(msgid: &str, msgid_plural: &str, count: u64) -> String {
+    let (c_msgid, c_msgid_plural) =
+        match (CString::new(msgid), CString::new(msgid_plural)) {
+            (Ok(msgid), Ok(msgid_plural)) => (msgid, msgid_plural),
+            _ => return untranslated(msgid, msgid_plural, count),
+        };
+    unsafe {
+        from_c_str(g_dngettext(ptr::null(), c_msgid.as_ptr(), c_msgid_plural.as_ptr(), count as c_ulong))
+    }
+}
+
+/// Translate `msgid`, or its plural form `msgid_plural`, in `context`.
+pub fn npgettext(context: &str, msgid: &str, msgid_plural: &str, count: u64) -> String {
+    let (c_msgid, c_msgid_plural) =
+        match (CString::new(format!("{}{}{}", context, CONTEXT_SEPARATOR, msgid)), CString::new(msgid_plural)) {
+            (Ok(msgid), Ok(msgid_plural)) => (msgid, msgid_plural),
+            _ => return untranslated(msgid, msgid_plural, count),
+        };
+    unsafe {
+        let translation = g_dngettext(ptr::null(), c_msgid.as_ptr(), c_msgid_plural.as_ptr(), count as c_ulong);
+        // NOTE: the message id with the context is returned when there's no translation.
+        if translation == c_msgid.as_ptr() {
+            msgid.to_string()
+        }
+        else {
+            from_c_str(translation)
+        }
+    }
+}
+
+/// Translate `msgid` in `context`, to distinguish identical messages with different meanings.
+pub fn pgettext(context: &str, msgid: &str) -> String {
+    let (c_context, c_msgid) =
+        match (CString::new(context), CString::new(msgid)) {
+            (Ok(context), Ok(msgid)) => (context, msgid),
+            _ => return msgid.to_string(),
+        };
+    unsafe {
+        from_c_str(g_dpgettext2(ptr::null(), c_context.as_ptr(), c_msgid.as_ptr()))
+    }
+}
+
+/// Change the language of the translations, like `"fr"` or `"pt_BR"`, and set the properties
+/// using `tr!()` in the `view!` macros again.
+///
+/// Only the language of the messages changes: this sets the `LANGUAGE` environment variable and
+/// calls `setlocale()` with the locale of the environment to clear the cache of the translations,
+/// so the other categories of the locale, like the formatting of numbers, stay the same.
+/// Since `LANGUAGE` is ignored by gettext when the locale is `C`, `LANG` or `LC_ALL` must be set
+/// to another locale for the translations to be used.
+///
+/// The properties which depend on the model are set again once the current message is
+/// processed, since they read the model of their widget.
+pub fn set_locale(language: &str) {
+    env::set_var("LANGUAGE", language);
+    // NOTE: setlocale() clears the cache of the translations.
+    unsafe {
+        let _ = setlocale(LC_ALL, b"\0".as_ptr() as *const c_char);
+    }
+    let setters: Vec<_> = LOCALE_SETTERS.with(|setters| setters.borrow().clone());
+    let alive_setters: Vec<_> = setters.into_iter()
+        .filter(|setter| setter())
+        .collect();
+    LOCALE_SETTERS.with(|setters| *setters.borrow_mut() = alive_setters);
+
+    // NOTE: set_locale() is usually called from the update() method of a component, which is then
+    // borrowed, so the components are updated from the main loop. A component still being updated
+    // (in a nested main loop) is updated later.
+    let components: Vec<_> = LOCALE_COMPONENTS.with(|components| components.borrow().values().cloned().collect());
+    let components = RefCell::new(components);
+    gtk::idle_add(move || {
+        components.borrow_mut().retain(|component| !component.update_translations());
+        gtk::Continue(!components.borrow().is_empty())
+    });
+}
+
+/// Use the translations of `domain`, which are in `directory/<language>/LC_MESSAGES/<domain>.mo`.
+pub fn set_text_domain<P: AsRef<Path>>(domain: &str, directory: P) -> io::Result<()> {
+    let invalid = |_| io::Error::new(io::ErrorKind::InvalidInput, "unexpected nul byte");
+    let c_domain = CString::new(domain).map_err(invalid)?;
+    let directory = directory.as_ref().to_str()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "the directory is not valid UTF-8"))?;
+    let c_directory = CString::new(directory).map_err(invalid)?;
+    unsafe {
+        if bindtextdomain(c_domain.as_ptr(), c_directory.as_ptr()).is_null() ||
+            bind_textdomain_codeset(c_domain.as_ptr(), b"UTF-8\0".as_ptr() as *const c_char).is_null() ||
+            textdomain(c_domain.as_ptr()).is_null()
+        {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+unsafe fn from_c_str(string: *const c_char) -> String {
+    CStr::from_ptr(string).to_string_lossy().into_owned()
+}
+
+fn untranslated(msgid: &str, msgid_plural: &str, count: u64) -> String {
+    if count == 1 {
+        msgid.to_string()
+    }
+    else {
+        msgid_plural.to_string()
+    }
+}
@@ -0,0 +1,375 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Debug window listing the live relm components.
+//!
+//! The components are only tracked once the inspector is enabled, either by calling
+//! `enable_inspector()`/`show_inspector()` or by setting the `RELM_INSPECTOR` environment
+//! variable, in which case the window is shown when the first component is created.
+
+use std::any::{Any, TypeId, type_name};
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::env;
+use std::rc::Rc;
+
+use glib::{Cast, ObjectExt, Type, WeakRef};
+use gtk::{
+    self,
+    BoxExt,
+    ButtonExt,
+    CellLayoutExt,
+    ContainerExt,
+    EntryExt,
+    GtkWindowExt,
+    Inhibit,
+    TreeModelExt,
+    TreeSelectionExt,
+    TreeStoreExt,
+    TreeStoreExtManual,
+    TreeViewColumnExt,
+    TreeViewExt,
+    WidgetExt,
+};
+
+use super::{DisplayVariant, EventStream, WeakEventStream};
+use widget::Widget;
+
+const HISTORY_SIZE: usize = 10;
+const REFRESH_INTERVAL: u32 = 1000;
+
+const ID_COL: u32 = 0;
+const WIDGET_COL: u32 = 1;
+const MODEL_COL: u32 = 2;
+const ROOT_COL: u32 = 3;
+const QUEUE_COL: u32 = 4;
+const MESSAGES_COL: u32 = 5;
+
+thread_local! {
+    static INSPECTOR: RefCell<Inspector> = RefCell::new(Inspector::new());
+}
+
+type MsgParser<MSG> = Rc<Fn(&str) -> Option<MSG>>;
+
+trait InspectedStream {
+    fn inject(&self, msg: &str) -> Result<(), String>;
+    fn is_alive(&self) -> bool;
+    fn queue_len(&self) -> usize;
+}
+
+struct TypedStream<WIDGET: Widget> {
+    stream: WeakEventStream<WIDGET::Msg>,
+}
+
+impl<WIDGET: Widget + 'static> InspectedStream for TypedStream<WIDGET> {
+    fn inject(&self, msg: &str) -> Result<(), String> {
+        let parser = INSPECTOR.with(|inspector| {
+            inspector.borrow().parsers.get(&TypeId::of::<WIDGET>())
+                .and_then(|parser| parser.downcast_ref::<MsgParser<WIDGET::Msg>>())
+                .cloned()
+        });
+        let parser = parser.ok_or_else(|| format!("No message parser set for {}", type_name::<WIDGET>()))?;
+        let msg = parser(msg).ok_or_else(|| format!("Cannot parse message {:?}", msg))?;
+        let stream = self.stream.upgrade().ok_or_else(|| "The component was dropped".to_string())?;
+        stream.emit(msg);
+        Ok(())
+    }
+
+    fn is_alive(&self) -> bool {
+        self.stream.upgrade().map(|stream| !stream.is_closed()).unwrap_or(false)
+    }
+
+    fn queue_len(&self) -> usize {
+        self.stream.upgrade().map(|stream| stream.len()).unwrap_or(0)
+    }
+}
+
+struct ComponentInfo {
+    history: Rc<RefCell<VecDeque<&'static str>>>,
+    id: usize,
+    model_type: &'static str,
+    parent: Option<usize>,
+    root: WeakRef<gtk::Widget>,
+    stream: Rc<InspectedStream>,
+    // The id of the event stream, used to find the parent of the components.
+    stream_id: usize,
+    widget_type: &'static str,
+}
+
+struct Inspector {
+    components: Vec<ComponentInfo>,
+    enabled: bool,
+    next_id: usize,
+    parsers: HashMap<TypeId, Box<Any>>,
+    show_on_register: bool,
+    window: Option<gtk::Window>,
+}
+
+impl Inspector {
+    fn new() -> Self {
+        let from_env = env::var_os("RELM_INSPECTOR").is_some();
+        Inspector {
+            components: vec![],
+            enabled: from_env,
+            next_id: 0,
+            parsers: HashMap::new(),
+            show_on_register: from_env,
+            window: None,
+        }
+    }
+}
+
+/// Start tracking the components created from now on so that they show up in the inspector.
+pub fn enable_inspector() {
+    INSPECTOR.with(|inspector| inspector.borrow_mut().enabled = true);
+}
+
+/// Set the function used to convert the text entered in the inspector to a message of `WIDGET`.
+/// Messages cannot be injected in a component whose widget type has no parser.
+pub fn set_inspector_msg_parser<WIDGET, PARSER>(parser: PARSER)
+    where PARSER: Fn(&str) -> Option<WIDGET::Msg> + 'static,
+          WIDGET: Widget + 'static,
+{
+    let parser: MsgParser<WIDGET::Msg> = Rc::new(parser);
+    INSPECTOR.with(|inspector| {
+        let _ = inspector.borrow_mut().parsers.insert(TypeId::of::<WIDGET>(), Box::new(parser));
+    });
+}
+
+/// Show the inspector window.
+/// This also enables the inspector if it was not already enabled.
+/// GTK+ must be initialized before calling this function.
+pub fn show_inspector() {
+    enable_inspector();
+    let window = INSPECTOR.with(|inspector| inspector.borrow().window.clone());
+    let window = window.unwrap_or_else(|| {
+        let window = create_window();
+        INSPECTOR.with(|inspector| inspector.borrow_mut().window = Some(window.clone()));
+        window
+    });
+    window.show_all();
+    window.present();
+}
+
+/// Track the component created with `stream` if the inspector is enabled.
+/// `parent` is the id of the stream of the parent component.
+pub fn register<WIDGET>(stream: &EventStream<WIDGET::Msg>, root: &WIDGET::Root, parent: Option<usize>)
+    where WIDGET: Widget + 'static,
+          WIDGET::Msg: DisplayVariant + 'static,
+{
+    let (enabled, show) = INSPECTOR.with(|inspector| {
+        let mut inspector = inspector.borrow_mut();
+        let show = inspector.show_on_register;
+        inspector.show_on_register = false;
+        (inspector.enabled, show)
+    });
+    if !enabled {
+        return;
+    }
+
+    let history = Rc::new(RefCell::new(VecDeque::new()));
+    {
+        let history = history.clone();
+        stream.observe(move |msg: &WIDGET::Msg| {
+            let mut history = history.borrow_mut();
+            if history.len() == HISTORY_SIZE {
+                let _ = history.pop_front();
+            }
+            history.push_back(msg.display_variant());
+        });
+    }
+    let id = INSPECTOR.with(|inspector| {
+        let mut inspector = inspector.borrow_mut();
+        // NOTE: the id of a stream can be reused after it is dropped, so remove the dropped
+        // components before looking for the parent.
+        inspector.components.retain(|component| component.stream.is_alive());
+        let parent = parent.and_then(|parent| inspector.components.iter()
+            .find(|component| component.stream_id == parent)
+            .map(|component| component.id));
+        let id = inspector.next_id;
+        inspector.next_id += 1;
+        let root: gtk::Widget = root.clone().upcast();
+        inspector.components.push(ComponentInfo {
+            history,
+            id,
+            model_type: type_name::<WIDGET::Model>(),
+            parent,
+            root: root.downgrade(),
+            stream: Rc::new(TypedStream::<WIDGET> {
+                stream: stream.downgrade(),
+            }),
+            stream_id: stream.id(),
+            widget_type: type_name::<WIDGET>(),
+        });
+        id
+    });
+    let _ = root.connect_destroy(move |_| {
+        INSPECTOR.with(|inspector| {
+            inspector.borrow_mut().components.retain(|component| component.id != id);
+        });
+    });
+
+    if show {
+        // NOTE: wait for the component to be fully created before showing the window.
+        gtk::idle_add(|| {
+            show_inspector();
+            gtk::Continue(false)
+        });
+    }
+}
+
+fn create_window() -> gtk::Window {
+    let window = gtk::Window::new(gtk::WindowType::Toplevel);
+    window.set_title("Relm inspector");
+    window.set_default_size(800, 400);
+
+    let store = gtk::TreeStore::new(&[Type::U64, Type::String, Type::String, Type::String, Type::U32,
+        Type::String]);
+    let tree_view = gtk::TreeView::new_with_model(&store);
+    for &(title, column) in &[("Widget", WIDGET_COL), ("Model", MODEL_COL), ("Root", ROOT_COL),
+        ("Queue", QUEUE_COL), ("Messages", MESSAGES_COL)]
+    {
+        let view_column = gtk::TreeViewColumn::new();
+        let cell = gtk::CellRendererText::new();
+        view_column.set_title(title);
+        view_column.set_resizable(true);
+        view_column.pack_start(&cell, true);
+        view_column.add_attribute(&cell, "text", column as i32);
+        tree_view.append_column(&view_column);
+    }
+
+    let scrolled_window = gtk::ScrolledWindow::new(None, None);
+    scrolled_window.add(&tree_view);
+
+    let entry = gtk::Entry::new();
+    entry.set_placeholder_text("Message to send to the selected component");
+    let inject_button = gtk::Button::new_with_label("Inject");
+    let status = gtk::Label::new(None);
+
+    let hbox = gtk::Box::new(gtk::Orientation::Horizontal, 5);
+    hbox.pack_start(&entry, true, true, 0);
+    hbox.pack_start(&inject_button, false, false, 0);
+
+    let vbox = gtk::Box::new(gtk::Orientation::Vertical, 5);
+    vbox.pack_start(&scrolled_window, true, true, 0);
+    vbox.pack_start(&hbox, false, false, 0);
+    vbox.pack_start(&status, false, false, 0);
+    window.add(&vbox);
+
+    {
+        let entry = entry.clone();
+        let tree_view = tree_view.clone();
+        inject_button.connect_clicked(move |_| {
+            let text = entry.get_text().unwrap_or_default();
+            let result = selected_id(&tree_view)
+                .ok_or_else(|| "No component selected".to_string())
+                .and_then(|id| inject(id, &text));
+            match result {
+                Ok(()) => status.set_text(""),
+                Err(error) => status.set_text(&error),
+            }
+        });
+    }
+
+    // Hide the window instead of destroying it so that it can be shown again.
+    window.connect_delete_event(|window, _| {
+        window.hide();
+        Inhibit(true)
+    });
+
+    refresh(&tree_view, &store);
+    gtk::timeout_add(REFRESH_INTERVAL, move || {
+        if tree_view.get_mapped() {
+            refresh(&tree_view, &store);
+        }
+        gtk::Continue(true)
+    });
+
+    window
+}
+
+fn inject(id: usize, msg: &str) -> Result<(), String> {
+    // NOTE: clone the stream to avoid borrowing the inspector while the message is emitted.
+    let stream = INSPECTOR.with(|inspector| {
+        inspector.borrow().components.iter()
+            .find(|component| component.id == id)
+            .map(|component| component.stream.clone())
+    });
+    let stream = stream.ok_or_else(|| "The component was dropped".to_string())?;
+    stream.inject(msg)
+}
+
+fn refresh(tree_view: &gtk::TreeView, store: &gtk::TreeStore) {
+    let selected = selected_id(tree_view);
+    INSPECTOR.with(|inspector| {
+        let mut inspector = inspector.borrow_mut();
+        inspector.components.retain(|component| component.stream.is_alive());
+
+        let ids: Vec<_> = inspector.components.iter().map(|component| component.id).collect();
+        let mut children = HashMap::new();
+        for (index, component) in inspector.components.iter().enumerate() {
+            // Components whose parent is not tracked are shown at the top-level.
+            let parent = component.parent.filter(|parent| ids.contains(parent));
+            children.entry(parent).or_insert_with(Vec::new).push(index);
+        }
+
+        store.clear();
+        let mut iters = HashMap::new();
+        insert_rows(store, &inspector.components, &children, None, None, &mut iters);
+        tree_view.expand_all();
+        if let Some(iter) = selected.and_then(|id| iters.get(&id)) {
+            tree_view.get_selection().select_iter(iter);
+        }
+    });
+}
+
+fn insert_rows(store: &gtk::TreeStore, components: &[ComponentInfo], children: &HashMap<Option<usize>, Vec<usize>>,
+    parent: Option<usize>, parent_iter: Option<&gtk::TreeIter>, iters: &mut HashMap<usize, gtk::TreeIter>)
+{
+    for &index in children.get(&parent).map(|indices| indices.as_slice()).unwrap_or(&[]) {
+        let component = &components[index];
+        let messages: Vec<_> = component.history.borrow().iter().cloned().collect();
+        let iter = store.insert_with_values(parent_iter, None,
+            &[ID_COL, WIDGET_COL, MODEL_COL, ROOT_COL, QUEUE_COL, MESSAGES_COL],
+            &[&(component.id as u64), &component.widget_type, &component.model_type, &root_name(&component.root),
+              &(component.stream.queue_len() as u32), &messages.join(", ")]);
+        insert_rows(store, components, children, Some(component.id), Some(&iter), iters);
+        let _ = iters.insert(component.id, iter);
+    }
+}
+
+fn root_name(root: &WeakRef<gtk::Widget>) -> String {
+    let root = match root.upgrade() {
+        Some(root) => root,
+        None => return "(destroyed)".to_string(),
+    };
+    let type_name = root.get_type().name();
+    match root.get_name() {
+        Some(ref name) if *name != type_name => format!("{}#{}", type_name, name),
+        _ => type_name,
+    }
+}
+
+fn selected_id(tree_view: &gtk::TreeView) -> Option<usize> {
+    tree_view.get_selection().get_selected()
+        .and_then(|(model, iter)| model.get_value(&iter, ID_COL as i32).get::<u64>())
+        .map(|id| id as usize)
+}
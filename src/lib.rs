@@ -71,7 +71,6 @@
  * GSource).
  * For timers, add a connect_timeout!() macro or something.
  *
- * TODO: show a warning when a component is imediately destroyed.
  * FIXME: cannot add a trailing coma at the end of a initializer list.
  * TODO: switch from gtk::main() to MainLoop to avoid issues with nested loops.
  * TODO: prefix generated container name with _ to hide warnings.
@@ -79,14 +78,9 @@
  * TODO: remove the closure transformer code.
  *
  * TODO: move most of the examples in the tests/ directory.
- * TODO: add construct-only properties for relm widget (to replace initial parameters) to allow
- * setting them by name (or with default value).
- * TODO: find a way to do two-step initialization (to avoid using unitialized in model()).
- * TODO: consider using GBinding instead of manually adding calls to set_property().
  *
  * TODO: add a FAQ with the question related to getting an error when not importing the gtk traits.
  *
- * TODO: show a warning when components are destroyed after the end of call to Widget::view().
  * TODO: warn in the attribute when an event cycle is found.
  * TODO: add a Deref<Widget> for Component?
  * TODO: look at how Elm works with the <canvas> element.
@@ -102,29 +96,54 @@
  *
  * TODO: add default type of () for Model in Widget when it is stable.
  * TODO: optionnaly multi-threaded.
- * TODO: convert GTK+ callback to Stream (does not seem worth it, nor convenient since it will
- * still need to use USFC for the callback method).
  *
  * These probably won't be needed anymore when switching to futures-glib (single-threaded model).
- * TODO: use weak pointers to avoid leaking.
  * TODO: should have a free function to delete the stream in connect_recv.
  * TODO: try tk-easyloop in another branch.
  */
 
+extern crate atk_sys;
+extern crate futures;
 extern crate futures_glib;
+extern crate gdk;
+extern crate gdk_pixbuf;
+extern crate gio;
 extern crate glib;
 extern crate glib_sys;
 extern crate gobject_sys;
 extern crate gtk;
 extern crate gtk_sys;
 extern crate libc;
+#[macro_use]
+extern crate log;
 extern crate relm_core;
 extern crate relm_state;
 
+mod accessible;
+mod action;
+mod application;
+mod binding;
+mod clipboard;
 mod component;
+mod component_list;
 mod container;
+mod dialog;
+mod drag;
+mod form;
+mod i18n;
+mod inspector;
 mod macros;
+mod process;
+mod router;
+mod settings;
+mod shortcuts;
+mod signal_stream;
+mod style;
+mod tree_model;
 mod widget;
+mod window_manager;
+
+use std::rc::Rc;
 
 use futures_glib::Executor;
 #[doc(hidden)]
@@ -135,13 +154,15 @@ pub use glib::Cast;
 pub use glib::object::Downcast;
 #[doc(hidden)]
 pub use glib::translate::{FromGlibPtrNone, ToGlib, ToGlibPtr};
+#[doc(hidden)]
+pub use glib::{StaticType, ToValue, Type, Value};
 use glib_sys::GType;
 #[doc(hidden)]
 pub use gobject_sys::{GParameter, g_object_newv};
 use gobject_sys::{GObject, GValue};
 use libc::{c_char, c_uint};
 #[doc(hidden)]
-pub use relm_core::EventStream;
+pub use relm_core::{EventStream, WeakEventStream};
 pub use relm_state::{
     DisplayVariant,
     IntoOption,
@@ -152,11 +173,63 @@ pub use relm_state::{
     create_executor,
     execute,
 };
-use relm_state::init_component;
 
-pub use component::Component;
-pub use container::{Container, ContainerComponent, ContainerWidget};
+use component::ParentStream;
+
+pub use accessible::{
+    AccessibleRelation,
+    add_accessible_relation,
+    set_accessible_description,
+    set_accessible_name,
+    set_accessible_role,
+};
+pub use action::{ActionStateExt, Actions};
+pub use application::{AppEvent, AppWidget, run_app};
+pub use binding::{Binding, BindingBuilder, BindingSource, bind_property};
+pub use clipboard::{
+    ClipboardContent,
+    clipboard,
+    connect_clipboard_owner_change,
+    request_clipboard_image,
+    request_clipboard_text,
+    request_clipboard_uris,
+    set_clipboard,
+};
+pub use component::{Component, WeakComponent, set_strict_mode};
+pub use component_list::ComponentList;
+pub use container::{Container, ContainerComponent, ContainerWidget, remove_child, reorder_child};
+pub use dialog::{
+    DialogFuture,
+    DialogWidget,
+    FileChooser,
+    FileChooserMsg,
+    FileChooserParams,
+    MessageBox,
+    MessageBoxMsg,
+    MessageBoxParams,
+    dialog,
+};
+pub use drag::{DragData, DragExt, DragTarget, connect_reorder};
+pub use form::{ERROR_CLASS, Field, Form, FormField, FormMsg, Rule};
+pub use i18n::{
+    connect_locale_changed,
+    gettext,
+    ngettext,
+    npgettext,
+    pgettext,
+    set_locale,
+    set_text_domain,
+};
+pub use inspector::{enable_inspector, set_inspector_msg_parser, show_inspector};
+pub use process::{ExitFuture, Output, OutputMode, OutputStream, Process};
+pub use router::{Page, Pages, Router};
+pub use settings::{SettingBinding, Settings};
+pub use shortcuts::Shortcuts;
+pub use signal_stream::{SignalGuard, SignalSender, SignalStream};
+pub use style::{StyleSheet, toggle_style_class};
+pub use tree_model::{RowList, RowTree, TreeNode, TreeRow};
 pub use widget::Widget;
+pub use window_manager::{WindowId, WindowManager};
 
 extern "C" {
     pub fn g_object_new_with_properties(object_type: GType, n_properties: c_uint, names: *mut *const c_char,
@@ -192,6 +265,34 @@ macro_rules! relm_widget {
     };
 }
 
+/// Translate a message with gettext.
+///
+/// The message can have a context and a plural form chosen according to a count:
+///
+/// ```ignore
+/// tr!("Open");
+/// tr!(context = "menu", "Open");
+/// tr!("One file", "Several files", count);
+/// tr!(context = "status", "One file", "Several files", count);
+/// ```
+///
+/// The messages must be string literals so that they can be extracted with `relm-xgettext`.
+#[macro_export]
+macro_rules! tr {
+    (context = $context:expr, $msgid:expr, $msgid_plural:expr, $count:expr) => {
+        $crate::npgettext($context, $msgid, $msgid_plural, ($count) as u64)
+    };
+    (context = $context:expr, $msgid:expr) => {
+        $crate::pgettext($context, $msgid)
+    };
+    ($msgid:expr, $msgid_plural:expr, $count:expr) => {
+        $crate::ngettext($msgid, $msgid_plural, ($count) as u64)
+    };
+    ($msgid:expr) => {
+        $crate::gettext($msgid)
+    };
+}
+
 #[doc(hidden)]
 #[macro_export]
 macro_rules! use_impl_self_type {
@@ -200,37 +301,53 @@ macro_rules! use_impl_self_type {
     };
 }
 
+// Initialize the component and set its translated properties depending on the model again when
+// the locale changes.
+fn init_component<WIDGET>(stream: &EventStream<WIDGET::Msg>, component: WIDGET, executor: &Executor,
+    relm: &Relm<WIDGET>)
+    where WIDGET: Widget + 'static,
+          WIDGET::Msg: DisplayVariant + 'static,
+{
+    let component = relm_state::init_shared_component(stream, component, executor, relm);
+    i18n::connect_component_locale_changed(stream.id(), component);
+}
+
+#[track_caller]
 fn create_widget_test<WIDGET>(executor: &Executor, model_param: WIDGET::ModelParam) -> Component<WIDGET>
     where WIDGET: Widget + 'static,
           WIDGET::Msg: DisplayVariant + 'static,
 {
-    let (widget, component, relm) = create_widget(executor, model_param);
+    let (widget, component, relm) = create_widget(executor, model_param, None);
     init_component::<WIDGET>(widget.stream(), component, executor, &relm);
     widget
 }
 
 /// Create a new relm widget without adding it to an existing widget.
 /// This is useful when a relm widget is at the root of another relm widget.
+#[track_caller]
 pub fn create_component<CHILDWIDGET, WIDGET>(relm: &Relm<WIDGET>, model_param: CHILDWIDGET::ModelParam)
         -> Component<CHILDWIDGET>
     where CHILDWIDGET: Widget + 'static,
           CHILDWIDGET::Msg: DisplayVariant + 'static,
           WIDGET: Widget,
 {
-    let (widget, component, child_relm) = create_widget::<CHILDWIDGET>(relm.executor(), model_param);
+    let (widget, component, child_relm) = create_widget::<CHILDWIDGET>(relm.executor(), model_param,
+            Some(Rc::new(relm.stream().downgrade())));
     init_component::<CHILDWIDGET>(widget.stream(), component, relm.executor(), &child_relm);
     widget
 }
 
 /// Create a new relm container widget without adding it to an existing widget.
 /// This is useful when a relm widget is at the root of another relm widget.
+#[track_caller]
 pub fn create_container<CHILDWIDGET, WIDGET>(relm: &Relm<WIDGET>, model_param: CHILDWIDGET::ModelParam)
         -> ContainerComponent<CHILDWIDGET>
     where CHILDWIDGET: Container + Widget + 'static,
           CHILDWIDGET::Msg: DisplayVariant + 'static,
           WIDGET: Widget,
 {
-    let (widget, component, child_relm) = create_widget::<CHILDWIDGET>(relm.executor(), model_param);
+    let (widget, component, child_relm) = create_widget::<CHILDWIDGET>(relm.executor(), model_param,
+            Some(Rc::new(relm.stream().downgrade())));
     let container = component.container().clone();
     let containers = component.other_containers();
     init_component::<CHILDWIDGET>(widget.stream(), component, relm.executor(), &child_relm);
@@ -238,7 +355,9 @@ pub fn create_container<CHILDWIDGET, WIDGET>(relm: &Relm<WIDGET>, model_param: C
 }
 
 /// Create a new relm widget with `model_param` as initialization value.
-fn create_widget<WIDGET>(executor: &Executor, model_param: WIDGET::ModelParam)
+/// `parent` is the stream of the parent component, if any.
+#[track_caller]
+fn create_widget<WIDGET>(executor: &Executor, model_param: WIDGET::ModelParam, parent: Option<Rc<ParentStream>>)
     -> (Component<WIDGET>, WIDGET, Relm<WIDGET>)
     where WIDGET: Widget + 'static,
           WIDGET::Msg: DisplayVariant + 'static,
@@ -251,7 +370,8 @@ fn create_widget<WIDGET>(executor: &Executor, model_param: WIDGET::ModelParam)
     widget.init_view();
 
     let root = widget.root().clone();
-    (Component::new(stream, root), widget, relm)
+    inspector::register::<WIDGET>(&stream, &root, parent.as_ref().map(|parent| parent.id()));
+    (Component::new(stream, root, parent), widget, relm)
 }
 
 // TODO: remove this workaround.
@@ -317,6 +437,7 @@ fn init_gtk() {
 /// let widgets = component.widget();
 /// # }
 /// ```
+#[track_caller]
 pub fn init_test<WIDGET>(model_param: WIDGET::ModelParam) -> Result<Component<WIDGET>, ()>
     where WIDGET: Widget + 'static,
           WIDGET::Msg: DisplayVariant + 'static
@@ -330,6 +451,7 @@ pub fn init_test<WIDGET>(model_param: WIDGET::ModelParam) -> Result<Component<WI
 }
 
 /// Initialize a widget.
+#[track_caller]
 pub fn init<WIDGET>(model_param: WIDGET::ModelParam) -> Result<Component<WIDGET>, ()>
     where WIDGET: Widget + 'static,
           WIDGET::Msg: DisplayVariant + 'static
@@ -338,7 +460,7 @@ pub fn init<WIDGET>(model_param: WIDGET::ModelParam) -> Result<Component<WIDGET>
     gtk::init().map_err(|_| ())?;
 
     let executor = create_executor();
-    let (widget, component, relm) = create_widget::<WIDGET>(&executor, model_param);
+    let (widget, component, relm) = create_widget::<WIDGET>(&executor, model_param, None);
     init_component::<WIDGET>(widget.stream(), component, &executor, &relm);
     Ok(widget)
 }
@@ -400,7 +522,5 @@ pub fn init<WIDGET>(model_param: WIDGET::ModelParam) -> Result<Component<WIDGET>
 pub fn run<WIDGET>(model_param: WIDGET::ModelParam) -> Result<(), ()>
     where WIDGET: Widget + 'static,
 {
-    let _component = init::<WIDGET>(model_param)?;
-    gtk::main();
-    Ok(())
+    application::run_with_application::<WIDGET>(model_param)
 }
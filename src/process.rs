@@ -0,0 +1,538 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::io::{self, Read, Write};
+#[cfg(unix)]
+use std::os::unix::io::AsRawFd;
+#[cfg(unix)]
+use std::os::unix::process::ExitStatusExt;
+#[cfg(windows)]
+use std::os::windows::io::AsRawHandle;
+#[cfg(windows)]
+use std::os::windows::process::ExitStatusExt;
+use std::process::{Child, ChildStderr, ChildStdin, ChildStdout, Command, ExitStatus, Stdio};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use futures::{Async, Future, Poll, Stream};
+use futures::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded};
+use futures::sync::oneshot;
+use glib::{self, Pid};
+use glib::translate::ToGlib;
+use glib_sys::{
+    GIOChannel,
+    GIOCondition,
+    G_IO_ERR,
+    G_IO_HUP,
+    G_IO_IN,
+    G_IO_OUT,
+    G_PRIORITY_DEFAULT,
+    gboolean,
+    gpointer,
+};
+
+use self::Output::*;
+
+const CHUNK_SIZE: usize = 4096;
+
+/// How the output of the process is split before being sent.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OutputMode {
+    /// Send whatever data is available, in chunks of at most 4096 bytes.
+    Chunks,
+    /// Send one line at a time, without the trailing newline.
+    Lines,
+}
+
+/// A line or a chunk of data written by the process.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Output {
+    /// Data written on the standard error.
+    Stderr(Vec<u8>),
+    /// Data written on the standard output.
+    Stdout(Vec<u8>),
+}
+
+impl Output {
+    /// Get the data written by the process.
+    pub fn bytes(&self) -> &[u8] {
+        match *self {
+            Stderr(ref bytes) | Stdout(ref bytes) => bytes,
+        }
+    }
+
+    /// Get the data as a string, replacing the invalid UTF-8 sequences.
+    pub fn to_string_lossy(&self) -> Cow<str> {
+        String::from_utf8_lossy(self.bytes())
+    }
+}
+
+/// Stream of the data written by a process on its standard output and error.
+///
+/// The stream ends when both outputs are closed.
+pub struct OutputStream {
+    receiver: UnboundedReceiver<Output>,
+}
+
+impl Stream for OutputStream {
+    type Item = Output;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Option<Output>, ()> {
+        self.receiver.poll()
+    }
+}
+
+/// Future resolving to the exit status of a process.
+pub struct ExitFuture {
+    receiver: oneshot::Receiver<io::Result<ExitStatus>>,
+}
+
+impl Future for ExitFuture {
+    type Item = ExitStatus;
+    type Error = io::Error;
+
+    fn poll(&mut self) -> Poll<ExitStatus, io::Error> {
+        match self.receiver.poll() {
+            Ok(Async::Ready(result)) => result.map(Async::Ready),
+            Ok(Async::NotReady) => Ok(Async::NotReady),
+            Err(_) => Err(io::Error::new(io::ErrorKind::Other, "the process watcher stopped")),
+        }
+    }
+}
+
+/// A child process whose output and exit status can be connected to messages with
+/// [`Relm::connect_exec()`](struct.Relm.html#method.connect_exec).
+///
+/// The process is watched from the GLib main loop: its exit is reported by a child watch and its
+/// input and output are read and written when the pipes are ready, so no thread is used.
+///
+/// The process is killed when the `Process` is dropped, so it is usually stored in the model of
+/// the component that spawned it:
+///
+/// ```ignore
+/// let mut process = Process::spawn(Command::new("ls").arg("-l"), OutputMode::Lines)?;
+/// relm.connect_exec_ignore_err(process.output(), Output);
+/// relm.connect_exec(process.wait(), Exited, WaitError);
+/// ```
+pub struct Process {
+    child: RefCell<Child>,
+    exit: Option<oneshot::Receiver<io::Result<ExitStatus>>>,
+    exited: Arc<AtomicBool>,
+    id: u32,
+    input: Rc<RefCell<Input>>,
+    output: Option<UnboundedReceiver<Output>>,
+}
+
+impl Process {
+    /// Spawn the `command` with its standard input, output and error piped.
+    pub fn spawn(command: &mut Command, mode: OutputMode) -> io::Result<Self> {
+        let mut child = command.stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let id = child.id();
+
+        let (tx, rx) = unbounded();
+        if let Some(stdout) = child.stdout.take() {
+            watch_output(stdout, mode, tx.clone(), Stdout);
+        }
+        if let Some(stderr) = child.stderr.take() {
+            watch_output(stderr, mode, tx, Stderr);
+        }
+        if let Some(ref stdin) = child.stdin {
+            set_non_blocking(stdin)?;
+        }
+        let input = Rc::new(RefCell::new(Input {
+            data: vec![],
+            source: None,
+            stdin: child.stdin.take(),
+        }));
+
+        let exited = Arc::new(AtomicBool::new(false));
+        let exit = watch_child(&child, exited.clone());
+
+        Ok(Process {
+            child: RefCell::new(child),
+            exit: Some(exit),
+            exited,
+            id,
+            input,
+            output: Some(rx),
+        })
+    }
+
+    /// Close the standard input of the process, dropping the data not written yet.
+    /// This is needed by the programs reading their input until the end of file.
+    pub fn close_stdin(&mut self) {
+        self.input.borrow_mut().close();
+    }
+
+    /// Get the OS-assigned process identifier.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Kill the process.
+    pub fn kill(&self) -> io::Result<()> {
+        // NOTE: the child watch reaps the process, so it must not be killed after it exited
+        // since its identifier could be reused by another process.
+        if !self.exited.load(Ordering::SeqCst) {
+            self.child.borrow_mut().kill()?;
+        }
+        Ok(())
+    }
+
+    /// Get the stream of the data written by the process.
+    ///
+    /// ## Panics
+    /// Panics if the output stream was already taken.
+    pub fn output(&mut self) -> OutputStream {
+        OutputStream {
+            receiver: self.output.take().expect("the output stream of the process was already taken"),
+        }
+    }
+
+    /// Get a future resolving to the exit status of the process.
+    ///
+    /// ## Panics
+    /// Panics if the exit future was already taken.
+    pub fn wait(&mut self) -> ExitFuture {
+        ExitFuture {
+            receiver: self.exit.take().expect("the exit future of the process was already taken"),
+        }
+    }
+
+    /// Write `data` to the standard input of the process.
+    /// The data is written when the process is ready to read it, so this never blocks.
+    pub fn write(&self, data: &[u8]) {
+        let mut input = self.input.borrow_mut();
+        if input.stdin.is_none() {
+            return;
+        }
+        input.data.extend_from_slice(data);
+        if input.source.is_none() {
+            let source = {
+                let stdin = input.stdin.as_ref().expect("stdin");
+                let input = self.input.clone();
+                add_watch(stdin, G_IO_OUT | G_IO_ERR | G_IO_HUP, move |condition| {
+                    input.borrow_mut().write_pending(condition)
+                })
+            };
+            input.source = Some(source);
+        }
+    }
+}
+
+impl Drop for Process {
+    fn drop(&mut self) {
+        self.input.borrow_mut().close();
+        let _ = self.kill();
+    }
+}
+
+// Standard input of the process with the data waiting for the process to read it.
+struct Input {
+    data: Vec<u8>,
+    source: Option<glib::SourceId>,
+    stdin: Option<ChildStdin>,
+}
+
+impl Input {
+    fn close(&mut self) {
+        if let Some(source) = self.source.take() {
+            glib::source_remove(source);
+        }
+        self.data.clear();
+        self.stdin = None;
+    }
+
+    // Write as much data as possible and return whether the watch should continue.
+    fn write_pending(&mut self, condition: GIOCondition) -> bool {
+        if condition & G_IO_OUT == 0 {
+            // NOTE: an error means that the process closed its input, so there's nothing to do.
+            let _ = self.source.take();
+            self.data.clear();
+            self.stdin = None;
+            return false;
+        }
+        let result =
+            match self.stdin {
+                Some(ref mut stdin) => stdin.write(&self.data),
+                None => Ok(0),
+            };
+        match result {
+            Ok(size) => {
+                let _ = self.data.drain(..size);
+            },
+            Err(ref error) if error.kind() == io::ErrorKind::WouldBlock => (),
+            Err(_) => {
+                self.data.clear();
+                self.stdin = None;
+            },
+        }
+        if self.data.is_empty() {
+            let _ = self.source.take();
+            false
+        }
+        else {
+            true
+        }
+    }
+}
+
+type WatchCallback = Box<FnMut(GIOCondition) -> bool>;
+
+unsafe extern "C" fn watch_trampoline(_channel: *mut GIOChannel, condition: GIOCondition, data: gpointer)
+    -> gboolean
+{
+    let callback = &mut *(data as *mut WatchCallback);
+    callback(condition).to_glib()
+}
+
+unsafe extern "C" fn watch_destroy(data: gpointer) {
+    let _ = Box::from_raw(data as *mut WatchCallback);
+}
+
+// Call `callback` from the main loop every time `condition` is met on `file`, until it returns false.
+fn add_watch<FILE, CALLBACK>(file: &FILE, condition: GIOCondition, callback: CALLBACK) -> glib::SourceId
+    where FILE: AsFd,
+          CALLBACK: FnMut(GIOCondition) -> bool + 'static,
+{
+    let callback: Box<WatchCallback> = Box::new(Box::new(callback));
+    unsafe {
+        let channel = file.io_channel();
+        let id = glib_sys::g_io_add_watch_full(channel, G_PRIORITY_DEFAULT, condition, Some(watch_trampoline),
+            Box::into_raw(callback) as gpointer, Some(watch_destroy));
+        // NOTE: the watch keeps a reference to the channel.
+        glib_sys::g_io_channel_unref(channel);
+        glib::translate::from_glib(id)
+    }
+}
+
+// File descriptor of a pipe of the child process that can be watched by GLib.
+trait AsFd {
+    // Create a new reference to a channel for this file.
+    unsafe fn io_channel(&self) -> *mut GIOChannel;
+}
+
+// Channel created before the file is moved in the watch callback.
+struct Channel(*mut GIOChannel);
+
+impl AsFd for Channel {
+    unsafe fn io_channel(&self) -> *mut GIOChannel {
+        self.0
+    }
+}
+
+macro_rules! impl_as_fd {
+    ($($file:ty),*) => {
+        $(
+            #[cfg(unix)]
+            impl AsFd for $file {
+                unsafe fn io_channel(&self) -> *mut GIOChannel {
+                    glib_sys::g_io_channel_unix_new(self.as_raw_fd())
+                }
+            }
+
+            #[cfg(windows)]
+            impl AsFd for $file {
+                unsafe fn io_channel(&self) -> *mut GIOChannel {
+                    let fd = ::libc::open_osfhandle(self.as_raw_handle() as ::libc::intptr_t, 0);
+                    glib_sys::g_io_channel_win32_new_fd(fd)
+                }
+            }
+        )*
+    };
+}
+
+impl_as_fd!(ChildStderr, ChildStdin, ChildStdout);
+
+#[cfg(unix)]
+fn set_non_blocking<FILE: AsRawFd>(file: &FILE) -> io::Result<()> {
+    let fd = file.as_raw_fd();
+    unsafe {
+        let flags = ::libc::fcntl(fd, ::libc::F_GETFL);
+        if flags == -1 || ::libc::fcntl(fd, ::libc::F_SETFL, flags | ::libc::O_NONBLOCK) == -1 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+// NOTE: pipes cannot be made non-blocking on Windows, so a write can block when the process does
+// not read its input.
+#[cfg(windows)]
+fn set_non_blocking<FILE>(_file: &FILE) -> io::Result<()> {
+    Ok(())
+}
+
+// Split the data read by the process according to `mode`.
+// When `end` is true, the remaining data is sent even if it does not end with a newline.
+fn split_output(buffer: &mut Vec<u8>, mode: OutputMode, end: bool) -> Vec<Vec<u8>> {
+    match mode {
+        OutputMode::Chunks => {
+            if buffer.is_empty() {
+                vec![]
+            }
+            else {
+                vec![buffer.drain(..).collect()]
+            }
+        },
+        OutputMode::Lines => {
+            let mut lines = vec![];
+            while let Some(position) = buffer.iter().position(|&byte| byte == b'\n') {
+                let mut line: Vec<_> = buffer.drain(..position + 1).collect();
+                let _ = line.pop();
+                if line.last() == Some(&b'\r') {
+                    let _ = line.pop();
+                }
+                lines.push(line);
+            }
+            if end && !buffer.is_empty() {
+                lines.push(buffer.drain(..).collect());
+            }
+            lines
+        },
+    }
+}
+
+// Read from `input`, retrying when the read is interrupted by a signal.
+fn read_uninterrupted<READ: Read>(input: &mut READ, buffer: &mut [u8]) -> io::Result<usize> {
+    loop {
+        match input.read(buffer) {
+            Err(ref error) if error.kind() == io::ErrorKind::Interrupted => (),
+            result => return result,
+        }
+    }
+}
+
+fn watch_child(child: &Child, exited: Arc<AtomicBool>) -> oneshot::Receiver<io::Result<ExitStatus>> {
+    let (tx, rx) = oneshot::channel();
+    let mut tx = Some(tx);
+    #[cfg(unix)]
+    let pid = Pid(child.id() as ::libc::c_int);
+    #[cfg(windows)]
+    let pid = Pid(child.as_raw_handle() as _);
+    let _ = glib::child_watch_add(pid, move |_, status| {
+        exited.store(true, Ordering::SeqCst);
+        if let Some(tx) = tx.take() {
+            let _ = tx.send(Ok(ExitStatus::from_raw(status as _)));
+        }
+    });
+    rx
+}
+
+fn watch_output<READ, VARIANT>(mut output: READ, mode: OutputMode, tx: UnboundedSender<Output>, variant: VARIANT)
+    where READ: AsFd + Read + 'static,
+          VARIANT: Fn(Vec<u8>) -> Output + 'static,
+{
+    let mut buffer = vec![];
+    let mut chunk = vec![0; CHUNK_SIZE];
+    let channel = Channel(unsafe { output.io_channel() });
+    let _ = add_watch(&channel, G_IO_IN | G_IO_ERR | G_IO_HUP, move |condition| {
+        let end =
+            if condition & G_IO_IN != 0 {
+                // NOTE: a single read does not block since the pipe is ready.
+                match read_uninterrupted(&mut output, &mut chunk) {
+                    Ok(0) => true,
+                    Ok(size) => {
+                        buffer.extend_from_slice(&chunk[..size]);
+                        false
+                    },
+                    Err(ref error) if error.kind() == io::ErrorKind::WouldBlock => false,
+                    Err(error) => {
+                        warn!("Cannot read the output of the process: {}", error);
+                        true
+                    },
+                }
+            }
+            else {
+                // The pipe was closed and all its data was read.
+                true
+            };
+        for data in split_output(&mut buffer, mode, end) {
+            if tx.unbounded_send(variant(data)).is_err() {
+                // The stream was dropped.
+                return false;
+            }
+        }
+        !end
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Read};
+
+    use super::{OutputMode, read_uninterrupted, split_output};
+
+    // Reader failing with the errors before reading its data.
+    struct FailingReader {
+        data: &'static [u8],
+        errors: Vec<io::ErrorKind>,
+    }
+
+    impl Read for FailingReader {
+        fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+            if !self.errors.is_empty() {
+                return Err(io::Error::new(self.errors.remove(0), "read error"));
+            }
+            self.data.read(buffer)
+        }
+    }
+
+    #[test]
+    fn read_interrupted() {
+        let mut buffer = [0; 8];
+        let mut reader = FailingReader {
+            data: b"data",
+            errors: vec![io::ErrorKind::Interrupted, io::ErrorKind::Interrupted, io::ErrorKind::WouldBlock],
+        };
+        let error = read_uninterrupted(&mut reader, &mut buffer).expect_err("would block");
+        assert_eq!(error.kind(), io::ErrorKind::WouldBlock);
+        assert_eq!(read_uninterrupted(&mut reader, &mut buffer).expect("read"), 4);
+        assert_eq!(&buffer[..4], b"data");
+        assert_eq!(read_uninterrupted(&mut reader, &mut buffer).expect("end"), 0);
+    }
+
+    #[test]
+    fn split_chunks() {
+        let mut buffer = b"a\nb".to_vec();
+        assert_eq!(split_output(&mut buffer, OutputMode::Chunks, false), vec![b"a\nb".to_vec()]);
+        assert!(buffer.is_empty());
+        assert!(split_output(&mut buffer, OutputMode::Chunks, true).is_empty());
+    }
+
+    #[test]
+    fn split_lines() {
+        let mut buffer = b"first\r\nsecond\nthi".to_vec();
+        assert_eq!(split_output(&mut buffer, OutputMode::Lines, false), vec![b"first".to_vec(), b"second".to_vec()]);
+        assert_eq!(buffer, b"thi");
+        buffer.extend_from_slice(b"rd\n\nlast");
+        assert_eq!(split_output(&mut buffer, OutputMode::Lines, false), vec![b"third".to_vec(), vec![]]);
+        assert_eq!(split_output(&mut buffer, OutputMode::Lines, true), vec![b"last".to_vec()]);
+        assert!(buffer.is_empty());
+    }
+}
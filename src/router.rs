@@ -0,0 +1,344 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::any::Any;
+use std::mem;
+
+use glib::Cast;
+use gtk::{self, ContainerExt, IsA, Object, StackExt, WidgetExt};
+
+use super::{Component, ContainerWidget, DisplayVariant, Relm, Widget};
+use self::Keep::*;
+
+// How long the page of a route is kept once another route is shown.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Keep {
+    // Until the router is dropped.
+    Always,
+    // While the route is in the back stack.
+    InBackStack,
+    // Not kept.
+    Never,
+}
+
+/// Page of a [`Router`](struct.Router.html), created by [`Pages::create()`](struct.Pages.html#method.create).
+///
+/// By default, a page is kept while its route is shown or in the back stack, so that going back
+/// shows the page as it was left, and it is destroyed when its route is removed from the back
+/// stack.
+pub struct Page {
+    component: Box<Any>,
+    keep: Keep,
+    widget: gtk::Widget,
+}
+
+impl Page {
+    /// Get the component of the page.
+    /// Return `None` if the page is not a `WIDGET`.
+    pub fn component<WIDGET: Widget + 'static>(&self) -> Option<&Component<WIDGET>> {
+        self.component.downcast_ref()
+    }
+
+    /// Destroy the component of the page when another page is shown, even if its route is in
+    /// the back stack.
+    /// The page will be created again the next time its route is shown.
+    pub fn destroy_when_hidden(mut self) -> Self {
+        self.keep = Never;
+        self
+    }
+
+    /// Keep the component of the page until the router is dropped, even when its route is not in
+    /// the back stack.
+    /// Since every route showing such a page keeps it, this should not be used for the routes
+    /// holding parameters.
+    pub fn keep_when_hidden(mut self) -> Self {
+        self.keep = Always;
+        self
+    }
+}
+
+/// Factory given to the closure of [`Router::new()`](struct.Router.html#method.new) to create the
+/// pages.
+pub struct Pages<'a, PARENTWIDGET: Widget + 'a> {
+    relm: &'a Relm<PARENTWIDGET>,
+    stack: &'a gtk::Stack,
+}
+
+impl<'a, PARENTWIDGET: Widget> Pages<'a, PARENTWIDGET> {
+    /// Create the relm widget of a page, with the parameters of the route as its model parameter.
+    pub fn create<WIDGET>(&self, model_param: WIDGET::ModelParam) -> Page
+        where WIDGET: Widget + 'static,
+              WIDGET::Msg: DisplayVariant + 'static,
+              WIDGET::Root: IsA<gtk::Widget> + IsA<Object>,
+    {
+        let component = self.stack.add_widget::<WIDGET, _>(self.relm, model_param);
+        let widget = component.widget().clone().upcast();
+        Page {
+            component: Box::new(component),
+            keep: InBackStack,
+            widget,
+        }
+    }
+
+    /// Get the relm of the widget containing the router, to connect the messages of the pages.
+    pub fn relm(&self) -> &Relm<PARENTWIDGET> {
+        self.relm
+    }
+}
+
+/// Navigation between the pages of a `gtk::Stack`.
+///
+/// Every route is associated with a page created by the closure given to
+/// [`Router::new()`](struct.Router.html#method.new): a route is usually an `enum` whose variants
+/// hold the model parameter of the page.
+/// The pages are created when their route is shown for the first time.
+/// The routes that were shown before the current one are kept in a back stack so that
+/// [`pop()`](struct.Router.html#method.pop) goes back to the previous page.
+///
+/// A page is destroyed when its route is neither shown nor in the back stack, so the pages of
+/// the routes holding parameters do not accumulate: see [`Page`](struct.Page.html) to keep or
+/// destroy a page sooner.
+pub struct Router<ROUTE, PARENTWIDGET: Widget> {
+    create_page: Box<Fn(&ROUTE, &Pages<PARENTWIDGET>) -> Page>,
+    history: History<ROUTE>,
+    pages: Vec<(ROUTE, Page)>,
+    relm: Relm<PARENTWIDGET>,
+    stack: gtk::Stack,
+}
+
+impl<ROUTE, PARENTWIDGET> Router<ROUTE, PARENTWIDGET>
+    where ROUTE: Clone + PartialEq,
+          PARENTWIDGET: Widget,
+{
+    /// Create a router showing its pages in `stack`.
+    /// The `create_page` closure creates the page of a route.
+    pub fn new<CREATEPAGE>(stack: &gtk::Stack, relm: &Relm<PARENTWIDGET>, create_page: CREATEPAGE) -> Self
+        where CREATEPAGE: Fn(&ROUTE, &Pages<PARENTWIDGET>) -> Page + 'static,
+    {
+        Router {
+            create_page: Box::new(create_page),
+            history: History::new(),
+            pages: vec![],
+            relm: relm.clone(),
+            stack: stack.clone(),
+        }
+    }
+
+    /// Get the routes in the back stack, the last one being shown by [`pop()`](#method.pop).
+    pub fn back_stack(&self) -> &[ROUTE] {
+        &self.history.back_stack
+    }
+
+    /// Check whether there is a route to go back to.
+    pub fn can_go_back(&self) -> bool {
+        !self.history.back_stack.is_empty()
+    }
+
+    /// Get the component of the page of `route`.
+    /// Return `None` if the page was not created, was destroyed or is not a `WIDGET`.
+    pub fn component<WIDGET: Widget + 'static>(&self, route: &ROUTE) -> Option<&Component<WIDGET>> {
+        self.page(route).and_then(|page| page.component())
+    }
+
+    /// Get the route currently shown.
+    pub fn current(&self) -> Option<&ROUTE> {
+        self.history.current.as_ref()
+    }
+
+    /// Get the page of `route`, if it was created.
+    pub fn page(&self, route: &ROUTE) -> Option<&Page> {
+        self.pages.iter()
+            .find(|&&(ref page_route, _)| page_route == route)
+            .map(|&(_, ref page)| page)
+    }
+
+    /// Go back to the previous route.
+    /// Return the route that was shown, or `None` if the back stack is empty.
+    pub fn pop(&mut self) -> Option<ROUTE> {
+        let previous = self.history.pop()?;
+        self.show();
+        Some(previous)
+    }
+
+    /// Show `route`, pushing the current route in the back stack.
+    pub fn push(&mut self, route: ROUTE) {
+        if self.history.push(route) {
+            self.show();
+        }
+    }
+
+    /// Show `route` instead of the current route, without changing the back stack.
+    pub fn replace(&mut self, route: ROUTE) {
+        self.history.replace(route);
+        self.show();
+    }
+
+    /// Show `route` and clear the back stack.
+    pub fn reset(&mut self, route: ROUTE) {
+        self.history.reset(route);
+        self.show();
+    }
+
+    fn show(&mut self) {
+        let route =
+            match self.history.current {
+                Some(ref route) => route.clone(),
+                None => return,
+            };
+        if self.page(&route).is_none() {
+            let page = {
+                let pages = Pages {
+                    relm: &self.relm,
+                    stack: &self.stack,
+                };
+                (self.create_page)(&route, &pages)
+            };
+            self.pages.push((route.clone(), page));
+        }
+        if let Some(page) = self.page(&route) {
+            self.stack.set_visible_child(&page.widget);
+        }
+        self.destroy_hidden_pages();
+    }
+
+    // NOTE: the widget is removed from the stack before dropping the component so that relm does
+    // not warn about a component dropped while its widget is still in a container.
+    fn destroy_hidden_pages(&mut self) {
+        let history = &self.history;
+        let stack = &self.stack;
+        self.pages.retain(|&(ref route, ref page)| {
+            let keep = history.keeps(route, page.keep);
+            if !keep {
+                stack.remove(&page.widget);
+            }
+            keep
+        });
+    }
+}
+
+// The route currently shown and the routes to go back to.
+struct History<ROUTE> {
+    back_stack: Vec<ROUTE>,
+    current: Option<ROUTE>,
+}
+
+impl<ROUTE: PartialEq> History<ROUTE> {
+    fn new() -> Self {
+        History {
+            back_stack: vec![],
+            current: None,
+        }
+    }
+
+    // Check whether the page of `route` should be kept.
+    fn keeps(&self, route: &ROUTE, keep: Keep) -> bool {
+        self.current.as_ref() == Some(route) ||
+            match keep {
+                Always => true,
+                InBackStack => self.back_stack.contains(route),
+                Never => false,
+            }
+    }
+
+    // Make the last route of the back stack the current one and return the previous current route.
+    fn pop(&mut self) -> Option<ROUTE> {
+        let route = self.back_stack.pop()?;
+        mem::replace(&mut self.current, Some(route))
+    }
+
+    // Return false if `route` is already the current route.
+    fn push(&mut self, route: ROUTE) -> bool {
+        if self.current.as_ref() == Some(&route) {
+            return false;
+        }
+        if let Some(current) = self.current.take() {
+            self.back_stack.push(current);
+        }
+        self.current = Some(route);
+        true
+    }
+
+    fn replace(&mut self, route: ROUTE) {
+        self.current = Some(route);
+    }
+
+    fn reset(&mut self, route: ROUTE) {
+        self.back_stack.clear();
+        self.current = Some(route);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::History;
+    use super::Keep::{Always, InBackStack, Never};
+
+    #[test]
+    fn back_stack() {
+        let mut history = History::new();
+        assert_eq!(history.pop(), None);
+        assert!(history.push("home"));
+        assert!(!history.push("home"));
+        assert!(history.push("article 1"));
+        assert!(history.push("article 2"));
+        assert_eq!(history.back_stack, vec!["home", "article 1"]);
+        assert_eq!(history.current, Some("article 2"));
+
+        assert_eq!(history.pop(), Some("article 2"));
+        assert_eq!(history.back_stack, vec!["home"]);
+        assert_eq!(history.current, Some("article 1"));
+
+        history.replace("article 3");
+        assert_eq!(history.back_stack, vec!["home"]);
+        assert_eq!(history.current, Some("article 3"));
+
+        assert_eq!(history.pop(), Some("article 3"));
+        assert_eq!(history.pop(), None);
+        assert!(history.back_stack.is_empty());
+        assert_eq!(history.current, Some("home"));
+
+        assert!(history.push("article 4"));
+        history.reset("settings");
+        assert!(history.back_stack.is_empty());
+        assert_eq!(history.current, Some("settings"));
+    }
+
+    #[test]
+    fn kept_pages() {
+        let mut history = History::new();
+        assert!(history.push("home"));
+        assert!(history.push("article 1"));
+        assert!(history.push("article 2"));
+
+        assert!(history.keeps(&"article 2", Never));
+        assert!(history.keeps(&"article 1", InBackStack));
+        assert!(!history.keeps(&"article 1", Never));
+        assert!(history.keeps(&"article 1", Always));
+
+        assert_eq!(history.pop(), Some("article 2"));
+        assert!(!history.keeps(&"article 2", InBackStack));
+        assert!(history.keeps(&"article 2", Always));
+
+        history.reset("home");
+        assert!(history.keeps(&"home", InBackStack));
+        assert!(!history.keeps(&"article 1", InBackStack));
+    }
+}
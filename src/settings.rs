@@ -0,0 +1,567 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::str::FromStr;
+
+use gio::{self, FileExt, FileMonitorExt};
+use glib::{self, Cast, Continue, Object, ObjectExt, SignalHandlerId, SourceId, ToValue, Type, Value, WeakRef};
+use gtk::{self, IsA};
+
+use binding::BindingSource;
+use super::{Relm, Update};
+
+// Delay, in milliseconds, between a change and its write to the file.
+const SAVE_DELAY: u32 = 500;
+
+type Groups = BTreeMap<String, BTreeMap<String, String>>;
+
+thread_local! {
+    static NEXT_NOTIFY_ID: Cell<u64> = Cell::new(0);
+    // NOTE: the handlers of the notify signal of the bound properties are kept here because the
+    // callback of ObjectExt::connect() must be Send.
+    static NOTIFY_HANDLERS: RefCell<HashMap<u64, Box<Fn()>>> = RefCell::new(HashMap::new());
+}
+
+// The notify handler of a property bound to a setting.
+struct Binding {
+    handler_id: SignalHandlerId,
+    notify_id: u64,
+    target: WeakRef<Object>,
+}
+
+impl Binding {
+    fn disconnect(self) {
+        let _ = NOTIFY_HANDLERS.with(|handlers| handlers.borrow_mut().remove(&self.notify_id));
+        if let Some(target) = self.target.upgrade() {
+            target.disconnect(self.handler_id);
+        }
+    }
+}
+
+// The observers receive None when the setting is removed from the file.
+type Observer = Rc<Fn(Option<&str>)>;
+
+struct SettingsData {
+    bindings: Vec<Binding>,
+    group: String,
+    groups: Groups,
+    monitor: Option<gio::FileMonitor>,
+    observers: Vec<(String, Observer)>,
+    path: PathBuf,
+    save_source: Option<SourceId>,
+}
+
+impl Drop for SettingsData {
+    fn drop(&mut self) {
+        for binding in self.bindings.drain(..) {
+            binding.disconnect();
+        }
+        if self.save_source.take().is_some() {
+            if let Err(error) = write_file(&self.path, &self.groups) {
+                error!("Cannot save the settings to {}: {}", self.path.display(), error);
+            }
+        }
+    }
+}
+
+/// Preferences stored in a key file, with one `key=value` line per setting in a `[group]`.
+/// The values are escaped like `GKeyFile` does, so the file can be read by `glib::KeyFile`.
+///
+/// The changes are written to the file shortly after they are made and the changes made to the
+/// file by other programs are loaded, sending the messages of the [`watch()`](#method.watch)es.
+/// Settings can be bound to widget properties with [`bind()`](#method.bind), or in the `view!`
+/// macro with `bind!()`, where the underscores of the key are replaced by dashes:
+///
+/// ```ignore
+/// gtk::Switch {
+///     // Bound to the key dark-theme.
+///     active: bind!(self.model.settings.dark_theme),
+/// },
+/// ```
+#[derive(Clone)]
+pub struct Settings {
+    data: Rc<RefCell<SettingsData>>,
+}
+
+impl Settings {
+    /// Load the settings of `group` from the key file at `path`.
+    /// The file is created when a setting is changed if it does not exist.
+    pub fn load<P: AsRef<Path>>(path: P, group: &str) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let groups =
+            match File::open(&path) {
+                Ok(file) => read_file(file)?,
+                Err(ref error) if error.kind() == io::ErrorKind::NotFound => Groups::new(),
+                Err(error) => return Err(error),
+            };
+        let settings = Settings {
+            data: Rc::new(RefCell::new(SettingsData {
+                bindings: vec![],
+                group: group.to_string(),
+                groups,
+                monitor: None,
+                observers: vec![],
+                path: path.clone(),
+                save_source: None,
+            })),
+        };
+        let monitor = gio::File::new_for_path(&path).monitor_file(gio::FileMonitorFlags::NONE, None)
+            .map_err(|error| io::Error::new(io::ErrorKind::Other, error.to_string()))?;
+        let data = Rc::downgrade(&settings.data);
+        let _ = monitor.connect_changed(move |_, _, _, event| {
+            if event == gio::FileMonitorEvent::ChangesDoneHint || event == gio::FileMonitorEvent::Deleted {
+                if let Some(data) = data.upgrade() {
+                    Settings { data }.reload();
+                }
+            }
+        });
+        settings.data.borrow_mut().monitor = Some(monitor);
+        Ok(settings)
+    }
+
+    /// Bind the `property` of `target` to the setting `key`: the property is set to the value of
+    /// the setting right away and when it changes, and the setting is changed when the property is.
+    /// The property keeps its value when the setting is removed from the file.
+    /// The binding is removed when the settings are dropped.
+    pub fn bind<TARGET: IsA<Object>>(&self, key: &str, target: &TARGET, property: &str) {
+        let target: Object = target.clone().upcast();
+        let property_type =
+            match target.find_property(property) {
+                Some(spec) => spec.get_value_type(),
+                None => {
+                    warn!("Cannot bind the setting {}: no property {}", key, property);
+                    return;
+                },
+            };
+
+        let weak_target: WeakRef<Object> = target.downgrade();
+        let target_property = property.to_string();
+        let set_property = move |value: Option<&str>| {
+            let value = value.and_then(|value| string_to_value(value, property_type));
+            if let (Some(target), Some(value)) = (weak_target.upgrade(), value) {
+                let _ = target.set_property(&target_property, &value);
+            }
+        };
+        if let Some(value) = self.get::<String>(key) {
+            set_property(Some(&value));
+        }
+        self.observe(key, set_property);
+
+        let notify_id = NEXT_NOTIFY_ID.with(|next_id| {
+            let id = next_id.get();
+            next_id.set(id + 1);
+            id
+        });
+        let data = Rc::downgrade(&self.data);
+        let weak_target = target.downgrade();
+        let key = key.to_string();
+        let source_property = property.to_string();
+        let handler: Box<Fn()> = Box::new(move || {
+            let value = weak_target.upgrade()
+                .and_then(|target| target.get_property(&source_property).ok())
+                .and_then(|value| value_to_string(&value));
+            if let (Some(data), Some(value)) = (data.upgrade(), value) {
+                Settings { data }.set(&key, value);
+            }
+        });
+        let signal = format!("notify::{}", property);
+        let handler_id = target.connect(&signal, false, move |_| {
+            NOTIFY_HANDLERS.with(|handlers| {
+                if let Some(handler) = handlers.borrow().get(&notify_id) {
+                    handler();
+                }
+            });
+            None
+        });
+        let handler_id =
+            match handler_id {
+                Ok(handler_id) => handler_id,
+                Err(error) => {
+                    warn!("Cannot bind the setting {}: {}", key, error);
+                    return;
+                },
+            };
+        let _ = NOTIFY_HANDLERS.with(|handlers| handlers.borrow_mut().insert(notify_id, handler));
+
+        let mut data = self.data.borrow_mut();
+        // Remove the bindings of the destroyed objects.
+        let (bindings, destroyed): (Vec<_>, Vec<_>) = data.bindings.drain(..)
+            .partition(|binding: &Binding| binding.target.upgrade().is_some());
+        data.bindings = bindings;
+        for binding in destroyed {
+            binding.disconnect();
+        }
+        data.bindings.push(Binding {
+            handler_id,
+            notify_id,
+            target: target.downgrade(),
+        });
+    }
+
+    /// Get the value of the setting `key`, or `None` if it does not exist or cannot be parsed.
+    pub fn get<T: FromStr>(&self, key: &str) -> Option<T> {
+        let data = self.data.borrow();
+        data.groups.get(&data.group)
+            .and_then(|group| group.get(key))
+            .and_then(|value| value.parse().ok())
+    }
+
+    /// Get the value of the setting `key`, or `default` if it does not exist or cannot be parsed.
+    pub fn get_or<T: FromStr>(&self, key: &str, default: T) -> T {
+        self.get(key).unwrap_or(default)
+    }
+
+    /// Write the settings to the file now instead of waiting for the delayed write.
+    pub fn save(&self) -> io::Result<()> {
+        let mut data = self.data.borrow_mut();
+        if let Some(source) = data.save_source.take() {
+            glib::source_remove(source);
+        }
+        write_file(&data.path, &data.groups)
+    }
+
+    /// Change the value of the setting `key`.
+    pub fn set<T: ToString>(&self, key: &str, value: T) {
+        let value = value.to_string();
+        {
+            let mut data = self.data.borrow_mut();
+            let data = &mut *data;
+            let group = data.groups.entry(data.group.clone()).or_insert_with(BTreeMap::new);
+            if group.get(key) == Some(&value) {
+                return;
+            }
+            let _ = group.insert(key.to_string(), value.clone());
+            if data.save_source.is_none() {
+                let weak_data = Rc::downgrade(&self.data);
+                data.save_source = Some(gtk::timeout_add(SAVE_DELAY, move || {
+                    if let Some(data) = weak_data.upgrade() {
+                        let mut data = data.borrow_mut();
+                        data.save_source = None;
+                        if let Err(error) = write_file(&data.path, &data.groups) {
+                            error!("Cannot save the settings to {}: {}", data.path.display(), error);
+                        }
+                    }
+                    Continue(false)
+                }));
+            }
+        }
+        self.notify(key, Some(&value));
+    }
+
+    /// Send the message created by `callback` with the new value of the setting `key` when it
+    /// changes, including when the file is changed by another program.
+    pub fn watch<CALLBACK, T, UPDATE>(&self, key: &str, relm: &Relm<UPDATE>, callback: CALLBACK)
+        where CALLBACK: Fn(T) -> UPDATE::Msg + 'static,
+              T: FromStr,
+              UPDATE: Update + 'static,
+              UPDATE::Msg: 'static,
+    {
+        let stream = relm.stream().downgrade();
+        self.observe(key, move |value| {
+            if let (Some(Ok(value)), Some(stream)) = (value.map(str::parse), stream.upgrade()) {
+                stream.emit(callback(value));
+            }
+        });
+    }
+
+    /// Send the message created by `callback` when the setting `key` is removed from the file by
+    /// another program.
+    pub fn watch_removed<CALLBACK, UPDATE>(&self, key: &str, relm: &Relm<UPDATE>, callback: CALLBACK)
+        where CALLBACK: Fn() -> UPDATE::Msg + 'static,
+              UPDATE: Update + 'static,
+              UPDATE::Msg: 'static,
+    {
+        let stream = relm.stream().downgrade();
+        self.observe(key, move |value| {
+            if let (None, Some(stream)) = (value, stream.upgrade()) {
+                stream.emit(callback());
+            }
+        });
+    }
+
+    fn notify(&self, key: &str, value: Option<&str>) {
+        let observers: Vec<_> = self.data.borrow().observers.iter()
+            .filter(|&&(ref observed_key, _)| observed_key == key)
+            .map(|&(_, ref observer)| observer.clone())
+            .collect();
+        for observer in observers {
+            observer(value);
+        }
+    }
+
+    fn observe<OBSERVER: Fn(Option<&str>) + 'static>(&self, key: &str, observer: OBSERVER) {
+        self.data.borrow_mut().observers.push((key.to_string(), Rc::new(observer)));
+    }
+
+    fn reload(&self) {
+        // NOTE: the unsaved changes are kept and will overwrite the file.
+        if self.data.borrow().save_source.is_some() {
+            return;
+        }
+        let path = self.data.borrow().path.clone();
+        let groups =
+            match File::open(&path) {
+                Ok(file) => read_file(file),
+                Err(ref error) if error.kind() == io::ErrorKind::NotFound => Ok(Groups::new()),
+                Err(error) => Err(error),
+            };
+        let groups =
+            match groups {
+                Ok(groups) => groups,
+                Err(error) => {
+                    error!("Cannot reload the settings from {}: {}", path.display(), error);
+                    return;
+                },
+            };
+        let changes = {
+            let mut data = self.data.borrow_mut();
+            let changes = changed_values(data.groups.get(&data.group), groups.get(&data.group));
+            data.groups = groups;
+            changes
+        };
+        for (key, value) in changes {
+            self.notify(&key, value.as_ref().map(String::as_str));
+        }
+    }
+}
+
+impl BindingSource for Settings {
+    type Builder = SettingBinding;
+
+    fn bind_property_to(&self, source_property: &str, target: Object, target_property: &str) -> SettingBinding {
+        SettingBinding {
+            key: source_property.to_string(),
+            settings: self.clone(),
+            target,
+            target_property: target_property.to_string(),
+        }
+    }
+}
+
+/// Builder of a binding between a setting and a property, created by `bind!()` in the `view!`
+/// macro.
+pub struct SettingBinding {
+    key: String,
+    settings: Settings,
+    target: Object,
+    target_property: String,
+}
+
+impl SettingBinding {
+    /// Create the binding, as with [`Settings::bind()`](struct.Settings.html#method.bind).
+    pub fn build(self) {
+        self.settings.bind(&self.key, &self.target, &self.target_property);
+    }
+}
+
+// Get the values that changed between `old_values` and `new_values`, with None for the removed
+// keys.
+fn changed_values(old_values: Option<&BTreeMap<String, String>>, new_values: Option<&BTreeMap<String, String>>)
+    -> Vec<(String, Option<String>)>
+{
+    let empty = BTreeMap::new();
+    let old_values = old_values.unwrap_or(&empty);
+    let new_values = new_values.unwrap_or(&empty);
+    let removed = old_values.keys()
+        .filter(|key| !new_values.contains_key(*key))
+        .map(|key| (key.clone(), None));
+    let changed = new_values.iter()
+        .filter(|&(key, value)| old_values.get(key) != Some(value))
+        .map(|(key, value)| (key.clone(), Some(value.clone())));
+    removed.chain(changed).collect()
+}
+
+// Escape a value like GKeyFile does.
+fn escape(value: &str) -> String {
+    let mut result = String::new();
+    let last = value.chars().count().saturating_sub(1);
+    for (index, character) in value.chars().enumerate() {
+        match character {
+            // NOTE: the spaces around the value would be stripped when reading it.
+            ' ' if index == 0 || index == last => result.push_str("\\s"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            '\\' => result.push_str("\\\\"),
+            _ => result.push(character),
+        }
+    }
+    result
+}
+
+fn unescape(value: &str) -> io::Result<String> {
+    let mut result = String::new();
+    let mut chars = value.chars();
+    while let Some(character) = chars.next() {
+        if character != '\\' {
+            result.push(character);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => result.push(' '),
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some('t') => result.push('\t'),
+            Some('\\') => result.push('\\'),
+            _ => return Err(io::Error::new(io::ErrorKind::InvalidData,
+                format!("invalid escape sequence in `{}`", value))),
+        }
+    }
+    Ok(result)
+}
+
+fn read_file(file: File) -> io::Result<Groups> {
+    parse(BufReader::new(file))
+}
+
+fn parse<READ: BufRead>(reader: READ) -> io::Result<Groups> {
+    let mut groups = Groups::new();
+    let mut current_group = None;
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('[') && line.ends_with(']') {
+            let name = line[1..line.len() - 1].trim().to_string();
+            current_group = Some(name.clone());
+            let _ = groups.entry(name).or_insert_with(BTreeMap::new);
+            continue;
+        }
+        let mut parts = line.splitn(2, '=');
+        match (&current_group, parts.next(), parts.next()) {
+            (&Some(ref group), Some(key), Some(value)) => {
+                let group = groups.entry(group.clone()).or_insert_with(BTreeMap::new);
+                let _ = group.insert(key.trim().to_string(), unescape(value.trim())?);
+            },
+            _ => return Err(io::Error::new(io::ErrorKind::InvalidData,
+                format!("expected `key=value` in a group, found `{}`", line))),
+        }
+    }
+    Ok(groups)
+}
+
+fn string_to_value(value: &str, typ: Type) -> Option<Value> {
+    match typ {
+        Type::Bool => value.parse::<bool>().ok().map(|value| value.to_value()),
+        Type::F32 => value.parse::<f32>().ok().map(|value| value.to_value()),
+        Type::F64 => value.parse::<f64>().ok().map(|value| value.to_value()),
+        Type::I32 => value.parse::<i32>().ok().map(|value| value.to_value()),
+        Type::I64 => value.parse::<i64>().ok().map(|value| value.to_value()),
+        Type::String => Some(value.to_value()),
+        Type::U32 => value.parse::<u32>().ok().map(|value| value.to_value()),
+        Type::U64 => value.parse::<u64>().ok().map(|value| value.to_value()),
+        _ => None,
+    }
+}
+
+fn value_to_string(value: &Value) -> Option<String> {
+    match value.type_() {
+        Type::Bool => value.get::<bool>().map(|value| value.to_string()),
+        Type::F32 => value.get::<f32>().map(|value| value.to_string()),
+        Type::F64 => value.get::<f64>().map(|value| value.to_string()),
+        Type::I32 => value.get::<i32>().map(|value| value.to_string()),
+        Type::I64 => value.get::<i64>().map(|value| value.to_string()),
+        Type::String => value.get::<String>(),
+        Type::U32 => value.get::<u32>().map(|value| value.to_string()),
+        Type::U64 => value.get::<u64>().map(|value| value.to_string()),
+        _ => None,
+    }
+}
+
+fn write_file(path: &Path, groups: &Groups) -> io::Result<()> {
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory)?;
+    }
+    let mut file = File::create(path)?;
+    format(&mut file, groups)
+}
+
+fn format<WRITE: Write>(writer: &mut WRITE, groups: &Groups) -> io::Result<()> {
+    for (name, values) in groups {
+        writeln!(writer, "[{}]", name)?;
+        for (key, value) in values {
+            writeln!(writer, "{}={}", key, escape(value))?;
+        }
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{Groups, changed_values, escape, format, parse, unescape};
+
+    #[test]
+    fn escaping() {
+        let value = " two\tlines\nwith \\ ";
+        assert_eq!(escape(value), "\\stwo\\tlines\\nwith \\\\\\s");
+        assert_eq!(unescape(&escape(value)).unwrap(), value);
+        assert_eq!(escape(" "), "\\s");
+        assert!(unescape("\\x").is_err());
+        assert!(unescape("end\\").is_err());
+    }
+
+    #[test]
+    fn parse_key_file() {
+        let groups = parse("# comment\n[app]\nname = relm\npath=C:\\\\relm\n\n[other]\nkey=\\svalue\n".as_bytes())
+            .unwrap();
+        assert_eq!(groups["app"]["name"], "relm");
+        assert_eq!(groups["app"]["path"], "C:\\relm");
+        assert_eq!(groups["other"]["key"], " value");
+        assert!(parse("key=value\n".as_bytes()).is_err());
+        assert!(parse("[app]\nkey\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut groups = Groups::new();
+        let mut values = BTreeMap::new();
+        let _ = values.insert("text".to_string(), "multi\nline = text ".to_string());
+        let _ = groups.insert("app".to_string(), values);
+        let mut file = vec![];
+        format(&mut file, &groups).unwrap();
+        assert_eq!(parse(file.as_slice()).unwrap(), groups);
+    }
+
+    #[test]
+    fn removed_keys() {
+        let mut old_values = BTreeMap::new();
+        let _ = old_values.insert("kept".to_string(), "1".to_string());
+        let _ = old_values.insert("removed".to_string(), "2".to_string());
+        let mut new_values = BTreeMap::new();
+        let _ = new_values.insert("added".to_string(), "3".to_string());
+        let _ = new_values.insert("kept".to_string(), "1".to_string());
+        assert_eq!(changed_values(Some(&old_values), Some(&new_values)),
+            vec![("removed".to_string(), None), ("added".to_string(), Some("3".to_string()))]);
+        assert_eq!(changed_values(Some(&old_values), None),
+            vec![("kept".to_string(), None), ("removed".to_string(), None)]);
+    }
+}
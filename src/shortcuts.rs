@@ -0,0 +1,350 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+use std::rc::Rc;
+
+use gdk::ModifierType;
+use glib::{Cast, Closure, ToValue};
+use gtk::{
+    self,
+    AccelFlags,
+    AccelGroup,
+    AccelGroupExt,
+    GtkWindowExt,
+    IsA,
+};
+
+use super::{Relm, Update};
+
+thread_local! {
+    static NEXT_SHORTCUTS_ID: Cell<u64> = Cell::new(0);
+    // NOTE: the callbacks are kept here because the function of a glib::Closure must be Send.
+    // They are keyed by the id of their Shortcuts and their name.
+    static CALLBACKS: RefCell<HashMap<(u64, String), Rc<Fn()>>> = RefCell::new(HashMap::new());
+}
+
+struct Shortcut {
+    accelerator: String,
+    key: u32,
+    modifiers: ModifierType,
+}
+
+/// Keyboard shortcuts of a window sending messages to a component.
+///
+/// The shortcuts only work when their window is focused and are removed when this object is dropped,
+/// so it is usually kept in the model:
+///
+/// ```ignore
+/// let mut shortcuts = Shortcuts::new(&self.window, &self.model.relm);
+/// shortcuts.add("save", "<Control>s", || Save)?;
+/// // The user can remap the shortcuts with lines like "save = <Control><Shift>s".
+/// shortcuts.load_config(config_dir.join("shortcuts"))?;
+/// self.model.shortcuts = Some(shortcuts);
+/// ```
+pub struct Shortcuts<UPDATE: Update> {
+    accel_group: AccelGroup,
+    id: u64,
+    relm: Relm<UPDATE>,
+    shortcuts: HashMap<String, Shortcut>,
+    window: gtk::Window,
+}
+
+impl<UPDATE> Shortcuts<UPDATE>
+    where UPDATE: Update + 'static,
+          UPDATE::Msg: 'static,
+{
+    /// Create the shortcuts of `window`, which will send their messages to `relm`.
+    pub fn new<WINDOW: IsA<gtk::Window>>(window: &WINDOW, relm: &Relm<UPDATE>) -> Self {
+        let accel_group = AccelGroup::new();
+        window.add_accel_group(&accel_group);
+        let id = NEXT_SHORTCUTS_ID.with(|next_id| {
+            let id = next_id.get();
+            next_id.set(id + 1);
+            id
+        });
+        Shortcuts {
+            accel_group,
+            id,
+            relm: relm.clone(),
+            shortcuts: HashMap::new(),
+            window: window.clone().upcast(),
+        }
+    }
+
+    /// Get the current accelerator of the shortcut called `name`.
+    pub fn accelerator(&self, name: &str) -> Option<&str> {
+        self.shortcuts.get(name).map(|shortcut| shortcut.accelerator.as_str())
+    }
+
+    /// Add a shortcut called `name` sending the message created by `callback` when the keys of
+    /// `accelerator` (like `<Control>s`) are pressed.
+    /// If a shortcut with the same name already exists, it is replaced.
+    ///
+    /// Return an error if the accelerator is invalid or used by another shortcut.
+    pub fn add<CALLBACK>(&mut self, name: &str, accelerator: &str, callback: CALLBACK) -> io::Result<()>
+        where CALLBACK: Fn() -> UPDATE::Msg + 'static,
+    {
+        let (key, modifiers) = parse_accelerator(accelerator)?;
+        {
+            let mut accelerators = self.accelerators();
+            let _ = accelerators.insert(name, (key, modifiers));
+            check_duplicates(&accelerators)?;
+        }
+        if let Some(old_shortcut) = self.shortcuts.remove(name) {
+            let _ = self.accel_group.disconnect_key(old_shortcut.key, old_shortcut.modifiers);
+        }
+        let stream = self.relm.stream().downgrade();
+        let emit_callback: Rc<Fn()> = Rc::new(move || {
+            if let Some(stream) = stream.upgrade() {
+                stream.emit(callback());
+            }
+        });
+        let _ = CALLBACKS.with(|callbacks| callbacks.borrow_mut().insert((self.id, name.to_string()), emit_callback));
+        let shortcut = Shortcut {
+            accelerator: accelerator.to_string(),
+            key,
+            modifiers,
+        };
+        self.connect(name, &shortcut);
+        let _ = self.shortcuts.insert(name.to_string(), shortcut);
+        Ok(())
+    }
+
+    /// Override the accelerators with the ones in the file at `path`.
+    ///
+    /// Each line of this file has the form `name = accelerator`, where `name` is the name given to
+    /// [`add()`](#method.add). Empty lines and lines starting with `#` are ignored.
+    /// No accelerator is changed if the file has an error, like an unknown name or an accelerator
+    /// used by two shortcuts.
+    pub fn load_config<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        let config = read_config(BufReader::new(File::open(path)?))?;
+        self.remap(config)
+    }
+
+    /// Change the accelerator of the shortcut called `name`.
+    ///
+    /// Return an error if the accelerator is invalid or used by another shortcut.
+    pub fn set_accelerator(&mut self, name: &str, accelerator: &str) -> io::Result<()> {
+        self.remap(vec![(name.to_string(), accelerator.to_string())])
+    }
+
+    fn accelerators(&self) -> HashMap<&str, (u32, ModifierType)> {
+        self.shortcuts.iter()
+            .map(|(name, shortcut)| (name.as_str(), (shortcut.key, shortcut.modifiers)))
+            .collect()
+    }
+
+    fn connect(&self, name: &str, shortcut: &Shortcut) {
+        let key = (self.id, name.to_string());
+        let closure = Closure::new(move |_| {
+            let callback = CALLBACKS.with(|callbacks| callbacks.borrow().get(&key).cloned());
+            if let Some(callback) = callback {
+                callback();
+            }
+            Some(true.to_value())
+        });
+        AccelGroupExt::connect(&self.accel_group, shortcut.key, shortcut.modifiers, AccelFlags::VISIBLE, &closure);
+    }
+
+    // Change the accelerators of the shortcuts, after checking all of them, so that shortcuts can
+    // swap their accelerators.
+    fn remap(&mut self, accelerators: Vec<(String, String)>) -> io::Result<()> {
+        let mut changes = vec![];
+        {
+            let mut new_accelerators = self.accelerators();
+            for (name, accelerator) in accelerators {
+                let keys = parse_accelerator(&accelerator)?;
+                match new_accelerators.get_mut(name.as_str()) {
+                    Some(new_keys) => *new_keys = keys,
+                    None => return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                        format!("unknown shortcut `{}`", name))),
+                }
+                changes.push((name, accelerator, keys));
+            }
+            check_duplicates(&new_accelerators)?;
+        }
+        for &(ref name, _, _) in &changes {
+            if let Some(shortcut) = self.shortcuts.get(name) {
+                let _ = self.accel_group.disconnect_key(shortcut.key, shortcut.modifiers);
+            }
+        }
+        let mut names = vec![];
+        for (name, accelerator, (key, modifiers)) in changes {
+            if let Some(shortcut) = self.shortcuts.get_mut(&name) {
+                shortcut.accelerator = accelerator;
+                shortcut.key = key;
+                shortcut.modifiers = modifiers;
+            }
+            names.push(name);
+        }
+        names.sort();
+        names.dedup();
+        for name in names {
+            if let Some(shortcut) = self.shortcuts.get(&name) {
+                self.connect(&name, shortcut);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<UPDATE: Update> Drop for Shortcuts<UPDATE> {
+    fn drop(&mut self) {
+        for (name, shortcut) in self.shortcuts.drain() {
+            let _ = self.accel_group.disconnect_key(shortcut.key, shortcut.modifiers);
+            let _ = CALLBACKS.with(|callbacks| callbacks.borrow_mut().remove(&(self.id, name)));
+        }
+        self.window.remove_accel_group(&self.accel_group);
+    }
+}
+
+// Return an error naming the first two shortcuts (by name) using the same accelerator.
+fn check_duplicates(accelerators: &HashMap<&str, (u32, ModifierType)>) -> io::Result<()> {
+    let mut names: Vec<_> = accelerators.keys().collect();
+    names.sort();
+    let mut used = HashMap::new();
+    for name in names {
+        if let Some(other_name) = used.insert(accelerators[name], name) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                format!("the shortcuts `{}` and `{}` have the same accelerator", other_name, name)));
+        }
+    }
+    Ok(())
+}
+
+fn parse_accelerator(accelerator: &str) -> io::Result<(u32, ModifierType)> {
+    let (key, modifiers) = gtk::accelerator_parse(accelerator);
+    if key == 0 || !gtk::accelerator_valid(key, modifiers) {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("invalid accelerator `{}`", accelerator)));
+    }
+    Ok((key, modifiers))
+}
+
+// Read the `name = accelerator` lines of a configuration file.
+fn read_config<READ: BufRead>(reader: READ) -> io::Result<Vec<(String, String)>> {
+    let mut accelerators = vec![];
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut parts = line.splitn(2, '=');
+        match (parts.next().map(str::trim), parts.next()) {
+            (Some(name), Some(accelerator)) if !name.is_empty() =>
+                accelerators.push((name.to_string(), accelerator.trim().to_string())),
+            _ => return Err(io::Error::new(io::ErrorKind::InvalidData,
+                format!("expected `name = accelerator`, found `{}`", line))),
+        }
+    }
+    Ok(accelerators)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use std::io::{Cursor, ErrorKind};
+
+    use gdk::ModifierType;
+    use gdk::enums::key;
+    use gtk::{self, WindowType};
+
+    use {EventStream, Relm, Update, create_executor};
+    use super::{Shortcuts, parse_accelerator, read_config};
+
+    struct Test;
+
+    impl Update for Test {
+        type Model = ();
+        type ModelParam = ();
+        type Msg = ();
+
+        fn model(_: &Relm<Self>, _: ()) {
+        }
+
+        fn update(&mut self, _msg: ()) {
+        }
+    }
+
+    fn config(text: &str) -> Vec<(String, String)> {
+        read_config(Cursor::new(text)).expect("config")
+    }
+
+    #[test]
+    fn read_configs() {
+        assert_eq!(config("# Shortcuts\n\nsave = <Control>s\n  # open = <Control>o\n   \nquit=<Control>q  \n"),
+            vec![("save".to_string(), "<Control>s".to_string()), ("quit".to_string(), "<Control>q".to_string())]);
+        assert_eq!(config("copy = <Control>=\n"), vec![("copy".to_string(), "<Control>=".to_string())]);
+        assert!(config("").is_empty());
+        for text in &["save <Control>s", "= <Control>s", "save"] {
+            let error = read_config(Cursor::new(text)).expect_err("malformed line");
+            assert_eq!(error.kind(), ErrorKind::InvalidData);
+        }
+    }
+
+    // NOTE: GTK+ can only be used from one thread, so everything using it is tested in the same test.
+    #[test]
+    fn accelerators() {
+        gtk::init().expect("gtk::init()");
+
+        let (key, modifiers) = parse_accelerator("<Control><Shift>s").expect("accelerator");
+        assert_eq!(key, key::s);
+        assert_eq!(modifiers, ModifierType::CONTROL_MASK | ModifierType::SHIFT_MASK);
+        assert!(parse_accelerator("<Control>").is_err());
+        assert!(parse_accelerator("<Control>unknown").is_err());
+        assert!(parse_accelerator("").is_err());
+
+        let window = gtk::Window::new(WindowType::Toplevel);
+        let relm = Relm::<Test>::new(create_executor(), EventStream::new());
+        let mut shortcuts = Shortcuts::new(&window, &relm);
+        shortcuts.add("open", "<Control>o", || ()).expect("add open");
+        shortcuts.add("save", "<Control>s", || ()).expect("add save");
+        assert!(shortcuts.add("quit", "<Control>s", || ()).is_err());
+        assert!(shortcuts.accelerator("quit").is_none());
+        assert!(shortcuts.set_accelerator("open", "<Control>s").is_err());
+        assert!(shortcuts.set_accelerator("quit", "<Control>q").is_err());
+        assert_eq!(shortcuts.accelerator("open"), Some("<Control>o"));
+
+        let path = env::temp_dir().join("relm-shortcuts-test");
+        // The accelerators can be swapped.
+        fs::write(&path, "# Swap open and save.\nopen = <Control>s\n\nsave = <Control>o\n").expect("write config");
+        shortcuts.load_config(&path).expect("load config");
+        assert_eq!(shortcuts.accelerator("open"), Some("<Control>s"));
+        assert_eq!(shortcuts.accelerator("save"), Some("<Control>o"));
+        // Nothing is changed when the configuration has an error.
+        fs::write(&path, "open = <Control>p\nquit = <Control>q\n").expect("write config");
+        assert!(shortcuts.load_config(&path).is_err());
+        fs::write(&path, "open = <Control>p\nsave = <Control>p\n").expect("write config");
+        assert!(shortcuts.load_config(&path).is_err());
+        assert_eq!(shortcuts.accelerator("open"), Some("<Control>s"));
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(gtk::accel_groups_from_object(&window).len(), 1);
+        drop(shortcuts);
+        assert!(gtk::accel_groups_from_object(&window).is_empty());
+    }
+}
@@ -0,0 +1,168 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+use futures::{Poll, Stream};
+use futures::unsync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded};
+use glib::{self, Cast, Object, ObjectExt, SignalHandlerId, Value, WeakRef};
+use gtk::IsA;
+
+thread_local! {
+    static NEXT_SENDER_ID: Cell<u64> = Cell::new(0);
+    // NOTE: the senders of the signals connected by name are kept here because the callback of
+    // ObjectExt::connect() must be Send.
+    static SENDERS: RefCell<HashMap<u64, UnboundedSender<Vec<Value>>>> = RefCell::new(HashMap::new());
+}
+
+/// Sender given to the closure connecting the signal of a [`SignalStream`](struct.SignalStream.html).
+pub struct SignalSender<ITEM> {
+    sender: UnboundedSender<ITEM>,
+}
+
+impl<ITEM> Clone for SignalSender<ITEM> {
+    fn clone(&self) -> Self {
+        SignalSender {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<ITEM> SignalSender<ITEM> {
+    /// Send an item to the stream.
+    pub fn send(&self, item: ITEM) {
+        // NOTE: an error means that the stream was dropped, so there's nothing to do.
+        let _ = self.sender.unbounded_send(item);
+    }
+}
+
+/// Guard disconnecting a signal handler when it is dropped.
+/// The handler is not disconnected if the object was already destroyed.
+#[must_use]
+pub struct SignalGuard {
+    handler: Option<(WeakRef<Object>, SignalHandlerId)>,
+}
+
+impl SignalGuard {
+    /// Create a guard disconnecting the handler `handler_id` of `object` when dropped.
+    pub fn new<OBJECT>(object: &OBJECT, handler_id: SignalHandlerId) -> Self
+        where OBJECT: Clone + IsA<Object>,
+    {
+        let object: Object = object.clone().upcast();
+        SignalGuard {
+            handler: Some((object.downgrade(), handler_id)),
+        }
+    }
+}
+
+impl Drop for SignalGuard {
+    fn drop(&mut self) {
+        if let Some((object, handler_id)) = self.handler.take() {
+            if let Some(object) = object.upgrade() {
+                object.disconnect(handler_id);
+            }
+        }
+    }
+}
+
+/// A `Stream` of the emissions of a GTK+ signal, which can be used with futures combinators and
+/// [`Relm::connect_exec()`](struct.Relm.html#method.connect_exec):
+///
+/// ```ignore
+/// let clicks = SignalStream::new(&button, |button, sender| button.connect_clicked(move |_| sender.send(())));
+/// relm.connect_exec_ignore_err(clicks.take(3), |()| Clicked);
+/// ```
+///
+/// The signal handler is disconnected when the stream is dropped.
+pub struct SignalStream<ITEM> {
+    // NOTE: only used to disconnect the handler when the stream is dropped.
+    _guard: SignalGuard,
+    receiver: UnboundedReceiver<ITEM>,
+    sender_id: Option<u64>,
+}
+
+impl<ITEM> SignalStream<ITEM> {
+    /// Create a stream of the items sent by the handler connected by `connect`.
+    /// `connect` receives the object and the sender to call from the signal handler and must
+    /// return the id of the connected handler.
+    pub fn new<CONNECT, OBJECT>(object: &OBJECT, connect: CONNECT) -> Self
+        where CONNECT: FnOnce(&OBJECT, SignalSender<ITEM>) -> SignalHandlerId,
+              OBJECT: Clone + IsA<Object>,
+    {
+        let (sender, receiver) = unbounded();
+        let handler_id = connect(object, SignalSender {
+            sender,
+        });
+        SignalStream {
+            _guard: SignalGuard::new(object, handler_id),
+            receiver,
+            sender_id: None,
+        }
+    }
+}
+
+impl SignalStream<Vec<Value>> {
+    /// Create a stream of the arguments of the `signal` of `object`, including the object itself.
+    /// This only works for signals without return value and the signal must be emitted from the
+    /// main thread.
+    pub fn by_name<OBJECT>(object: &OBJECT, signal: &str) -> Result<Self, glib::BoolError>
+        where OBJECT: Clone + IsA<Object>,
+    {
+        let (sender, receiver) = unbounded();
+        let sender_id = NEXT_SENDER_ID.with(|next_id| {
+            let id = next_id.get();
+            next_id.set(id + 1);
+            id
+        });
+        let handler_id = object.connect(signal, false, move |values| {
+            SENDERS.with(|senders| {
+                if let Some(sender) = senders.borrow().get(&sender_id) {
+                    let _ = sender.unbounded_send(values.to_vec());
+                }
+            });
+            None
+        })?;
+        let _ = SENDERS.with(|senders| senders.borrow_mut().insert(sender_id, sender));
+        Ok(SignalStream {
+            _guard: SignalGuard::new(object, handler_id),
+            receiver,
+            sender_id: Some(sender_id),
+        })
+    }
+}
+
+impl<ITEM> Drop for SignalStream<ITEM> {
+    fn drop(&mut self) {
+        if let Some(sender_id) = self.sender_id {
+            let _ = SENDERS.with(|senders| senders.borrow_mut().remove(&sender_id));
+        }
+    }
+}
+
+impl<ITEM> Stream for SignalStream<ITEM> {
+    type Item = ITEM;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Option<ITEM>, ()> {
+        self.receiver.poll()
+    }
+}
@@ -0,0 +1,291 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::cell::{Cell, RefCell};
+use std::env;
+use std::path::{Path, PathBuf};
+use std::ptr;
+use std::rc::Rc;
+
+use gio::{self, FileExt, FileMonitorExt};
+use glib::{self, Cast, ObjectExt, StaticType, Value, WeakRef};
+use glib::translate::{FromGlibPtrNone, ToGlib, ToGlibPtr, from_glib_none};
+use glib_sys::{GFALSE, GTRUE, GType, gboolean, gpointer};
+use gobject_sys::GValue;
+use gtk::{
+    self,
+    CssProvider,
+    CssProviderExt,
+    IsA,
+    StyleContextExt,
+    WidgetExt,
+};
+use gtk_sys::{GtkWidget, gtk_container_forall};
+use libc::{c_char, c_uint, c_ulong};
+
+type EmissionHook = unsafe extern "C" fn(gpointer, c_uint, *const GValue, gpointer) -> gboolean;
+
+extern "C" {
+    fn g_signal_add_emission_hook(signal_id: c_uint, detail: u32, hook_func: Option<EmissionHook>,
+        hook_data: gpointer, data_destroy: Option<unsafe extern "C" fn(gpointer)>) -> c_ulong;
+    fn g_signal_lookup(name: *const c_char, itype: GType) -> c_uint;
+    fn g_signal_remove_emission_hook(signal_id: c_uint, hook_id: c_ulong);
+}
+
+thread_local! {
+    // NOTE: the signal id and the hook id of the emission hook, while there are styled widgets.
+    static PARENT_SET_HOOK: Cell<Option<(c_uint, c_ulong)>> = Cell::new(None);
+    // NOTE: the widgets a style sheet was applied to, with its provider. The provider is added to
+    // the widgets added in their descendants from an emission hook of the parent-set signal, since
+    // most containers do not emit their add signal when a child is added.
+    static STYLED_WIDGETS: RefCell<Vec<(WeakRef<gtk::Widget>, CssProvider)>> = RefCell::new(vec![]);
+}
+
+/// Add the style `class` to `widget` if `enabled` is true, remove it otherwise.
+///
+/// This is used by the `class name: value` items of the `view!` macro.
+pub fn toggle_style_class<WIDGET: IsA<gtk::Widget>>(widget: &WIDGET, class: &str, enabled: bool) {
+    if let Some(style_context) = widget.get_style_context() {
+        if enabled {
+            style_context.add_class(class);
+        }
+        else {
+            style_context.remove_class(class);
+        }
+    }
+}
+
+/// CSS styling the widgets of a component.
+///
+/// Unlike a provider added to the screen, it only applies to the widgets it is
+/// [`apply()`](#method.apply)ed to and their descendants, so it is usually kept in the model of
+/// the component.
+///
+/// When the `RELM_CSS_RELOAD` environment variable is set, the style sheets loaded from files are
+/// reloaded when the files change.
+pub struct StyleSheet {
+    monitor: Option<gio::FileMonitor>,
+    provider: Rc<RefCell<CssProvider>>,
+}
+
+impl StyleSheet {
+    /// Create a style sheet from `css`.
+    pub fn from_data(css: &str) -> Result<Self, glib::Error> {
+        let provider = CssProvider::new();
+        provider.load_from_data(css.as_bytes())?;
+        Ok(StyleSheet {
+            monitor: None,
+            provider: Rc::new(RefCell::new(provider)),
+        })
+    }
+
+    /// Create a style sheet from the CSS file at `path`.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, glib::Error> {
+        let path = path.as_ref().to_path_buf();
+        let provider = CssProvider::new();
+        provider.load_from_path(&path.to_string_lossy())?;
+        let mut style_sheet = StyleSheet {
+            monitor: None,
+            provider: Rc::new(RefCell::new(provider)),
+        };
+        if env::var_os("RELM_CSS_RELOAD").is_some() {
+            style_sheet.watch(path)?;
+        }
+        Ok(style_sheet)
+    }
+
+    /// Style `widget` and its descendants, including the ones added later.
+    /// Applying the style sheet again to the same widget has no effect.
+    pub fn apply<WIDGET: IsA<gtk::Widget>>(&self, widget: &WIDGET) {
+        let widget: gtk::Widget = widget.clone().upcast();
+        let provider = self.provider();
+        for_each_style_context(&widget, &|style_context| add_provider(style_context, &provider));
+        STYLED_WIDGETS.with(|styled_widgets| {
+            let mut styled_widgets = styled_widgets.borrow_mut();
+            styled_widgets.retain(|&(ref root, _)| root.upgrade().is_some());
+            let applied = styled_widgets.iter()
+                .any(|&(ref root, ref root_provider)| *root_provider == provider && root.upgrade() == Some(widget.clone()));
+            if !applied {
+                styled_widgets.push((widget.downgrade(), provider));
+            }
+        });
+        add_parent_set_hook();
+    }
+
+    /// Get the underlying CSS provider.
+    /// The provider is replaced when the style sheet is reloaded.
+    pub fn provider(&self) -> CssProvider {
+        self.provider.borrow().clone()
+    }
+
+    /// Reload the style sheet when the file at `path` changes, even when this is not enabled by the
+    /// `RELM_CSS_RELOAD` environment variable.
+    pub fn watch(&mut self, path: PathBuf) -> Result<(), glib::Error> {
+        let monitor = gio::File::new_for_path(&path).monitor_file(gio::FileMonitorFlags::NONE, None)?;
+        let provider = Rc::downgrade(&self.provider);
+        let _ = monitor.connect_changed(move |_, _, _, event| {
+            if event == gio::FileMonitorEvent::ChangesDoneHint {
+                // NOTE: the CSS is loaded in a new provider so that the current style is kept when
+                // the new one cannot be loaded.
+                let new_provider = CssProvider::new();
+                match new_provider.load_from_path(&path.to_string_lossy()) {
+                    Ok(()) => {
+                        if let Some(provider) = provider.upgrade() {
+                            replace_provider(&provider, new_provider);
+                        }
+                    },
+                    Err(error) => error!("Cannot reload {}: {}", path.display(), error),
+                }
+            }
+        });
+        self.monitor = Some(monitor);
+        Ok(())
+    }
+}
+
+impl Drop for StyleSheet {
+    fn drop(&mut self) {
+        let provider = self.provider();
+        let mut roots = vec![];
+        let no_styled_widgets = STYLED_WIDGETS.with(|styled_widgets| {
+            let mut styled_widgets = styled_widgets.borrow_mut();
+            styled_widgets.retain(|&(ref root, ref root_provider)| {
+                if *root_provider == provider {
+                    roots.extend(root.upgrade());
+                    return false;
+                }
+                root.upgrade().is_some()
+            });
+            styled_widgets.is_empty()
+        });
+        for root in roots {
+            for_each_style_context(&root, &|style_context| style_context.remove_provider(&provider));
+        }
+        if no_styled_widgets {
+            remove_parent_set_hook();
+        }
+    }
+}
+
+fn add_provider(style_context: &gtk::StyleContext, provider: &CssProvider) {
+    // NOTE: GTK+ does not add a provider twice to the same style context.
+    style_context.add_provider(provider, gtk::STYLE_PROVIDER_PRIORITY_APPLICATION as u32);
+}
+
+fn add_parent_set_hook() {
+    if PARENT_SET_HOOK.with(|hook| hook.get().is_some()) {
+        return;
+    }
+    unsafe {
+        let signal_id = g_signal_lookup(b"parent-set\0".as_ptr() as *const c_char,
+            gtk::Widget::static_type().to_glib());
+        let hook_id = g_signal_add_emission_hook(signal_id, 0, Some(parent_set_hook), ptr::null_mut(), None);
+        PARENT_SET_HOOK.with(|hook| hook.set(Some((signal_id, hook_id))));
+    }
+}
+
+// Get the children of `container`, including its internal children, like the buttons of a
+// gtk::SpinButton or the tab labels of a gtk::Notebook.
+fn all_children(container: &gtk::Container) -> Vec<gtk::Widget> {
+    unsafe extern "C" fn push_child(widget: *mut GtkWidget, data: gpointer) {
+        let children = &mut *(data as *mut Vec<gtk::Widget>);
+        children.push(from_glib_none(widget));
+    }
+
+    let mut children: Vec<gtk::Widget> = vec![];
+    unsafe {
+        gtk_container_forall(container.to_glib_none().0, Some(push_child), &mut children as *mut _ as gpointer);
+    }
+    children
+}
+
+fn for_each_style_context<CALLBACK: Fn(&gtk::StyleContext)>(widget: &gtk::Widget, callback: &CALLBACK) {
+    if let Some(style_context) = widget.get_style_context() {
+        callback(&style_context);
+    }
+    if let Ok(container) = widget.clone().downcast::<gtk::Container>() {
+        for child in all_children(&container) {
+            for_each_style_context(&child, callback);
+        }
+    }
+}
+
+unsafe extern "C" fn parent_set_hook(_hint: gpointer, _param_count: c_uint, params: *const GValue, _data: gpointer)
+    -> gboolean
+{
+    // NOTE: the first parameter is the widget whose parent changed.
+    if let Some(widget) = Value::from_glib_none(params).get::<gtk::Widget>() {
+        style_added_widget(&widget);
+    }
+    let styled_widgets = STYLED_WIDGETS.with(|styled_widgets| {
+        let mut styled_widgets = styled_widgets.borrow_mut();
+        styled_widgets.retain(|&(ref root, _)| root.upgrade().is_some());
+        !styled_widgets.is_empty()
+    });
+    if styled_widgets {
+        GTRUE
+    }
+    else {
+        // NOTE: GLib removes the hook when it returns false.
+        PARENT_SET_HOOK.with(|hook| hook.set(None));
+        GFALSE
+    }
+}
+
+fn remove_parent_set_hook() {
+    if let Some((signal_id, hook_id)) = PARENT_SET_HOOK.with(|hook| hook.take()) {
+        unsafe {
+            g_signal_remove_emission_hook(signal_id, hook_id);
+        }
+    }
+}
+
+// Replace the provider of a style sheet in the widgets it was applied to, after it was reloaded.
+fn replace_provider(provider: &RefCell<CssProvider>, new_provider: CssProvider) {
+    let old_provider = provider.replace(new_provider.clone());
+    let mut roots = vec![];
+    STYLED_WIDGETS.with(|styled_widgets| {
+        for &mut (ref root, ref mut root_provider) in styled_widgets.borrow_mut().iter_mut() {
+            if *root_provider == old_provider {
+                *root_provider = new_provider.clone();
+                roots.extend(root.upgrade());
+            }
+        }
+    });
+    for root in roots {
+        for_each_style_context(&root, &|style_context| {
+            style_context.remove_provider(&old_provider);
+            add_provider(style_context, &new_provider);
+        });
+    }
+}
+
+fn style_added_widget(widget: &gtk::Widget) {
+    let providers: Vec<_> = STYLED_WIDGETS.with(|styled_widgets| {
+        styled_widgets.borrow().iter()
+            .filter(|&&(ref root, _)| root.upgrade().map_or(false, |root| widget.is_ancestor(&root)))
+            .map(|&(_, ref provider)| provider.clone())
+            .collect()
+    });
+    for provider in providers {
+        for_each_style_context(widget, &|style_context| add_provider(style_context, &provider));
+    }
+}
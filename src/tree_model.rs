@@ -0,0 +1,565 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::cell::{Ref, RefCell};
+use std::rc::Rc;
+
+use glib::{Cast, ToValue, Type, Value};
+use gtk::{
+    self,
+    ListStoreExt,
+    ListStoreExtManual,
+    TreeModelExt,
+    TreeModelFilterExt,
+    TreeModelSortExt,
+    TreeSelectionExt,
+    TreeStoreExt,
+    TreeStoreExtManual,
+    TreeViewExt,
+};
+
+use signal_stream::SignalGuard;
+use super::{Relm, Update};
+
+/// Row of a `gtk::ListStore` or a `gtk::TreeStore`, where every field is a column.
+///
+/// This is usually implemented with `#[derive(TreeRow)]`: the columns are the fields of the
+/// struct, in the order they are declared, except the ones with the `#[column(skip)]` attribute.
+pub trait TreeRow {
+    /// Get the types of the columns.
+    fn column_types() -> Vec<Type>;
+
+    /// Get the values of the columns for this row.
+    fn to_values(&self) -> Vec<Value>;
+}
+
+/// Node of a [`RowTree`](struct.RowTree.html): a row with its children.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TreeNode<ROW> {
+    /// The nodes shown under the row when it is expanded.
+    pub children: Vec<TreeNode<ROW>>,
+    /// The row of the node.
+    pub row: ROW,
+}
+
+impl<ROW> TreeNode<ROW> {
+    /// Create a node without children.
+    pub fn new(row: ROW) -> Self {
+        TreeNode {
+            children: vec![],
+            row,
+        }
+    }
+
+    /// Create a node with `children`.
+    pub fn with_children(row: ROW, children: Vec<TreeNode<ROW>>) -> Self {
+        TreeNode {
+            children,
+            row,
+        }
+    }
+}
+
+/// Rows of a `gtk::ListStore`, kept in sync with the store.
+///
+/// The list is usually stored in the model: every change made through its methods is applied
+/// to the store, and [`set_rows()`](struct.RowList.html#method.set_rows) only inserts, removes and
+/// updates the rows that changed.
+///
+/// The model of the tree views given to the `connect_*()` methods must be the store, or a
+/// `gtk::TreeModelFilter` or `gtk::TreeModelSort` wrapping it.
+pub struct RowList<ROW> {
+    rows: Rc<RefCell<Vec<ROW>>>,
+    store: gtk::ListStore,
+}
+
+impl<ROW> RowList<ROW>
+    where ROW: Clone + PartialEq + TreeRow + 'static,
+{
+    /// Create an empty list with a new store.
+    pub fn new() -> Self {
+        RowList {
+            rows: Rc::new(RefCell::new(vec![])),
+            store: gtk::ListStore::new(&ROW::column_types()),
+        }
+    }
+
+    /// Remove all the rows.
+    pub fn clear(&mut self) {
+        self.rows.borrow_mut().clear();
+        self.store.clear();
+    }
+
+    /// Send the message created by `callback` with the activated row of `tree_view`.
+    /// The handler is disconnected when the returned guard is dropped.
+    pub fn connect_activated<CALLBACK, UPDATE>(&self, tree_view: &gtk::TreeView, relm: &Relm<UPDATE>,
+            callback: CALLBACK) -> SignalGuard
+        where CALLBACK: Fn(ROW) -> UPDATE::Msg + 'static,
+              UPDATE: Update + 'static,
+    {
+        let relm = relm.clone();
+        let rows = self.rows.clone();
+        let store: gtk::TreeModel = self.store.clone().upcast();
+        let handler_id = tree_view.connect_row_activated(move |tree_view, path, _| {
+            let row = activated_path(tree_view, path, &store).and_then(|path| list_row(&rows.borrow(), &path));
+            if let Some(row) = row {
+                relm.stream().emit(callback(row));
+            }
+        });
+        SignalGuard::new(tree_view, handler_id)
+    }
+
+    /// Send the message created by `callback` with the selected row of `tree_view` when the
+    /// selection changes.
+    /// The handler is disconnected when the returned guard is dropped.
+    pub fn connect_selection_changed<CALLBACK, UPDATE>(&self, tree_view: &gtk::TreeView, relm: &Relm<UPDATE>,
+            callback: CALLBACK) -> SignalGuard
+        where CALLBACK: Fn(Option<ROW>) -> UPDATE::Msg + 'static,
+              UPDATE: Update + 'static,
+    {
+        let relm = relm.clone();
+        let rows = self.rows.clone();
+        let store: gtk::TreeModel = self.store.clone().upcast();
+        let selection = tree_view.get_selection();
+        let handler_id = selection.connect_changed(move |selection| {
+            let row = selected_path(selection, &store).and_then(|path| list_row(&rows.borrow(), &path));
+            relm.stream().emit(callback(row));
+        });
+        SignalGuard::new(&selection, handler_id)
+    }
+
+    /// Get the row at `index`.
+    pub fn get(&self, index: usize) -> Option<ROW> {
+        self.rows.borrow().get(index).cloned()
+    }
+
+    /// Insert `row` at `index`.
+    ///
+    /// ## Panics
+    /// Panics if `index` is greater than the number of rows.
+    pub fn insert(&mut self, index: usize, row: ROW) {
+        let values = row.to_values();
+        let _ = self.store.insert_with_values(Some(index as u32), &columns::<ROW>(), &value_refs(&values));
+        self.rows.borrow_mut().insert(index, row);
+    }
+
+    /// Check whether the list is empty.
+    pub fn is_empty(&self) -> bool {
+        self.rows.borrow().is_empty()
+    }
+
+    /// Get the number of rows.
+    pub fn len(&self) -> usize {
+        self.rows.borrow().len()
+    }
+
+    /// Add `row` at the end of the list.
+    pub fn push(&mut self, row: ROW) {
+        let index = self.len();
+        self.insert(index, row);
+    }
+
+    /// Remove the row at `index` and return it.
+    ///
+    /// ## Panics
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> ROW {
+        let row = self.rows.borrow_mut().remove(index);
+        if let Some(iter) = iter_at(&self.store, &[index]) {
+            let _ = self.store.remove(&iter);
+        }
+        row
+    }
+
+    /// Get the rows.
+    pub fn rows(&self) -> Ref<Vec<ROW>> {
+        self.rows.borrow()
+    }
+
+    /// Replace the row at `index`.
+    ///
+    /// ## Panics
+    /// Panics if `index` is out of bounds.
+    pub fn set(&mut self, index: usize, row: ROW) {
+        if self.rows.borrow()[index] == row {
+            return;
+        }
+        if let Some(iter) = iter_at(&self.store, &[index]) {
+            let values = row.to_values();
+            self.store.set(&iter, &columns::<ROW>(), &value_refs(&values));
+        }
+        self.rows.borrow_mut()[index] = row;
+    }
+
+    /// Replace the rows by `rows`, only changing the rows of the store that are different.
+    pub fn set_rows<ROWS: IntoIterator<Item=ROW>>(&mut self, rows: ROWS) {
+        let rows: Vec<_> = rows.into_iter().collect();
+        let (start, old_end, new_end) = changed_range(&self.rows.borrow(), &rows);
+        for index in start..old_end.min(new_end) {
+            self.set(index, rows[index].clone());
+        }
+        for index in (new_end..old_end).rev() {
+            let _ = self.remove(index);
+        }
+        for index in old_end..new_end {
+            self.insert(index, rows[index].clone());
+        }
+    }
+
+    /// Get the store to use as the model of a `gtk::TreeView`.
+    pub fn store(&self) -> &gtk::ListStore {
+        &self.store
+    }
+}
+
+impl<ROW> Default for RowList<ROW>
+    where ROW: Clone + PartialEq + TreeRow + 'static,
+{
+    fn default() -> Self {
+        RowList::new()
+    }
+}
+
+/// Rows of a `gtk::TreeStore`, kept in sync with the store.
+///
+/// The rows are identified by their path: the index of every node from the root to the row.
+/// Like [`RowList`](struct.RowList.html), [`set_nodes()`](struct.RowTree.html#method.set_nodes)
+/// only inserts, removes and updates the rows that changed.
+///
+/// The model of the tree views given to the `connect_*()` methods must be the store, or a
+/// `gtk::TreeModelFilter` or `gtk::TreeModelSort` wrapping it.
+pub struct RowTree<ROW> {
+    nodes: Rc<RefCell<Vec<TreeNode<ROW>>>>,
+    store: gtk::TreeStore,
+}
+
+impl<ROW> RowTree<ROW>
+    where ROW: Clone + PartialEq + TreeRow + 'static,
+{
+    /// Create an empty tree with a new store.
+    pub fn new() -> Self {
+        RowTree {
+            nodes: Rc::new(RefCell::new(vec![])),
+            store: gtk::TreeStore::new(&ROW::column_types()),
+        }
+    }
+
+    /// Remove all the rows.
+    pub fn clear(&mut self) {
+        self.nodes.borrow_mut().clear();
+        self.store.clear();
+    }
+
+    /// Send the message created by `callback` with the activated row of `tree_view`.
+    /// The handler is disconnected when the returned guard is dropped.
+    pub fn connect_activated<CALLBACK, UPDATE>(&self, tree_view: &gtk::TreeView, relm: &Relm<UPDATE>,
+            callback: CALLBACK) -> SignalGuard
+        where CALLBACK: Fn(ROW) -> UPDATE::Msg + 'static,
+              UPDATE: Update + 'static,
+    {
+        let relm = relm.clone();
+        let nodes = self.nodes.clone();
+        let store: gtk::TreeModel = self.store.clone().upcast();
+        let handler_id = tree_view.connect_row_activated(move |tree_view, path, _| {
+            let row = activated_path(tree_view, path, &store)
+                .and_then(|path| node(&nodes.borrow(), &to_usize_path(&path)).map(|node| node.row.clone()));
+            if let Some(row) = row {
+                relm.stream().emit(callback(row));
+            }
+        });
+        SignalGuard::new(tree_view, handler_id)
+    }
+
+    /// Send the message created by `callback` with the selected row of `tree_view` when the
+    /// selection changes.
+    /// The handler is disconnected when the returned guard is dropped.
+    pub fn connect_selection_changed<CALLBACK, UPDATE>(&self, tree_view: &gtk::TreeView, relm: &Relm<UPDATE>,
+            callback: CALLBACK) -> SignalGuard
+        where CALLBACK: Fn(Option<ROW>) -> UPDATE::Msg + 'static,
+              UPDATE: Update + 'static,
+    {
+        let relm = relm.clone();
+        let nodes = self.nodes.clone();
+        let store: gtk::TreeModel = self.store.clone().upcast();
+        let selection = tree_view.get_selection();
+        let handler_id = selection.connect_changed(move |selection| {
+            let row = selected_path(selection, &store)
+                .and_then(|path| node(&nodes.borrow(), &to_usize_path(&path)).map(|node| node.row.clone()));
+            relm.stream().emit(callback(row));
+        });
+        SignalGuard::new(&selection, handler_id)
+    }
+
+    /// Get the row at `path`.
+    pub fn get(&self, path: &[usize]) -> Option<ROW> {
+        node(&self.nodes.borrow(), path).map(|node| node.row.clone())
+    }
+
+    /// Insert `node` and its children at `index` in the children of the row at `parent`.
+    /// The node is inserted at the root when `parent` is empty.
+    ///
+    /// ## Panics
+    /// Panics if there's no row at `parent` or if `index` is greater than its number of children.
+    pub fn insert(&mut self, parent: &[usize], index: usize, node: TreeNode<ROW>) {
+        let parent_iter = self.parent_iter(parent);
+        insert_node(&self.store, parent_iter.as_ref(), index, &node);
+        children_mut(&mut self.nodes.borrow_mut(), parent).insert(index, node);
+    }
+
+    /// Get the nodes at the root of the tree.
+    pub fn nodes(&self) -> Ref<Vec<TreeNode<ROW>>> {
+        self.nodes.borrow()
+    }
+
+    /// Remove the row at `path`, with its children, and return it.
+    ///
+    /// ## Panics
+    /// Panics if there's no row at `path`.
+    pub fn remove(&mut self, path: &[usize]) -> TreeNode<ROW> {
+        let (&index, parent) = path.split_last().expect("non-empty path");
+        let node = children_mut(&mut self.nodes.borrow_mut(), parent).remove(index);
+        if let Some(iter) = iter_at(&self.store, path) {
+            let _ = self.store.remove(&iter);
+        }
+        node
+    }
+
+    /// Replace the row at `path`, keeping its children.
+    ///
+    /// ## Panics
+    /// Panics if there's no row at `path`.
+    pub fn set(&mut self, path: &[usize], row: ROW) {
+        let (&index, parent) = path.split_last().expect("non-empty path");
+        {
+            let mut nodes = self.nodes.borrow_mut();
+            let node = &mut children_mut(&mut nodes, parent)[index];
+            if node.row == row {
+                return;
+            }
+            node.row = row.clone();
+        }
+        if let Some(iter) = iter_at(&self.store, path) {
+            let values = row.to_values();
+            self.store.set(&iter, &columns::<ROW>(), &value_refs(&values));
+        }
+    }
+
+    /// Replace the nodes of the tree by `nodes`, only changing the rows of the store that are
+    /// different.
+    pub fn set_nodes(&mut self, nodes: Vec<TreeNode<ROW>>) {
+        self.set_children(&[], nodes);
+    }
+
+    /// Get the store to use as the model of a `gtk::TreeView`.
+    pub fn store(&self) -> &gtk::TreeStore {
+        &self.store
+    }
+
+    fn parent_iter(&self, parent: &[usize]) -> Option<gtk::TreeIter> {
+        if parent.is_empty() {
+            None
+        }
+        else {
+            Some(iter_at(&self.store, parent).expect("parent row"))
+        }
+    }
+
+    fn set_children(&mut self, parent: &[usize], nodes: Vec<TreeNode<ROW>>) {
+        let (start, old_end, new_end) = {
+            let current_nodes = self.nodes.borrow();
+            let children = node(&current_nodes, parent).map(|node| &node.children).unwrap_or(&*current_nodes);
+            changed_range(children, &nodes)
+        };
+        let mut path = parent.to_vec();
+        path.push(0);
+        let mut nodes = nodes.into_iter().skip(start);
+        for index in start..old_end.min(new_end) {
+            let new_node = nodes.next().expect("node");
+            *path.last_mut().expect("index") = index;
+            self.set(&path, new_node.row);
+            self.set_children(&path, new_node.children);
+        }
+        for index in (new_end..old_end).rev() {
+            *path.last_mut().expect("index") = index;
+            let _ = self.remove(&path);
+        }
+        for (index, new_node) in (old_end..new_end).zip(nodes) {
+            self.insert(parent, index, new_node);
+        }
+    }
+}
+
+impl<ROW> Default for RowTree<ROW>
+    where ROW: Clone + PartialEq + TreeRow + 'static,
+{
+    fn default() -> Self {
+        RowTree::new()
+    }
+}
+
+// Get the path in `store` of the row at `path` in the model of `tree_view`.
+fn activated_path(tree_view: &gtk::TreeView, path: &gtk::TreePath, store: &gtk::TreeModel) -> Option<Vec<i32>> {
+    let model = tree_view.get_model()?;
+    store_path(model, path.clone(), store)
+}
+
+/*
+ * Get the range of the elements that differ between `old` and `new`, by skipping their common
+ * prefix and suffix.
+ * Return the start of the range and its end in `old` and in `new`.
+ */
+fn changed_range<T: PartialEq>(old: &[T], new: &[T]) -> (usize, usize, usize) {
+    let start = old.iter().zip(new)
+        .take_while(|&(old, new)| old == new)
+        .count();
+    let suffix = old[start..].iter().rev().zip(new[start..].iter().rev())
+        .take_while(|&(old, new)| old == new)
+        .count();
+    (start, old.len() - suffix, new.len() - suffix)
+}
+
+fn children_mut<'a, ROW>(nodes: &'a mut Vec<TreeNode<ROW>>, path: &[usize]) -> &'a mut Vec<TreeNode<ROW>> {
+    match path.split_first() {
+        Some((&index, rest)) => children_mut(&mut nodes[index].children, rest),
+        None => nodes,
+    }
+}
+
+fn columns<ROW: TreeRow>() -> Vec<u32> {
+    (0..ROW::column_types().len() as u32).collect()
+}
+
+fn insert_node<ROW: TreeRow>(store: &gtk::TreeStore, parent: Option<&gtk::TreeIter>, index: usize,
+    node: &TreeNode<ROW>)
+{
+    let values = node.row.to_values();
+    let iter = store.insert_with_values(parent, Some(index as u32), &columns::<ROW>(), &value_refs(&values));
+    for (index, child) in node.children.iter().enumerate() {
+        insert_node(store, Some(&iter), index, child);
+    }
+}
+
+fn iter_at<MODEL: TreeModelExt>(model: &MODEL, path: &[usize]) -> Option<gtk::TreeIter> {
+    let mut iter = None;
+    for &index in path {
+        iter = Some(model.iter_nth_child(iter.as_ref(), index as i32)?);
+    }
+    iter
+}
+
+fn list_row<ROW: Clone>(rows: &[ROW], path: &[i32]) -> Option<ROW> {
+    path.first().and_then(|&index| rows.get(index as usize)).cloned()
+}
+
+fn node<'a, ROW>(nodes: &'a [TreeNode<ROW>], path: &[usize]) -> Option<&'a TreeNode<ROW>> {
+    let (&index, rest) = path.split_first()?;
+    let node = nodes.get(index)?;
+    if rest.is_empty() {
+        Some(node)
+    }
+    else {
+        self::node(&node.children, rest)
+    }
+}
+
+fn selected_path(selection: &gtk::TreeSelection, store: &gtk::TreeModel) -> Option<Vec<i32>> {
+    let (model, iter) = selection.get_selected()?;
+    let path = model.get_path(&iter)?;
+    store_path(model, path, store)
+}
+
+/*
+ * Convert `path`, a path in `model`, to a path in `store` by going through the filter and sort
+ * models wrapping the store.
+ * Return `None` if the row is not in `store`.
+ */
+fn store_path(mut model: gtk::TreeModel, mut path: gtk::TreePath, store: &gtk::TreeModel) -> Option<Vec<i32>> {
+    while model != *store {
+        if let Ok(filter) = model.clone().downcast::<gtk::TreeModelFilter>() {
+            path = filter.convert_path_to_child_path(&path)?;
+            model = filter.get_model()?;
+        }
+        else if let Ok(sort) = model.clone().downcast::<gtk::TreeModelSort>() {
+            path = sort.convert_path_to_child_path(&path)?;
+            model = sort.get_model()?;
+        }
+        else {
+            return None;
+        }
+    }
+    Some(path.get_indices())
+}
+
+fn to_usize_path(path: &[i32]) -> Vec<usize> {
+    path.iter().map(|&index| index as usize).collect()
+}
+
+// NOTE: the stores expect the values as `&ToValue`.
+fn value_refs(values: &[Value]) -> Vec<&ToValue> {
+    values.iter()
+        .map(|value| value as &ToValue)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TreeNode, changed_range, list_row, node};
+
+    #[test]
+    fn changed_ranges() {
+        let empty: &[i32] = &[];
+        assert_eq!(changed_range(empty, empty), (0, 0, 0));
+        assert_eq!(changed_range(&[1, 2, 3], &[1, 2, 3]), (3, 3, 3));
+        // Insertions.
+        assert_eq!(changed_range(empty, &[1, 2]), (0, 0, 2));
+        assert_eq!(changed_range(&[1, 2], &[1, 2, 3]), (2, 2, 3));
+        assert_eq!(changed_range(&[2, 3], &[1, 2, 3]), (0, 0, 1));
+        assert_eq!(changed_range(&[1, 3], &[1, 2, 3]), (1, 1, 2));
+        // Removals.
+        assert_eq!(changed_range(&[1, 2], empty), (0, 2, 0));
+        assert_eq!(changed_range(&[1, 2, 3], &[1, 3]), (1, 2, 1));
+        assert_eq!(changed_range(&[1, 2, 3], &[3]), (0, 2, 0));
+        // Updates.
+        assert_eq!(changed_range(&[1, 2, 3], &[1, 4, 3]), (1, 2, 2));
+        assert_eq!(changed_range(&[1, 2, 3], &[4, 5]), (0, 3, 2));
+        // The prefix and the suffix do not overlap when the values repeat.
+        assert_eq!(changed_range(&[1, 1], &[1]), (1, 2, 1));
+        assert_eq!(changed_range(&[1], &[1, 1, 1]), (1, 1, 3));
+    }
+
+    #[test]
+    fn rows_at_path() {
+        assert_eq!(list_row(&["a", "b"], &[1]), Some("b"));
+        assert_eq!(list_row(&["a", "b"], &[2]), None);
+        assert_eq!(list_row(&["a", "b"], &[]), None);
+
+        let nodes = vec![
+            TreeNode::with_children("a", vec![TreeNode::new("a1"), TreeNode::new("a2")]),
+            TreeNode::new("b"),
+        ];
+        assert_eq!(node(&nodes, &[0]).map(|node| node.row), Some("a"));
+        assert_eq!(node(&nodes, &[0, 1]).map(|node| node.row), Some("a2"));
+        assert_eq!(node(&nodes, &[1]).map(|node| node.row), Some("b"));
+        assert_eq!(node(&nodes, &[1, 0]).map(|node| node.row), None);
+        assert_eq!(node(&nodes, &[2]).map(|node| node.row), None);
+        assert_eq!(node(&nodes, &[]).map(|node| node.row), None);
+    }
+}
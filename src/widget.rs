@@ -38,6 +38,13 @@ pub trait Widget
     fn init_view(&mut self) {
     }
 
+    /// Set the translated properties which depend on the model again, after the locale was
+    /// changed with [`set_locale()`](fn.set_locale.html).
+    /// This method is generated by the `#[widget]` attribute.
+    #[doc(hidden)]
+    fn on_locale_changed(&self) {
+    }
+
     /// Method called when the widget is added to its parent.
     fn on_add<W: IsA<gtk::Widget> + IsA<Object>>(&self, _parent: W) {
     }
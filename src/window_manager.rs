@@ -0,0 +1,235 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::{Rc, Weak};
+
+use futures_glib::{self, Executor};
+use glib::Cast;
+use gtk::{self, GtkApplicationExt, GtkWindowExt, IsA, WidgetExt};
+
+use super::{Component, DisplayVariant, Relm, create_executor, create_widget, init_component};
+use widget::Widget;
+
+/// Identifier of a window opened by a [`WindowManager`](struct.WindowManager.html).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct WindowId(u64);
+
+struct TrackedWindow {
+    // Whether the window belongs to a gtk::Application, which then takes care of quitting.
+    application: bool,
+    // The Component<WIDGET> of the window. It is kept here so that the window keeps receiving
+    // messages as long as it is open.
+    component: Box<Any>,
+    window: gtk::Window,
+}
+
+struct Windows {
+    application: Option<gtk::Application>,
+    close_observers: Vec<Rc<Fn(WindowId)>>,
+    executor: Executor,
+    next_id: u64,
+    quit_on_last_window: bool,
+    windows: HashMap<WindowId, TrackedWindow>,
+}
+
+/// Manager of the top-level windows of an application.
+///
+/// The manager keeps the `Component` of every window it opened until the window is closed and
+/// quits the application when the last window is closed.
+/// It can be cloned to be sent to the windows, so that they can open and message other windows:
+///
+/// ```ignore
+/// gtk::init().unwrap();
+/// let manager = WindowManager::new();
+/// let document = manager.open::<Document>(manager.clone());
+/// manager.open::<Palette>(manager.clone());
+/// manager.emit::<Document>(document, DocumentMsg::Open(path));
+/// gtk::main();
+/// ```
+#[derive(Clone)]
+pub struct WindowManager {
+    windows: Rc<RefCell<Windows>>,
+}
+
+impl WindowManager {
+    /// Create a window manager calling `gtk::main_quit()` when its last window is closed, if a main
+    /// loop is running and the window does not belong to a `gtk::Application`.
+    /// GTK+ must be initialized before calling this function.
+    pub fn new() -> Self {
+        futures_glib::init();
+        WindowManager {
+            windows: Rc::new(RefCell::new(Windows {
+                application: None,
+                close_observers: vec![],
+                executor: create_executor(),
+                next_id: 0,
+                quit_on_last_window: true,
+                windows: HashMap::new(),
+            })),
+        }
+    }
+
+    /// Create a window manager adding its windows to `application`.
+    /// The application takes care of quitting when its last window is closed.
+    pub fn with_application(application: &gtk::Application) -> Self {
+        let manager = Self::new();
+        manager.windows.borrow_mut().application = Some(application.clone());
+        manager
+    }
+
+    /// Close the window `id`.
+    /// Return `false` if the window was already closed.
+    pub fn close(&self, id: WindowId) -> bool {
+        // NOTE: clone the window to avoid borrowing the windows while the destroy handler is called.
+        let window = self.window(id);
+        if let Some(window) = window {
+            window.destroy();
+            true
+        }
+        else {
+            false
+        }
+    }
+
+    /// Close all the windows.
+    pub fn close_all(&self) {
+        for id in self.ids() {
+            let _ = self.close(id);
+        }
+    }
+
+    /// Send the message created by `callback` to `relm` every time a window is closed.
+    pub fn connect_closed<CALLBACK, WIDGET>(&self, relm: &Relm<WIDGET>, callback: CALLBACK)
+        where CALLBACK: Fn(WindowId) -> WIDGET::Msg + 'static,
+              WIDGET: Widget + 'static,
+    {
+        let stream = relm.stream().clone();
+        self.windows.borrow_mut().close_observers.push(Rc::new(move |id| stream.emit(callback(id))));
+    }
+
+    /// Send `msg` to the window `id`.
+    /// Return `false` if the window is closed or if it was not opened with the type `WIDGET`.
+    pub fn emit<WIDGET>(&self, id: WindowId, msg: WIDGET::Msg) -> bool
+        where WIDGET: Widget + 'static,
+    {
+        let windows = self.windows.borrow();
+        let component = windows.windows.get(&id)
+            .and_then(|window| window.component.downcast_ref::<Component<WIDGET>>());
+        if let Some(component) = component {
+            component.emit(msg);
+            true
+        }
+        else {
+            false
+        }
+    }
+
+    /// Get the identifiers of the open windows.
+    pub fn ids(&self) -> Vec<WindowId> {
+        self.windows.borrow().windows.keys().cloned().collect()
+    }
+
+    /// Check whether there's no open window.
+    pub fn is_empty(&self) -> bool {
+        self.windows.borrow().windows.is_empty()
+    }
+
+    /// Get the number of open windows.
+    pub fn len(&self) -> usize {
+        self.windows.borrow().windows.len()
+    }
+
+    /// Create the relm `Widget` with `model_param` as initialization value and track its window.
+    pub fn open<WIDGET>(&self, model_param: WIDGET::ModelParam) -> WindowId
+        where WIDGET: Widget + 'static,
+              WIDGET::Msg: DisplayVariant + 'static,
+              WIDGET::Root: IsA<gtk::Window>,
+    {
+        let executor = self.windows.borrow().executor.clone();
+        let (component, widget, relm) = create_widget::<WIDGET>(&executor, model_param, None);
+        init_component::<WIDGET>(component.stream(), widget, &executor, &relm);
+        let window: gtk::Window = component.widget().clone().upcast();
+
+        let mut windows = self.windows.borrow_mut();
+        let id = WindowId(windows.next_id);
+        windows.next_id += 1;
+        if let Some(ref application) = windows.application {
+            application.add_window(&window);
+        }
+
+        let weak_windows = Rc::downgrade(&self.windows);
+        let _ = window.connect_destroy(move |_| {
+            on_destroy(&weak_windows, id);
+        });
+        let _ = windows.windows.insert(id, TrackedWindow {
+            application: window.get_application().is_some(),
+            component: Box::new(component),
+            window: window.clone(),
+        });
+        id
+    }
+
+    /// Set whether `gtk::main_quit()` is called when the last window is closed.
+    /// This has no effect when the manager was created with an application or when the last window
+    /// belongs to an application.
+    pub fn set_quit_on_last_window(&self, quit: bool) {
+        self.windows.borrow_mut().quit_on_last_window = quit;
+    }
+
+    /// Get the GTK+ window of the window `id`.
+    pub fn window(&self, id: WindowId) -> Option<gtk::Window> {
+        self.windows.borrow().windows.get(&id).map(|window| window.window.clone())
+    }
+}
+
+impl Default for WindowManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn on_destroy(windows: &Weak<RefCell<Windows>>, id: WindowId) {
+    if let Some(windows) = windows.upgrade() {
+        let window = windows.borrow_mut().windows.remove(&id);
+        if let Some(window) = window {
+            let owned_by_application = window.application;
+            // Drop the component (which closes its stream) before notifying the observers.
+            drop(window);
+            let (observers, quit) = {
+                let windows = windows.borrow();
+                // NOTE: the application quits by itself when its last window is closed, and
+                // gtk::main_quit() must not be called when no main loop is running.
+                let quit = windows.windows.is_empty() && windows.quit_on_last_window &&
+                    windows.application.is_none() && !owned_by_application && gtk::main_level() > 0;
+                (windows.close_observers.clone(), quit)
+            };
+            for observer in observers {
+                observer(id);
+            }
+            if quit {
+                gtk::main_quit();
+            }
+        }
+    }
+}
@@ -0,0 +1,210 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+#![feature(proc_macro)]
+
+extern crate gtk;
+#[macro_use]
+extern crate relm;
+extern crate relm_attributes;
+#[macro_use]
+extern crate relm_derive;
+
+use gtk::{
+    BinExt,
+    Cast,
+    ContainerExt,
+    LabelExt,
+    OrientableExt,
+};
+use gtk::Orientation::Horizontal;
+use relm::{Component, ContainerComponent, ContainerWidget, Relm, Widget};
+use relm_attributes::widget;
+
+use self::Msg::*;
+
+#[widget]
+impl Widget for Item {
+    fn model(text: String) -> String {
+        text
+    }
+
+    fn update(&mut self, _msg: ()) {
+    }
+
+    view! {
+        gtk::Label {
+            text: &self.model,
+        }
+    }
+}
+
+#[widget]
+impl Widget for RightItem {
+    fn model(text: String) -> String {
+        text
+    }
+
+    fn update(&mut self, _msg: ()) {
+    }
+
+    view! {
+        #[parent="right"]
+        gtk::Label {
+            text: &self.model,
+        }
+    }
+}
+
+#[widget]
+impl Widget for SplitBox {
+    fn model() -> () {
+    }
+
+    fn update(&mut self, _msg: ()) {
+    }
+
+    view! {
+        gtk::Box {
+            orientation: Horizontal,
+            #[container]
+            gtk::Box {
+            },
+            #[container="right"]
+            gtk::Box {
+            },
+        }
+    }
+}
+
+pub struct Model {
+    items: Vec<Component<Item>>,
+    relm: Relm<Win>,
+    right_items: Vec<Component<RightItem>>,
+    split_box: Option<ContainerComponent<SplitBox>>,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Clear,
+    Insert(i32, &'static str),
+    InsertRight(i32, &'static str),
+    Move(usize, i32),
+    MoveRight(usize, i32),
+    Remove(usize),
+    RemoveRight(usize),
+}
+
+#[widget]
+impl Widget for Win {
+    fn init(&mut self, relm: &Relm<Self>) {
+        self.model.split_box = Some(self.window.add_container::<SplitBox, _>(relm, ()));
+    }
+
+    fn model(relm: &Relm<Self>, _: ()) -> Model {
+        Model {
+            items: vec![],
+            relm: relm.clone(),
+            right_items: vec![],
+            split_box: None,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        let split_box = self.model.split_box.as_ref().expect("split box");
+        match event {
+            Clear => {
+                split_box.clear();
+                self.model.items.clear();
+                self.model.right_items.clear();
+            },
+            Insert(position, text) => {
+                let item = split_box.insert_widget_at::<Item, _>(&self.model.relm, text.to_string(), position);
+                self.model.items.push(item);
+            },
+            InsertRight(position, text) => {
+                let item = split_box.insert_widget_at::<RightItem, _>(&self.model.relm, text.to_string(),
+                    position);
+                self.model.right_items.push(item);
+            },
+            Move(index, position) => split_box.move_widget(&self.model.items[index], position),
+            MoveRight(index, position) => split_box.move_widget(&self.model.right_items[index], position),
+            Remove(index) => split_box.remove_widget(self.model.items.remove(index)),
+            RemoveRight(index) => split_box.remove_widget(self.model.right_items.remove(index)),
+        }
+    }
+
+    view! {
+        #[name="window"]
+        gtk::Window {
+        }
+    }
+}
+
+fn emit(component: &Component<Win>, msg: Msg) {
+    component.stream().emit(msg);
+    while gtk::events_pending() {
+        gtk::main_iteration();
+    }
+}
+
+// Get the texts of the labels of the default and of the "right" containers.
+fn texts(component: &Component<Win>) -> (Vec<String>, Vec<String>) {
+    let split_box = component.widget().get_child().expect("split box")
+        .downcast::<gtk::Container>().expect("container");
+    let containers = split_box.get_children();
+    let texts = |container: &gtk::Widget| -> Vec<String> {
+        container.clone().downcast::<gtk::Container>().expect("container").get_children().into_iter()
+            .map(|widget| widget.downcast::<gtk::Label>().expect("label").get_text().unwrap_or_default())
+            .collect()
+    };
+    (texts(&containers[0]), texts(&containers[1]))
+}
+
+fn strings(texts: &[&str]) -> Vec<String> {
+    texts.iter()
+        .map(|text| text.to_string())
+        .collect()
+}
+
+// NOTE: GTK+ can only be used from one thread, so everything is tested in the same test.
+#[test]
+fn container_component() {
+    let component = relm::init_test::<Win>(()).expect("init_test()");
+
+    emit(&component, Insert(0, "a"));
+    emit(&component, Insert(0, "b"));
+    emit(&component, Insert(1, "c"));
+    emit(&component, InsertRight(0, "d"));
+    emit(&component, InsertRight(0, "e"));
+    assert_eq!(texts(&component), (strings(&["b", "c", "a"]), strings(&["e", "d"])));
+
+    emit(&component, Move(0, 0));
+    emit(&component, MoveRight(1, 1));
+    assert_eq!(texts(&component), (strings(&["a", "b", "c"]), strings(&["d", "e"])));
+
+    emit(&component, Remove(2));
+    emit(&component, RemoveRight(0));
+    assert_eq!(texts(&component), (strings(&["a", "b"]), strings(&["e"])));
+
+    emit(&component, Clear);
+    assert_eq!(texts(&component), (vec![], vec![]));
+}
@@ -0,0 +1,105 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+extern crate gtk;
+extern crate relm;
+
+use gtk::{
+    Bin,
+    BinExt,
+    Cast,
+    ContainerExt,
+    GridExt,
+    Label,
+    LabelExt,
+    StackExt,
+    WidgetExt,
+};
+use relm::{remove_child, reorder_child};
+
+fn label_texts(widgets: Vec<gtk::Widget>) -> Vec<String> {
+    widgets.into_iter()
+        .map(|widget| {
+            // Skip the row wrapping the label in a ListBox.
+            let widget = match widget.clone().downcast::<Bin>() {
+                Ok(bin) => bin.get_child().expect("child of row"),
+                Err(_) => widget,
+            };
+            widget.downcast::<Label>().expect("label").get_text().unwrap_or_default()
+        })
+        .collect()
+}
+
+fn labels() -> Vec<Label> {
+    vec![Label::new("a"), Label::new("b"), Label::new("c")]
+}
+
+// NOTE: GTK+ can only be used from one thread, so everything is tested in the same test.
+#[test]
+fn reorder_and_remove() {
+    gtk::init().expect("gtk::init()");
+
+    let list_box = gtk::ListBox::new();
+    let labels = labels();
+    for label in &labels {
+        list_box.add(label);
+    }
+    let container: gtk::Container = list_box.clone().upcast();
+    reorder_child(&container, &labels[2], 0);
+    assert_eq!(label_texts(list_box.get_children()), vec!["c", "a", "b"]);
+    remove_child(&container, &labels[0]);
+    assert_eq!(label_texts(list_box.get_children()), vec!["c", "b"]);
+    assert!(labels[0].get_parent().is_none());
+
+    // A Stack is reordered with the position child property.
+    let stack = gtk::Stack::new();
+    let labels = self::labels();
+    for label in &labels {
+        let name = label.get_text().unwrap_or_default();
+        stack.add_titled(label, &name, &name.to_uppercase());
+    }
+    let container: gtk::Container = stack.clone().upcast();
+    reorder_child(&container, &labels[0], 2);
+    assert_eq!(label_texts(stack.get_children()), vec!["b", "c", "a"]);
+    reorder_child(&container, &labels[0], 0);
+    assert_eq!(label_texts(stack.get_children()), vec!["a", "b", "c"]);
+    assert_eq!(stack.get_child_name(&labels[0]), Some("a".to_string()));
+    assert_eq!(stack.get_child_title(&labels[0]), Some("A".to_string()));
+    remove_child(&container, &labels[1]);
+    assert!(labels[1].get_parent().is_none());
+    assert_eq!(label_texts(stack.get_children()), vec!["a", "c"]);
+
+    // A Grid cannot position its children, so they are added back with their child properties.
+    let grid = gtk::Grid::new();
+    let labels = self::labels();
+    for (index, label) in labels.iter().enumerate() {
+        grid.attach(label, index as i32, 1, 1, 2);
+    }
+    let container: gtk::Container = grid.clone().upcast();
+    reorder_child(&container, &labels[2], 0);
+    assert_eq!(label_texts(grid.get_children()).len(), 3);
+    for (index, label) in labels.iter().enumerate() {
+        assert_eq!(label.get_parent(), Some(grid.clone().upcast()));
+        assert_eq!(grid.get_cell_left_attach(label), index as i32);
+        assert_eq!(grid.get_cell_top_attach(label), 1);
+        assert_eq!(grid.get_cell_height(label), 2);
+    }
+}
@@ -0,0 +1,88 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+#![feature(proc_macro)]
+
+extern crate gtk;
+#[macro_use]
+extern crate relm;
+extern crate relm_attributes;
+#[macro_use]
+extern crate relm_derive;
+
+use gtk::LabelExt;
+use relm::{Relm, Widget};
+use relm_attributes::widget;
+
+use self::Msg::*;
+
+pub struct Model {
+    steps: String,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Loaded,
+}
+
+#[widget]
+impl Widget for Win {
+    fn init(&mut self, relm: &Relm<Self>) {
+        // The view is updated like in update().
+        self.model.steps = format!("{}, init", self.model.steps);
+        relm.stream().emit(Loaded);
+    }
+
+    fn model() -> Model {
+        Model {
+            steps: "model".to_string(),
+        }
+    }
+
+    fn subscriptions(&mut self, _relm: &Relm<Self>) {
+        self.model.steps.push_str(", subscriptions");
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Loaded => self.model.steps = format!("{}, loaded", self.model.steps),
+        }
+    }
+
+    view! {
+        gtk::Label {
+            text: &self.model.steps,
+        }
+    }
+}
+
+#[test]
+fn init() {
+    let component = relm::init_test::<Win>(()).expect("init_test()");
+    let label = component.widget();
+    assert_eq!(label.get_text(), Some("model, subscriptions, init".to_string()));
+
+    // The messages sent from init() are dispatched after the component is created.
+    while gtk::events_pending() {
+        gtk::main_iteration();
+    }
+    assert_eq!(label.get_text(), Some("model, subscriptions, init, loaded".to_string()));
+}
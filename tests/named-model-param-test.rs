@@ -0,0 +1,85 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+#![feature(proc_macro)]
+
+extern crate gtk;
+#[macro_use]
+extern crate relm;
+extern crate relm_attributes;
+#[macro_use]
+extern crate relm_derive;
+
+use gtk::{Cast, ContainerExt, LabelExt};
+use relm::Widget;
+use relm_attributes::widget;
+
+// The fields that are not set in view! take their default value.
+#[derive(ModelParam)]
+pub struct CounterParams {
+    #[model_param(default = "Counter".to_string())]
+    label: String,
+    start: i32,
+    #[model_param(default = 1)]
+    step: i32,
+}
+
+#[widget]
+impl Widget for Counter {
+    fn model(params: CounterParams) -> String {
+        format!("{} {} {}", params.label, params.start, params.step)
+    }
+
+    fn update(&mut self, _msg: ()) {
+    }
+
+    view! {
+        gtk::Label {
+            text: &self.model,
+        }
+    }
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> () {
+    }
+
+    fn update(&mut self, _msg: ()) {
+    }
+
+    view! {
+        gtk::Box {
+            Counter(step: 2),
+            Counter(label: "Big steps".to_string(), start: 100),
+            Counter(label: "Explicit".to_string(), start: 1, step: 10),
+        }
+    }
+}
+
+#[test]
+fn named_model_params() {
+    let component = relm::init_test::<Win>(()).expect("init_test()");
+    let texts: Vec<_> = component.widget().get_children().into_iter()
+        .map(|widget| widget.downcast::<gtk::Label>().expect("label").get_text().unwrap_or_default())
+        .collect();
+    assert_eq!(texts, vec!["Counter 0 2", "Big steps 100 1", "Explicit 1 10"]);
+}
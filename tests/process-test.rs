@@ -0,0 +1,106 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+#![cfg(unix)]
+#![feature(proc_macro)]
+
+extern crate gtk;
+#[macro_use]
+extern crate relm;
+extern crate relm_attributes;
+#[macro_use]
+extern crate relm_derive;
+
+use std::io;
+use std::process::{Command, ExitStatus};
+use std::time::{Duration, Instant};
+
+use gtk::LabelExt;
+use relm::{Output, OutputMode, Process, Relm, Widget};
+use relm_attributes::widget;
+
+use self::Msg::*;
+
+pub struct Model {
+    process: Option<Process>,
+    text: String,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Exited(ExitStatus),
+    Read(Output),
+    WaitError(io::Error),
+}
+
+#[widget]
+impl Widget for Win {
+    fn init(&mut self, relm: &Relm<Self>) {
+        let mut command = Command::new("sh");
+        let _ = command.arg("-c").arg("read line; echo \"$line\"; echo error >&2; printf last; exit 3");
+        let mut process = Process::spawn(&mut command, OutputMode::Lines).expect("spawn");
+        relm.connect_exec_ignore_err(process.output(), Read);
+        relm.connect_exec(process.wait(), Exited, WaitError);
+        process.write(b"input\n");
+        self.model.process = Some(process);
+    }
+
+    fn model() -> Model {
+        Model {
+            process: None,
+            text: String::new(),
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        let line =
+            match event {
+                Exited(status) => format!("exit: {:?}", status.code()),
+                Read(Output::Stderr(data)) => format!("stderr: {}", String::from_utf8_lossy(&data)),
+                Read(Output::Stdout(data)) => format!("stdout: {}", String::from_utf8_lossy(&data)),
+                WaitError(error) => format!("wait error: {}", error),
+            };
+        let mut lines: Vec<_> = self.model.text.lines().map(str::to_string).collect();
+        lines.push(line);
+        // NOTE: the standard output, the standard error and the exit are watched separately, so
+        // their messages can arrive in any order.
+        lines.sort();
+        self.model.text = lines.join("\n");
+    }
+
+    view! {
+        gtk::Label {
+            text: &self.model.text,
+        }
+    }
+}
+
+#[test]
+fn process() {
+    let component = relm::init_test::<Win>(()).expect("init_test()");
+    let label = component.widget();
+    let expected = "exit: Some(3)\nstderr: error\nstdout: input\nstdout: last";
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while label.get_text() != Some(expected.to_string()) && Instant::now() < deadline {
+        gtk::main_iteration_do(false);
+    }
+    assert_eq!(label.get_text(), Some(expected.to_string()));
+}
@@ -0,0 +1,97 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+#![cfg(feature = "std_future")]
+#![feature(proc_macro)]
+
+extern crate futures_util;
+extern crate gtk;
+#[macro_use]
+extern crate relm;
+extern crate relm_attributes;
+#[macro_use]
+extern crate relm_derive;
+
+use std::future;
+
+use futures_util::{future as util_future, stream};
+use gtk::LabelExt;
+use relm::{Relm, Widget};
+use relm_attributes::widget;
+
+use self::Msg::*;
+
+pub struct Model {
+    messages: Vec<String>,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Executed,
+    Failed(&'static str),
+    Received(u32),
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            messages: vec![],
+        }
+    }
+
+    fn subscriptions(&mut self, relm: &Relm<Self>) {
+        relm.connect_exec(future::ready(Ok::<u32, &'static str>(1)), Received, Failed);
+        relm.connect_exec(future::ready(Err::<u32, &'static str>("error")), Received, Failed);
+        relm.connect_exec_ignore_err(stream::iter(vec![Ok::<u32, ()>(2), Ok(3)]), Received);
+        let stream = relm.stream().clone();
+        relm.exec(util_future::lazy(move |_| stream.emit(Executed)));
+    }
+
+    fn update(&mut self, event: Msg) {
+        let message =
+            match event {
+                Executed => "executed".to_string(),
+                Failed(error) => error.to_string(),
+                Received(value) => value.to_string(),
+            };
+        let mut messages = self.model.messages.clone();
+        messages.push(message);
+        // NOTE: the futures can send their messages in any order.
+        messages.sort();
+        self.model.messages = messages;
+    }
+
+    view! {
+        gtk::Label {
+            text: &self.model.messages.join(", "),
+        }
+    }
+}
+
+#[test]
+fn std_future() {
+    let component = relm::init_test::<Win>(()).expect("init_test()");
+    while gtk::events_pending() {
+        gtk::main_iteration();
+    }
+    assert_eq!(component.widget().get_text(), Some("1, 2, 3, error, executed".to_string()));
+}
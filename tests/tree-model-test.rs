@@ -0,0 +1,128 @@
+/*
+ * Copyright (c) 2017 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+extern crate gtk;
+extern crate relm;
+#[macro_use]
+extern crate relm_derive;
+
+use gtk::TreeModelExt;
+use relm::{RowList, RowTree, StaticType, TreeNode, TreeRow};
+
+// The skipped field is not a column: the count is the second column.
+#[derive(Clone, Debug, PartialEq, TreeRow)]
+struct Row {
+    name: String,
+    #[column(skip)]
+    selected: bool,
+    count: u32,
+}
+
+fn row(name: &str, count: u32) -> Row {
+    Row {
+        name: name.to_string(),
+        selected: false,
+        count,
+    }
+}
+
+fn rows(names: &[&'static str]) -> Vec<Row> {
+    names.iter()
+        .map(|&name| row(name, 0))
+        .collect()
+}
+
+// Get the rows of the store as `name:count`, with the children in parentheses.
+fn store_rows<MODEL: TreeModelExt>(model: &MODEL, parent: Option<&gtk::TreeIter>) -> Vec<String> {
+    let mut rows = vec![];
+    for index in 0..model.iter_n_children(parent) {
+        let iter = model.iter_nth_child(parent, index).expect("row");
+        let name = model.get_value(&iter, 0).get::<String>().expect("name");
+        let count = model.get_value(&iter, 1).get::<u32>().expect("count");
+        let children = store_rows(model, Some(&iter));
+        if children.is_empty() {
+            rows.push(format!("{}:{}", name, count));
+        }
+        else {
+            rows.push(format!("{}:{}({})", name, count, children.join(",")));
+        }
+    }
+    rows
+}
+
+// NOTE: GTK+ can only be used from one thread, so everything is tested in the same test.
+#[test]
+fn sync_stores() {
+    gtk::init().expect("gtk::init()");
+
+    assert_eq!(Row::column_types(), vec![String::static_type(), u32::static_type()]);
+    assert_eq!(row("a", 1).to_values().len(), 2);
+
+    let mut list = RowList::default();
+    list.set_rows(rows(&["a", "b", "c"]));
+    assert_eq!(list.store().get_n_columns(), 2);
+    assert_eq!(store_rows(list.store(), None), vec!["a:0", "b:0", "c:0"]);
+
+    // Keep the iterators of the rows which did not change.
+    let first = list.store().get_iter_first().expect("first row");
+    list.set_rows(vec![row("a", 0), row("b", 1), row("d", 0), row("c", 0)]);
+    assert_eq!(store_rows(list.store(), None), vec!["a:0", "b:1", "d:0", "c:0"]);
+    assert_eq!(list.store().get_path(&first).map(|path| path.get_indices()), Some(vec![0]));
+
+    list.set_rows(rows(&["c"]));
+    assert_eq!(store_rows(list.store(), None), vec!["c:0"]);
+    assert_eq!(*list.rows(), rows(&["c"]));
+
+    list.push(row("e", 2));
+    list.insert(0, row("f", 3));
+    assert_eq!(list.remove(1), row("c", 0));
+    list.set(1, row("e", 4));
+    assert_eq!(store_rows(list.store(), None), vec!["f:3", "e:4"]);
+    assert_eq!(list.get(1), Some(row("e", 4)));
+
+    list.set_rows(vec![]);
+    assert!(list.is_empty());
+    assert_eq!(store_rows(list.store(), None), Vec::<String>::new());
+
+    let mut tree = RowTree::default();
+    tree.set_nodes(vec![
+        TreeNode::with_children(row("a", 0), vec![TreeNode::new(row("a1", 0)), TreeNode::new(row("a2", 0))]),
+        TreeNode::new(row("b", 0)),
+    ]);
+    assert_eq!(store_rows(tree.store(), None), vec!["a:0(a1:0,a2:0)", "b:0"]);
+
+    tree.set_nodes(vec![
+        TreeNode::with_children(row("a", 1), vec![TreeNode::new(row("a2", 0))]),
+        TreeNode::with_children(row("b", 0), vec![TreeNode::new(row("b1", 0))]),
+        TreeNode::new(row("c", 0)),
+    ]);
+    assert_eq!(store_rows(tree.store(), None), vec!["a:1(a2:0)", "b:0(b1:0)", "c:0"]);
+    assert_eq!(tree.get(&[1, 0]), Some(row("b1", 0)));
+
+    tree.insert(&[0], 0, TreeNode::new(row("a0", 0)));
+    assert_eq!(tree.remove(&[2]), TreeNode::new(row("c", 0)));
+    tree.set(&[1], row("b", 2));
+    assert_eq!(store_rows(tree.store(), None), vec!["a:1(a0:0,a2:0)", "b:2(b1:0)"]);
+
+    tree.clear();
+    assert!(tree.nodes().is_empty());
+    assert_eq!(store_rows(tree.store(), None), Vec::<String>::new());
+}